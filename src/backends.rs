@@ -25,7 +25,24 @@
 //! );
 //! ```
 
-use embedded_graphics::pixelcolor::{raw::RawU16, IntoStorage, PixelColor};
+use core::{marker::PhantomData, ops::Range};
+
+use embedded_graphics::{
+    pixelcolor::{
+        raw::{RawData, RawU16, RawU24, RawU32, RawU8, ToBytes},
+        BinaryColor, PixelColor, Rgb888, RgbColor,
+    },
+    prelude::{Point, Size},
+};
+
+#[cfg(feature = "embedded-hal")]
+pub use self::spi_ram::SpiRamBuffer;
+
+use crate::{
+    color::TriColor,
+    dither::{OrderedDither, BAYER_4X4},
+    rotation::Rotation,
+};
 
 /// This trait marks the requirements for backends for a
 /// [`FrameBuf`](crate::FrameBuf).
@@ -35,48 +52,1941 @@ use embedded_graphics::pixelcolor::{raw::RawU16, IntoStorage, PixelColor};
 /// the data on the fly.
 pub trait FrameBufferBackend {
     type Color: PixelColor;
+    /// Whether the backend maps the indices of the default
+    /// [`Layout::RowMajor`](crate::layout::Layout::RowMajor) without a
+    /// [stride](crate::FrameBuf::new_with_stride) to pixels itself, e.g. to
+    /// pack them into bytes. [`FrameBuf`](crate::FrameBuf) panics if such a
+    /// backend is used with another layout or stride.
+    const ROW_MAJOR_ONLY: bool = false;
     /// Sets a pixel to the respective color
     fn set(&mut self, index: usize, color: Self::Color);
 
-    /// Returns a pixels color
-    fn get(&self, index: usize) -> Self::Color;
+    /// Returns a pixels color
+    fn get(&self, index: usize) -> Self::Color;
+
+    /// Nr of elements in the backend
+    fn nr_elements(&self) -> usize;
+
+    /// Sets all pixels to `color`.
+    ///
+    /// The default implementation sets the pixels one by one, backends should
+    /// override it with a faster bulk fill where possible.
+    fn fill(&mut self, color: Self::Color) {
+        for index in 0..self.nr_elements() {
+            self.set(index, color);
+        }
+    }
+
+    /// Sets the pixels in `range` to `color`, e.g. a row of a rectangle.
+    ///
+    /// Like [`FrameBufferBackend::fill`], this should be overridden with a
+    /// faster bulk fill where possible.
+    fn fill_range(&mut self, range: Range<usize>, color: Self::Color) {
+        for index in range {
+            self.set(index, color);
+        }
+    }
+
+    /// The pixels as a slice, if they are stored as plain colors.
+    ///
+    /// This allows bulk copies, e.g. in [`FrameBuf::blit`](crate::FrameBuf::blit).
+    fn as_contiguous(&self) -> Option<&[Self::Color]> {
+        None
+    }
+
+    /// The pixels as a mutable slice, if they are stored as plain colors.
+    fn as_contiguous_mut(&mut self) -> Option<&mut [Self::Color]> {
+        None
+    }
+}
+
+impl<'a, C: PixelColor, const N: usize> FrameBufferBackend for &'a mut [C; N] {
+    type Color = C;
+    fn set(&mut self, index: usize, color: C) {
+        self[index] = color
+    }
+
+    fn get(&self, index: usize) -> C {
+        self[index]
+    }
+
+    fn nr_elements(&self) -> usize {
+        self.len()
+    }
+
+    fn fill(&mut self, color: C) {
+        self[..].fill(color)
+    }
+
+    fn fill_range(&mut self, range: Range<usize>, color: C) {
+        self[range].fill(color)
+    }
+
+    fn as_contiguous(&self) -> Option<&[C]> {
+        Some(&self[..])
+    }
+
+    fn as_contiguous_mut(&mut self) -> Option<&mut [C]> {
+        Some(&mut self[..])
+    }
+}
+
+impl<C: PixelColor, const N: usize> FrameBufferBackend for [C; N] {
+    type Color = C;
+    fn set(&mut self, index: usize, color: C) {
+        self[index] = color
+    }
+
+    fn get(&self, index: usize) -> C {
+        self[index]
+    }
+
+    fn nr_elements(&self) -> usize {
+        self.len()
+    }
+
+    fn fill(&mut self, color: C) {
+        self[..].fill(color)
+    }
+
+    fn fill_range(&mut self, range: Range<usize>, color: C) {
+        self[range].fill(color)
+    }
+
+    fn as_contiguous(&self) -> Option<&[C]> {
+        Some(&self[..])
+    }
+
+    fn as_contiguous_mut(&mut self) -> Option<&mut [C]> {
+        Some(&mut self[..])
+    }
+}
+
+/// Backend for slices with a length only known at runtime, e.g. memory
+/// obtained from `Vec::leak` or an MMIO region.
+impl<C: PixelColor> FrameBufferBackend for &mut [C] {
+    type Color = C;
+    fn set(&mut self, index: usize, color: C) {
+        self[index] = color
+    }
+
+    fn get(&self, index: usize) -> C {
+        self[index]
+    }
+
+    fn nr_elements(&self) -> usize {
+        self.len()
+    }
+
+    fn fill(&mut self, color: C) {
+        self[..].fill(color)
+    }
+
+    fn fill_range(&mut self, range: Range<usize>, color: C) {
+        self[range].fill(color)
+    }
+
+    fn as_contiguous(&self) -> Option<&[C]> {
+        Some(&self[..])
+    }
+
+    fn as_contiguous_mut(&mut self) -> Option<&mut [C]> {
+        Some(&mut self[..])
+    }
+}
+
+/// Backends implementing this Trait can be used for DMA.
+///
+/// # Safety
+///
+/// The same restrictions as for [`embedded_dma::ReadBuffer`] apply.
+pub unsafe trait DMACapableFrameBufferBackend: FrameBufferBackend {
+    fn data_ptr(&self) -> *const Self::Color;
+}
+
+/// # Safety:
+///
+/// The implementation of the trait for all lifetimes `'a` is safe. However,
+/// this doesn't mean that the use of it is safe for all lifetimes. The
+/// requirements specified in [`embedded_dma::ReadBuffer::read_buffer`] remain.
+unsafe impl<'a, C: PixelColor, const N: usize> DMACapableFrameBufferBackend for &'a mut [C; N] {
+    fn data_ptr(&self) -> *const C {
+        self.as_ptr()
+    }
+}
+
+/// # Safety:
+///
+/// Same as for the array implementation above.
+unsafe impl<C: PixelColor> DMACapableFrameBufferBackend for &mut [C] {
+    fn data_ptr(&self) -> *const C {
+        self.as_ptr()
+    }
+}
+
+/// Backends storing the pixels as plain colors in one slice, in the order of
+/// their indices.
+///
+/// This gives access to whole rows of a framebuffer, see [`FrameBuf::rows`].
+/// Unlike [`FrameBufferBackend::as_contiguous`], the slice is guaranteed at
+/// compile time, and both must return the same slice.
+///
+/// [`FrameBuf::rows`]: crate::FrameBuf::rows
+pub trait ContiguousBackend: FrameBufferBackend {
+    /// The pixels as a slice.
+    fn as_slice(&self) -> &[Self::Color];
+
+    /// The pixels as a mutable slice.
+    fn as_mut_slice(&mut self) -> &mut [Self::Color];
+}
+
+impl<C: PixelColor, const N: usize> ContiguousBackend for &mut [C; N] {
+    fn as_slice(&self) -> &[C] {
+        &self[..]
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [C] {
+        &mut self[..]
+    }
+}
+
+impl<C: PixelColor, const N: usize> ContiguousBackend for [C; N] {
+    fn as_slice(&self) -> &[C] {
+        &self[..]
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [C] {
+        &mut self[..]
+    }
+}
+
+impl<C: PixelColor> ContiguousBackend for &mut [C] {
+    fn as_slice(&self) -> &[C] {
+        &self[..]
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [C] {
+        &mut self[..]
+    }
+}
+
+/// Backends whose accesses can fail, e.g. memory behind a serial bus.
+///
+/// The errors propagate through the [`DrawTarget::Error`] of a
+/// [`FallibleFrameBuf`]. Every [`FrameBufferBackend`] is also a fallible
+/// backend that never fails.
+///
+/// [`DrawTarget::Error`]: embedded_graphics::draw_target::DrawTarget::Error
+/// [`FallibleFrameBuf`]: crate::fallible::FallibleFrameBuf
+pub trait FallibleFrameBufferBackend {
+    type Color: PixelColor;
+    type Error;
+
+    /// Sets a pixel to the respective color
+    fn try_set(&mut self, index: usize, color: Self::Color) -> Result<(), Self::Error>;
+
+    /// Returns a pixels color
+    ///
+    /// Reading takes `&mut self`, as it usually needs a bus transfer.
+    fn try_get(&mut self, index: usize) -> Result<Self::Color, Self::Error>;
+
+    /// Nr of elements in the backend, like
+    /// [`FrameBufferBackend::nr_elements`]
+    fn capacity(&self) -> usize;
+
+    /// Sets the pixels in `range` to `color`.
+    ///
+    /// The default implementation sets the pixels one by one, backends should
+    /// override it with a faster bulk fill (e.g. one burst transfer) where
+    /// possible.
+    fn try_fill_range(
+        &mut self,
+        range: Range<usize>,
+        color: Self::Color,
+    ) -> Result<(), Self::Error> {
+        for index in range {
+            self.try_set(index, color)?;
+        }
+        Ok(())
+    }
+}
+
+impl<B: FrameBufferBackend> FallibleFrameBufferBackend for B {
+    type Color = B::Color;
+    type Error = core::convert::Infallible;
+
+    fn try_set(&mut self, index: usize, color: B::Color) -> Result<(), Self::Error> {
+        self.set(index, color);
+        Ok(())
+    }
+
+    fn try_get(&mut self, index: usize) -> Result<B::Color, Self::Error> {
+        Ok(self.get(index))
+    }
+
+    fn capacity(&self) -> usize {
+        self.nr_elements()
+    }
+
+    fn try_fill_range(&mut self, range: Range<usize>, color: B::Color) -> Result<(), Self::Error> {
+        self.fill_range(range, color);
+        Ok(())
+    }
+}
+
+/// Enum indicating how the bytes should be converted in the host's memory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EndianCorrection {
+    ToLittleEndian,
+    ToBigEndian,
+}
+
+/// Raw color data whose byte order in memory can be corrected, see
+/// [`EndianCorrectedBuffer`].
+///
+/// 24 bit data ([`RawU24`]) is stored in a `u32`. Its three bytes come first in
+/// memory on little endian hosts (and last on big endian hosts), followed by
+/// the unused byte.
+pub trait EndianCorrectable: RawData {
+    /// Convert the data to the value with the byte order `endian` in memory.
+    fn to_endian(self, endian: EndianCorrection) -> Self;
+
+    /// Convert a value with the byte order `endian` in memory back to the
+    /// data.
+    fn from_endian(value: Self, endian: EndianCorrection) -> Self;
+}
+
+impl EndianCorrectable for RawU8 {
+    fn to_endian(self, _endian: EndianCorrection) -> Self {
+        self
+    }
+
+    fn from_endian(value: Self, _endian: EndianCorrection) -> Self {
+        value
+    }
+}
+
+impl EndianCorrectable for RawU16 {
+    fn to_endian(self, endian: EndianCorrection) -> Self {
+        RawU16::new(match endian {
+            EndianCorrection::ToBigEndian => self.into_inner().to_be(),
+            EndianCorrection::ToLittleEndian => self.into_inner().to_le(),
+        })
+    }
+
+    fn from_endian(value: Self, endian: EndianCorrection) -> Self {
+        RawU16::new(match endian {
+            EndianCorrection::ToBigEndian => u16::from_be(value.into_inner()),
+            EndianCorrection::ToLittleEndian => u16::from_le(value.into_inner()),
+        })
+    }
+}
+
+impl EndianCorrectable for RawU24 {
+    fn to_endian(self, endian: EndianCorrection) -> Self {
+        let [_, a, b, c] = self.into_inner().to_be_bytes();
+        let [a, b, c] = match endian {
+            EndianCorrection::ToBigEndian => [a, b, c],
+            EndianCorrection::ToLittleEndian => [c, b, a],
+        };
+        // The unused byte must stay the most significant one to fit in 24 bits.
+        RawU24::new(match cfg!(target_endian = "little") {
+            true => u32::from_le_bytes([a, b, c, 0]),
+            false => u32::from_be_bytes([0, a, b, c]),
+        })
+    }
+
+    fn from_endian(value: Self, endian: EndianCorrection) -> Self {
+        let [a, b, c] = match cfg!(target_endian = "little") {
+            true => {
+                let [a, b, c, _] = value.into_inner().to_le_bytes();
+                [a, b, c]
+            }
+            false => {
+                let [_, a, b, c] = value.into_inner().to_be_bytes();
+                [a, b, c]
+            }
+        };
+        RawU24::new(match endian {
+            EndianCorrection::ToBigEndian => u32::from_be_bytes([0, a, b, c]),
+            EndianCorrection::ToLittleEndian => u32::from_le_bytes([a, b, c, 0]),
+        })
+    }
+}
+
+impl EndianCorrectable for RawU32 {
+    fn to_endian(self, endian: EndianCorrection) -> Self {
+        RawU32::new(match endian {
+            EndianCorrection::ToBigEndian => self.into_inner().to_be(),
+            EndianCorrection::ToLittleEndian => self.into_inner().to_le(),
+        })
+    }
+
+    fn from_endian(value: Self, endian: EndianCorrection) -> Self {
+        RawU32::new(match endian {
+            EndianCorrection::ToBigEndian => u32::from_be(value.into_inner()),
+            EndianCorrection::ToLittleEndian => u32::from_le(value.into_inner()),
+        })
+    }
+}
+
+/// A backend for [`FrameBuf`](crate::FrameBuf) which changes the underlying
+/// byte order. This can be useful when using the buffer for DMA with
+/// peripherals that have a different endianness than the host.
+///
+/// Colors with 8, 16, 24 (e.g. [`Rgb888`]
+/// for displays with 18 or 24 bit interfaces) and 32 bit raw data are
+/// supported, see [`EndianCorrectable`].
+pub struct EndianCorrectedBuffer<'a, C> {
+    data: &'a mut [C],
+    endian: EndianCorrection,
+}
+impl<'a, C> EndianCorrectedBuffer<'a, C> {
+    pub fn new(data: &'a mut [C], endian: EndianCorrection) -> Self {
+        Self { data, endian }
+    }
+}
+impl<'a, C> FrameBufferBackend for EndianCorrectedBuffer<'a, C>
+where
+    C: PixelColor + From<C::Raw>,
+    C::Raw: From<C> + EndianCorrectable,
+{
+    type Color = C;
+    fn set(&mut self, index: usize, color: C) {
+        self.data[index] = C::Raw::from(color).to_endian(self.endian).into()
+    }
+
+    fn get(&self, index: usize) -> C {
+        C::Raw::from_endian(self.data[index].into(), self.endian).into()
+    }
+
+    fn nr_elements(&self) -> usize {
+        self.data.len()
+    }
+
+    fn fill(&mut self, color: C) {
+        let len = self.data.len();
+        self.fill_range(0..len, color)
+    }
+
+    fn fill_range(&mut self, range: Range<usize>, color: C) {
+        let converted = C::Raw::from(color).to_endian(self.endian).into();
+        self.data[range].fill(converted)
+    }
+}
+unsafe impl<'a, C> DMACapableFrameBufferBackend for EndianCorrectedBuffer<'a, C>
+where
+    C: PixelColor + From<C::Raw>,
+    C::Raw: From<C> + EndianCorrectable,
+{
+    fn data_ptr(&self) -> *const C {
+        self.data.as_ptr()
+    }
+}
+
+/// A backend on top of plain bytes, converting the colors to and from their
+/// raw data on every access.
+///
+/// Use this for memory that is handed out as bytes, e.g. by DMA descriptors
+/// or other graphics libraries, instead of transmuting it to colors. Each
+/// pixel takes the bytes of its raw data (so only colors with 8, 16, 24 or 32
+/// bits per pixel are supported) in the given byte order.
+///
+/// ```rust
+/// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+/// use embedded_graphics_framebuf::{
+///     backends::{ByteBuffer, EndianCorrection},
+///     FrameBuf,
+/// };
+///
+/// let mut bytes = [0u8; 240 * 135 * 2];
+/// let backend = ByteBuffer::new(&mut bytes, EndianCorrection::ToBigEndian);
+/// let mut fbuf: FrameBuf<Rgb565, _> = FrameBuf::new(backend, 240, 135);
+/// fbuf.set_color_at(Point::new(1, 0), Rgb565::RED);
+/// assert_eq!(bytes[2..4], [0xF8, 0x00]);
+/// ```
+pub struct ByteBuffer<D, C> {
+    data: D,
+    endian: EndianCorrection,
+    color: PhantomData<C>,
+}
+
+impl<D, C> ByteBuffer<D, C>
+where
+    D: AsRef<[u8]> + AsMut<[u8]>,
+    C: PixelColor,
+{
+    const BYTES_PER_PIXEL: usize = C::Raw::BITS_PER_PIXEL / 8;
+
+    /// Create a backend on top of `data`, storing the pixels in the byte
+    /// order `endian`.
+    ///
+    /// # Panic
+    /// Panics if the raw data of `C` doesn't take whole bytes, or the length
+    /// of `data` is not a multiple of the bytes per pixel.
+    pub fn new(data: D, endian: EndianCorrection) -> Self {
+        assert!(
+            matches!(C::Raw::BITS_PER_PIXEL, 8 | 16 | 24 | 32),
+            "ByteBuffer doesn't support colors with {} bits per pixel",
+            C::Raw::BITS_PER_PIXEL
+        );
+        assert!(
+            data.as_ref().len().is_multiple_of(Self::BYTES_PER_PIXEL),
+            "ByteBuffer data size {} is not a multiple of the pixel size {}",
+            data.as_ref().len(),
+            Self::BYTES_PER_PIXEL
+        );
+        Self {
+            data,
+            endian,
+            color: PhantomData,
+        }
+    }
+
+    /// The raw bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.data.as_ref()
+    }
+
+    /// Return the underlying bytes.
+    pub fn into_inner(self) -> D {
+        self.data
+    }
+}
+
+impl<D, C> FrameBufferBackend for ByteBuffer<D, C>
+where
+    D: AsRef<[u8]> + AsMut<[u8]>,
+    C: PixelColor + From<C::Raw>,
+    C::Raw: From<C>,
+    <C::Raw as ToBytes>::Bytes: AsRef<[u8]>,
+{
+    type Color = C;
+    fn set(&mut self, index: usize, color: C) {
+        self.fill_range(index..index + 1, color)
+    }
+
+    fn get(&self, index: usize) -> C {
+        let start = index * Self::BYTES_PER_PIXEL;
+        let bytes = &self.data.as_ref()[start..start + Self::BYTES_PER_PIXEL];
+        let value = match self.endian {
+            EndianCorrection::ToBigEndian => bytes.iter().fold(0, |v, &b| (v << 8) | b as u32),
+            EndianCorrection::ToLittleEndian => {
+                bytes.iter().rev().fold(0, |v, &b| (v << 8) | b as u32)
+            }
+        };
+        C::Raw::from_u32(value).into()
+    }
+
+    fn nr_elements(&self) -> usize {
+        self.data.as_ref().len() / Self::BYTES_PER_PIXEL
+    }
+
+    fn fill(&mut self, color: C) {
+        let len = self.nr_elements();
+        self.fill_range(0..len, color)
+    }
+
+    fn fill_range(&mut self, range: Range<usize>, color: C) {
+        let raw = C::Raw::from(color);
+        let bytes = match self.endian {
+            EndianCorrection::ToBigEndian => raw.to_be_bytes(),
+            EndianCorrection::ToLittleEndian => raw.to_le_bytes(),
+        };
+        let range = range.start * Self::BYTES_PER_PIXEL..range.end * Self::BYTES_PER_PIXEL;
+        for pixel in self.data.as_mut()[range].chunks_exact_mut(Self::BYTES_PER_PIXEL) {
+            pixel.copy_from_slice(bytes.as_ref());
+        }
+    }
+}
+
+/// A backend wrapper skipping writes of a key color.
+///
+/// Drawing a sprite with a "magic pink" background into a framebuffer on top
+/// of this wrapper leaves the pixels under the background untouched.
+///
+/// ```rust
+/// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+/// use embedded_graphics_framebuf::{backends::TransparentBuffer, FrameBuf};
+///
+/// let mut data = [Rgb565::BLUE; 4 * 4];
+/// let mut fbuf = FrameBuf::new(TransparentBuffer::new(&mut data, Rgb565::MAGENTA), 4, 4);
+/// fbuf.draw_iter([
+///     Pixel(Point::new(0, 0), Rgb565::MAGENTA),
+///     Pixel(Point::new(1, 0), Rgb565::RED),
+/// ])
+/// .unwrap();
+/// assert_eq!(data[..2], [Rgb565::BLUE, Rgb565::RED]);
+/// ```
+pub struct TransparentBuffer<B, C> {
+    inner: B,
+    key: C,
+}
+
+impl<B: FrameBufferBackend<Color = C>, C: PixelColor> TransparentBuffer<B, C> {
+    pub fn new(inner: B, key: C) -> Self {
+        Self { inner, key }
+    }
+
+    /// The color that is not written.
+    pub fn key(&self) -> C {
+        self.key
+    }
+
+    /// Return the wrapped backend.
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+}
+
+impl<B: FrameBufferBackend<Color = C>, C: PixelColor> FrameBufferBackend
+    for TransparentBuffer<B, C>
+{
+    type Color = C;
+    fn set(&mut self, index: usize, color: C) {
+        if color != self.key {
+            self.inner.set(index, color)
+        }
+    }
+
+    fn get(&self, index: usize) -> C {
+        self.inner.get(index)
+    }
+
+    fn nr_elements(&self) -> usize {
+        self.inner.nr_elements()
+    }
+
+    fn fill(&mut self, color: C) {
+        if color != self.key {
+            self.inner.fill(color)
+        }
+    }
+
+    fn fill_range(&mut self, range: Range<usize>, color: C) {
+        if color != self.key {
+            self.inner.fill_range(range, color)
+        }
+    }
+}
+
+unsafe impl<B: DMACapableFrameBufferBackend<Color = C>, C: PixelColor> DMACapableFrameBufferBackend
+    for TransparentBuffer<B, C>
+{
+    fn data_ptr(&self) -> *const C {
+        self.inner.data_ptr()
+    }
+}
+
+/// A lookup table mapping 8 bit color channels to gamma corrected ones.
+///
+/// The table can be generated from a gamma exponent in a `const`, so it is
+/// computed at build time and ends up in flash:
+///
+/// ```rust
+/// use embedded_graphics_framebuf::backends::GammaLut;
+///
+/// const GAMMA: GammaLut = GammaLut::from_gamma(2.2);
+/// assert_eq!(GAMMA.apply(128), 56);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GammaLut([u8; 256]);
+
+impl GammaLut {
+    /// Use a custom table, e.g. measured for a panel.
+    pub const fn new(table: [u8; 256]) -> Self {
+        Self(table)
+    }
+
+    /// Generate the table for `channel^gamma`, with channels normalized to
+    /// `0.0..=1.0`.
+    pub const fn from_gamma(gamma: f32) -> Self {
+        let mut table = [0; 256];
+        let mut i = 1;
+        while i < 256 {
+            let x = i as f64 / 255.0;
+            table[i] = (exp(gamma as f64 * ln(x)) * 255.0 + 0.5) as u8;
+            i += 1;
+        }
+        Self(table)
+    }
+
+    /// The table.
+    pub fn table(&self) -> &[u8; 256] {
+        &self.0
+    }
+
+    /// Correct one channel.
+    pub fn apply(&self, channel: u8) -> u8 {
+        self.0[channel as usize]
+    }
+
+    fn correct<C: From<Rgb888> + Into<Rgb888>>(&self, color: C) -> C {
+        let color = color.into();
+        Rgb888::new(
+            self.apply(color.r()),
+            self.apply(color.g()),
+            self.apply(color.b()),
+        )
+        .into()
+    }
+}
+
+/// Natural logarithm of `x` in `0.0..=1.0` for `const` contexts, where
+/// `f64::ln` isn't available.
+const fn ln(mut x: f64) -> f64 {
+    let mut halvings = 0;
+    while x < 0.5 {
+        x *= 2.0;
+        halvings += 1;
+    }
+    // ln(x) = 2 * atanh((x - 1) / (x + 1)), converging quickly for x >= 0.5
+    let t = (x - 1.0) / (x + 1.0);
+    let mut power = t;
+    let mut sum = 0.0;
+    let mut n = 1;
+    while n < 40 {
+        sum += power / n as f64;
+        power *= t * t;
+        n += 2;
+    }
+    2.0 * sum - halvings as f64 * core::f64::consts::LN_2
+}
+
+/// `e^x` for `const` contexts, where `f64::exp` isn't available.
+const fn exp(mut x: f64) -> f64 {
+    let mut squarings = 0;
+    while x < -0.5 || x > 0.5 {
+        x /= 2.0;
+        squarings += 1;
+    }
+    let mut term = 1.0;
+    let mut sum = 1.0;
+    let mut n = 1;
+    while n < 20 {
+        term *= x / n as f64;
+        sum += term;
+        n += 1;
+    }
+    while squarings > 0 {
+        sum *= sum;
+        squarings -= 1;
+    }
+    sum
+}
+
+/// A backend wrapper applying a [`GammaLut`] to every color channel written.
+///
+/// LEDs and OLEDs have a roughly linear brightness response, while the eye
+/// perceives brightness logarithmically, so linear color ramps look washed
+/// out. Correcting the colors when storing them gives perceptually linear
+/// brightness without touching the drawing code. The correction happens in
+/// [`Rgb888`] space, so it also works for colors with fewer bits per channel.
+///
+/// Reading pixels returns the stored, i.e. corrected, colors.
+///
+/// ```rust
+/// use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+/// use embedded_graphics_framebuf::{
+///     backends::{GammaCorrected, GammaLut},
+///     FrameBuf,
+/// };
+///
+/// const GAMMA: GammaLut = GammaLut::from_gamma(2.2);
+///
+/// let mut data = [Rgb888::BLACK; 16 * 16];
+/// let mut fbuf = FrameBuf::new(GammaCorrected::new(&mut data, GAMMA), 16, 16);
+/// fbuf.set_color_at(Point::new(0, 0), Rgb888::new(128, 255, 0));
+/// assert_eq!(data[0], Rgb888::new(56, 255, 0));
+/// ```
+pub struct GammaCorrected<B> {
+    inner: B,
+    lut: GammaLut,
+}
+
+impl<B: FrameBufferBackend> GammaCorrected<B> {
+    pub fn new(inner: B, lut: GammaLut) -> Self {
+        Self { inner, lut }
+    }
+
+    /// The lookup table.
+    pub fn lut(&self) -> &GammaLut {
+        &self.lut
+    }
+
+    /// Return the wrapped backend.
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+}
+
+impl<B> FrameBufferBackend for GammaCorrected<B>
+where
+    B: FrameBufferBackend,
+    B::Color: From<Rgb888> + Into<Rgb888>,
+{
+    type Color = B::Color;
+    fn set(&mut self, index: usize, color: B::Color) {
+        self.inner.set(index, self.lut.correct(color))
+    }
+
+    fn get(&self, index: usize) -> B::Color {
+        self.inner.get(index)
+    }
+
+    fn nr_elements(&self) -> usize {
+        self.inner.nr_elements()
+    }
+
+    fn fill(&mut self, color: B::Color) {
+        self.inner.fill(self.lut.correct(color))
+    }
+
+    fn fill_range(&mut self, range: Range<usize>, color: B::Color) {
+        self.inner.fill_range(range, self.lut.correct(color))
+    }
+}
+
+unsafe impl<B> DMACapableFrameBufferBackend for GammaCorrected<B>
+where
+    B: DMACapableFrameBufferBackend,
+    B::Color: From<Rgb888> + Into<Rgb888>,
+{
+    fn data_ptr(&self) -> *const B::Color {
+        self.inner.data_ptr()
+    }
+}
+
+/// A backend wrapper storing colors with fewer levels than they are drawn
+/// with, using ordered (Bayer) dithering.
+///
+/// Plain conversion of e.g. [`Rgb888`] gradients to a 16 bit panel shows
+/// bands where the channels step to the next level. This backend spreads the
+/// quantization error over a 4x4 pattern instead, so the average color of an
+/// area matches the drawn one. Supported stored colors implement
+/// [`OrderedDither`], e.g.
+/// [`Rgb565`](embedded_graphics::pixelcolor::Rgb565) and
+/// [`Gray4`](embedded_graphics::pixelcolor::Gray4).
+///
+/// Reading pixels returns the stored colors converted to `C`.
+///
+/// ```rust
+/// use embedded_graphics::{pixelcolor::{Rgb565, Rgb888}, prelude::*};
+/// use embedded_graphics_framebuf::{backends::DitheredBuffer, FrameBuf};
+///
+/// let mut data = [Rgb565::BLACK; 320 * 240];
+/// let mut fbuf: FrameBuf<Rgb888, _> = FrameBuf::new(DitheredBuffer::new(&mut data, 320), 320, 240);
+/// fbuf.clear(Rgb888::new(136, 0, 0)).unwrap();
+/// // 136 is halfway between the Rgb565 red levels 16 and 17
+/// assert_ne!(data[0], data[1]);
+/// ```
+pub struct DitheredBuffer<B, C> {
+    inner: B,
+    width: usize,
+    color: PhantomData<C>,
+}
+
+impl<B, C> DitheredBuffer<B, C>
+where
+    B: FrameBufferBackend,
+    B::Color: OrderedDither,
+{
+    /// Create a backend for a framebuffer `width` pixels wide.
+    ///
+    /// The backend expects the indices of the default
+    /// [`Layout::RowMajor`](crate::layout::Layout::RowMajor).
+    ///
+    /// # Panic
+    /// Panics if the size of `inner` is not a multiple of `width`.
+    pub fn new(inner: B, width: usize) -> Self {
+        assert!(
+            width > 0 && inner.nr_elements().is_multiple_of(width),
+            "DitheredBuffer backend size {} is not a multiple of the width {}",
+            inner.nr_elements(),
+            width
+        );
+        Self {
+            inner,
+            width,
+            color: PhantomData,
+        }
+    }
+
+    /// Return the wrapped backend.
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+}
+
+impl<B, C> FrameBufferBackend for DitheredBuffer<B, C>
+where
+    B: FrameBufferBackend,
+    B::Color: OrderedDither,
+    C: PixelColor + Into<Rgb888> + From<Rgb888>,
+{
+    type Color = C;
+    const ROW_MAJOR_ONLY: bool = true;
+    fn set(&mut self, index: usize, color: C) {
+        let threshold = BAYER_4X4[(index / self.width) % 4][(index % self.width) % 4];
+        self.inner
+            .set(index, OrderedDither::dither(color.into(), threshold))
+    }
+
+    fn get(&self, index: usize) -> C {
+        let color: Rgb888 = self.inner.get(index).into();
+        color.into()
+    }
+
+    fn nr_elements(&self) -> usize {
+        self.inner.nr_elements()
+    }
+}
+
+/// A backend wrapper storing the pixels rotated.
+///
+/// The framebuffer on top of it uses logical coordinates, while the memory
+/// of the wrapped backend stays in the native scan order of the panel, so it
+/// can be sent (e.g. by DMA) as is. Unlike
+/// [`FrameBuf::rotated`](crate::FrameBuf::rotated), the rotation is a
+/// property of the storage, so everything using the framebuffer (flush
+/// helpers, blits, ...) sees the logical orientation.
+///
+/// To change the orientation at runtime, take the backend out of the
+/// framebuffer, rotate it and put it into a framebuffer of the new
+/// [`RotatedBuffer::logical_size`]. The memory is reused, but its content is
+/// not rotated along.
+///
+/// ```rust
+/// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+/// use embedded_graphics_framebuf::{backends::RotatedBuffer, rotation::Rotation, FrameBuf};
+///
+/// // A 240x320 portrait panel
+/// let mut data = [Rgb565::BLACK; 240 * 320];
+/// let backend = RotatedBuffer::new(&mut data, Size::new(240, 320), Rotation::Deg0);
+/// let mut fbuf = FrameBuf::new(backend, 240, 320);
+///
+/// // Turned into landscape
+/// let mut backend = fbuf.into_inner();
+/// backend.set_rotation(Rotation::Deg90);
+/// let Size { width, height } = backend.logical_size();
+/// let mut fbuf = FrameBuf::new(backend, width as usize, height as usize);
+/// fbuf.set_color_at(Point::new(319, 0), Rgb565::RED);
+/// assert_eq!(data[240 * 320 - 1], Rgb565::RED);
+/// ```
+pub struct RotatedBuffer<B> {
+    inner: B,
+    native_size: Size,
+    rotation: Rotation,
+}
+
+impl<B: FrameBufferBackend> RotatedBuffer<B> {
+    /// Wrap the backend of a panel with the size `native_size` in its native
+    /// orientation.
+    ///
+    /// # Panic
+    /// Panics if the size of `inner` does not match `native_size`.
+    pub fn new(inner: B, native_size: Size, rotation: Rotation) -> Self {
+        assert_eq!(
+            inner.nr_elements(),
+            (native_size.width * native_size.height) as usize,
+            "RotatedBuffer backend size does not match the native size {:?}",
+            native_size
+        );
+        Self {
+            inner,
+            native_size,
+            rotation,
+        }
+    }
+
+    /// The current rotation.
+    pub fn rotation(&self) -> Rotation {
+        self.rotation
+    }
+
+    /// Change the rotation, see the type documentation.
+    pub fn set_rotation(&mut self, rotation: Rotation) {
+        self.rotation = rotation;
+    }
+
+    /// The size of a framebuffer on top of this backend.
+    pub fn logical_size(&self) -> Size {
+        match self.rotation.is_transposed() {
+            true => Size::new(self.native_size.height, self.native_size.width),
+            false => self.native_size,
+        }
+    }
+
+    /// Return the wrapped backend.
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    fn native_index(&self, index: usize) -> usize {
+        let width = self.logical_size().width as usize;
+        let p = Point::new((index % width) as i32, (index / width) as i32);
+        let p = self.rotation.to_fbuf(p, self.native_size);
+        p.y as usize * self.native_size.width as usize + p.x as usize
+    }
+}
+
+impl<B: FrameBufferBackend> FrameBufferBackend for RotatedBuffer<B> {
+    type Color = B::Color;
+    fn set(&mut self, index: usize, color: B::Color) {
+        assert!(index < self.nr_elements(), "index {} out of bounds", index);
+        let index = self.native_index(index);
+        self.inner.set(index, color)
+    }
+
+    fn get(&self, index: usize) -> B::Color {
+        assert!(index < self.nr_elements(), "index {} out of bounds", index);
+        self.inner.get(self.native_index(index))
+    }
+
+    fn nr_elements(&self) -> usize {
+        self.inner.nr_elements()
+    }
+
+    fn fill(&mut self, color: B::Color) {
+        self.inner.fill(color)
+    }
+}
+
+unsafe impl<B: DMACapableFrameBufferBackend> DMACapableFrameBufferBackend for RotatedBuffer<B> {
+    fn data_ptr(&self) -> *const B::Color {
+        self.inner.data_ptr()
+    }
+}
+
+/// A backend wrapper storing the pixels column by column.
+///
+/// Panels which are scanned column first (e.g. a Sharp Memory LCD mounted in
+/// portrait) expect the frame in that order. The framebuffer on top of this
+/// backend still draws in row major coordinates, while the memory of the
+/// wrapped backend can be streamed to the panel linearly. Unlike
+/// [`Layout::ColumnMajor`](crate::layout::Layout::ColumnMajor), the order is
+/// a property of the storage, so it stays with the backend when it is taken
+/// out of or swapped into a framebuffer.
+///
+/// ```rust
+/// use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+/// use embedded_graphics_framebuf::{backends::TransposedBuffer, FrameBuf};
+///
+/// let mut data = [BinaryColor::Off; 4 * 3];
+/// let mut fbuf = FrameBuf::new(TransposedBuffer::new(&mut data, 4), 4, 3);
+/// fbuf.set_color_at(Point::new(1, 0), BinaryColor::On);
+/// // The second column starts after the 3 pixels of the first one
+/// assert_eq!(data[3], BinaryColor::On);
+/// ```
+pub struct TransposedBuffer<B> {
+    inner: B,
+    width: usize,
+}
+
+impl<B: FrameBufferBackend> TransposedBuffer<B> {
+    /// Create a backend for a framebuffer `width` pixels wide.
+    ///
+    /// # Panic
+    /// Panics if the size of `inner` is not a multiple of `width`.
+    pub fn new(inner: B, width: usize) -> Self {
+        assert!(
+            width > 0 && inner.nr_elements().is_multiple_of(width),
+            "TransposedBuffer backend size {} is not a multiple of the width {}",
+            inner.nr_elements(),
+            width
+        );
+        Self { inner, width }
+    }
+
+    /// Return the wrapped backend.
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    fn column_major_index(&self, index: usize) -> usize {
+        let height = self.inner.nr_elements() / self.width;
+        (index % self.width) * height + index / self.width
+    }
+}
+
+impl<B: FrameBufferBackend> FrameBufferBackend for TransposedBuffer<B> {
+    type Color = B::Color;
+    const ROW_MAJOR_ONLY: bool = true;
+    fn set(&mut self, index: usize, color: B::Color) {
+        assert!(index < self.nr_elements(), "index {} out of bounds", index);
+        let index = self.column_major_index(index);
+        self.inner.set(index, color)
+    }
+
+    fn get(&self, index: usize) -> B::Color {
+        assert!(index < self.nr_elements(), "index {} out of bounds", index);
+        self.inner.get(self.column_major_index(index))
+    }
+
+    fn nr_elements(&self) -> usize {
+        self.inner.nr_elements()
+    }
+
+    fn fill(&mut self, color: B::Color) {
+        self.inner.fill(color)
+    }
+}
+
+unsafe impl<B: DMACapableFrameBufferBackend> DMACapableFrameBufferBackend for TransposedBuffer<B> {
+    fn data_ptr(&self) -> *const B::Color {
+        self.inner.data_ptr()
+    }
+}
+
+/// A monochrome backend in the page format of SSD1306/SH1106 style
+/// controllers: each byte holds 8 vertically stacked pixels, the least
+/// significant bit being the top one.
+///
+/// The backend works on borrowed (`&mut [u8]`) or owned (`[u8; N]`) bytes, so
+/// it can be put on top of a buffer that a display driver already owns.
+/// Alternatively, the bytes of our buffer can be lent to the driver via
+/// [`PageBuffer::as_bytes`]. Either way only one copy of the frame is kept in
+/// RAM, and no repacking is needed before sending it.
+///
+/// The backend expects the indices of the default
+/// [`Layout::RowMajor`](crate::layout::Layout::RowMajor).
+///
+/// ```rust
+/// use embedded_graphics::pixelcolor::BinaryColor;
+/// use embedded_graphics_framebuf::{backends::PageBuffer, FrameBuf};
+///
+/// let mut bytes = [0u8; 128 * 64 / 8];
+/// let mut fbuf = FrameBuf::new(PageBuffer::new(&mut bytes, 128), 128, 64);
+/// fbuf.set_color_at(embedded_graphics::prelude::Point::new(0, 9), BinaryColor::On);
+/// // display.draw(fbuf.data.as_bytes()).unwrap();
+/// assert_eq!(fbuf.data.as_bytes()[128], 0b10);
+/// ```
+pub struct PageBuffer<D> {
+    data: D,
+    width: usize,
+}
+
+impl<D: AsRef<[u8]> + AsMut<[u8]>> PageBuffer<D> {
+    /// Create a backend for a display `width` pixels wide.
+    ///
+    /// # Panic
+    /// Panics if the length of `data` is not a multiple of `width`.
+    pub fn new(data: D, width: usize) -> Self {
+        assert!(
+            width > 0 && data.as_ref().len().is_multiple_of(width),
+            "PageBuffer data size {} is not a multiple of the width {}",
+            data.as_ref().len(),
+            width
+        );
+        Self { data, width }
+    }
+
+    /// The raw bytes, ready to be sent to the controller.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.data.as_ref()
+    }
+
+    /// The width of a row in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Return the underlying bytes.
+    pub fn into_inner(self) -> D {
+        self.data
+    }
+
+    fn position(&self, index: usize) -> (usize, u8) {
+        let x = index % self.width;
+        let y = index / self.width;
+        ((y / 8) * self.width + x, 1 << (y % 8))
+    }
+}
+
+impl<D: AsRef<[u8]> + AsMut<[u8]>> FrameBufferBackend for PageBuffer<D> {
+    type Color = BinaryColor;
+    const ROW_MAJOR_ONLY: bool = true;
+    fn set(&mut self, index: usize, color: BinaryColor) {
+        let (byte, mask) = self.position(index);
+        match color {
+            BinaryColor::On => self.data.as_mut()[byte] |= mask,
+            BinaryColor::Off => self.data.as_mut()[byte] &= !mask,
+        }
+    }
+
+    fn get(&self, index: usize) -> BinaryColor {
+        let (byte, mask) = self.position(index);
+        (self.data.as_ref()[byte] & mask != 0).into()
+    }
+
+    fn nr_elements(&self) -> usize {
+        self.data.as_ref().len() * 8
+    }
+
+    fn fill(&mut self, color: BinaryColor) {
+        self.data.as_mut().fill(match color {
+            BinaryColor::On => 0xFF,
+            BinaryColor::Off => 0x00,
+        })
+    }
+}
+
+/// Order of the pixels within a byte of a [`PackedBinaryBuffer`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitOrder {
+    /// The most significant bit is the leftmost pixel (e.g. Sharp memory
+    /// displays, most e-paper controllers).
+    MsbFirst,
+    /// The least significant bit is the leftmost pixel.
+    LsbFirst,
+}
+
+/// A monochrome backend storing 8 horizontally adjacent pixels per byte.
+///
+/// Each row starts at a new byte, so for widths that aren't a multiple of 8
+/// the last byte of every row has unused bits. A 128x64 framebuffer takes
+/// 1 KiB instead of the 8 KiB of an array of [`BinaryColor`].
+///
+/// ```rust
+/// use embedded_graphics::{pixelcolor::BinaryColor, prelude::Point};
+/// use embedded_graphics_framebuf::{
+///     backends::{BitOrder, PackedBinaryBuffer},
+///     FrameBuf,
+/// };
+///
+/// let mut bytes = [0u8; 128 * 64 / 8];
+/// let mut fbuf = FrameBuf::new(
+///     PackedBinaryBuffer::new(&mut bytes, 128, BitOrder::MsbFirst),
+///     128,
+///     64,
+/// );
+/// fbuf.set_color_at(Point::new(9, 1), BinaryColor::On);
+/// assert_eq!(fbuf.data.as_bytes()[16 + 1], 0b0100_0000);
+/// ```
+pub struct PackedBinaryBuffer<D> {
+    data: D,
+    width: usize,
+    bit_order: BitOrder,
+}
+
+impl<D: AsRef<[u8]> + AsMut<[u8]>> PackedBinaryBuffer<D> {
+    /// Create a backend for a display `width` pixels wide.
+    ///
+    /// # Panic
+    /// Panics if the length of `data` is not a multiple of the bytes per row.
+    pub fn new(data: D, width: usize, bit_order: BitOrder) -> Self {
+        assert!(
+            width > 0 && data.as_ref().len().is_multiple_of(width.div_ceil(8)),
+            "PackedBinaryBuffer data size {} is not a multiple of the row size {}",
+            data.as_ref().len(),
+            width.div_ceil(8)
+        );
+        Self {
+            data,
+            width,
+            bit_order,
+        }
+    }
+
+    /// The raw bytes, ready to be sent to the controller.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.data.as_ref()
+    }
+
+    /// The width of a row in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The order of the pixels within a byte.
+    pub fn bit_order(&self) -> BitOrder {
+        self.bit_order
+    }
+
+    /// Return the underlying bytes.
+    pub fn into_inner(self) -> D {
+        self.data
+    }
+
+    fn position(&self, index: usize) -> (usize, u8) {
+        let x = index % self.width;
+        let y = index / self.width;
+        let mask = match self.bit_order {
+            BitOrder::MsbFirst => 0x80 >> (x % 8),
+            BitOrder::LsbFirst => 1 << (x % 8),
+        };
+        (y * self.width.div_ceil(8) + x / 8, mask)
+    }
+}
+
+impl<D: AsRef<[u8]> + AsMut<[u8]>> FrameBufferBackend for PackedBinaryBuffer<D> {
+    type Color = BinaryColor;
+    const ROW_MAJOR_ONLY: bool = true;
+    fn set(&mut self, index: usize, color: BinaryColor) {
+        let (byte, mask) = self.position(index);
+        match color {
+            BinaryColor::On => self.data.as_mut()[byte] |= mask,
+            BinaryColor::Off => self.data.as_mut()[byte] &= !mask,
+        }
+    }
+
+    fn get(&self, index: usize) -> BinaryColor {
+        let (byte, mask) = self.position(index);
+        (self.data.as_ref()[byte] & mask != 0).into()
+    }
+
+    fn nr_elements(&self) -> usize {
+        self.data.as_ref().len() / self.width.div_ceil(8) * self.width
+    }
+
+    fn fill(&mut self, color: BinaryColor) {
+        self.data.as_mut().fill(match color {
+            BinaryColor::On => 0xFF,
+            BinaryColor::Off => 0x00,
+        })
+    }
+}
+
+/// A backend for tri-color e-paper controllers (e.g. SSD1680), storing a
+/// black and white plane and a plane for the third color.
+///
+/// Both planes are packed like a [`PackedBinaryBuffer`] with
+/// [`BitOrder::MsbFirst`]. In the black and white plane a set bit is white,
+/// in the other plane a set bit is red (or yellow). The planes are sent to
+/// the two RAMs of the controller as they are, see [`TriColorBuffer::planes`].
+///
+/// ```rust
+/// use embedded_graphics::prelude::*;
+/// use embedded_graphics_framebuf::{backends::TriColorBuffer, color::TriColor, FrameBuf};
+///
+/// let (mut black_white, mut red) = ([0u8; 200 * 200 / 8], [0u8; 200 * 200 / 8]);
+/// let mut fbuf = FrameBuf::new(TriColorBuffer::new(&mut black_white, &mut red, 200), 200, 200);
+/// fbuf.clear(TriColor::White).unwrap();
+/// fbuf.set_color_at(Point::new(0, 0), TriColor::Black);
+/// fbuf.set_color_at(Point::new(1, 0), TriColor::Red);
+/// let (black_white, red) = fbuf.data.planes();
+/// assert_eq!(black_white[0], 0b0111_1111);
+/// assert_eq!(red[0], 0b0100_0000);
+/// ```
+pub struct TriColorBuffer<D> {
+    black_white: PackedBinaryBuffer<D>,
+    red: PackedBinaryBuffer<D>,
+}
+
+impl<D: AsRef<[u8]> + AsMut<[u8]>> TriColorBuffer<D> {
+    /// Create a backend for a display `width` pixels wide from the bytes of
+    /// the two planes.
+    ///
+    /// # Panic
+    /// Panics if the planes differ in size, or their size is not a multiple
+    /// of the bytes per row.
+    pub fn new(black_white: D, red: D, width: usize) -> Self {
+        assert_eq!(
+            black_white.as_ref().len(),
+            red.as_ref().len(),
+            "TriColorBuffer planes differ in size"
+        );
+        Self {
+            black_white: PackedBinaryBuffer::new(black_white, width, BitOrder::MsbFirst),
+            red: PackedBinaryBuffer::new(red, width, BitOrder::MsbFirst),
+        }
+    }
+
+    /// The bytes of the black and white plane and of the red plane.
+    pub fn planes(&self) -> (&[u8], &[u8]) {
+        (self.black_white.as_bytes(), self.red.as_bytes())
+    }
+
+    /// Return the bytes of the black and white plane and of the red plane.
+    pub fn into_inner(self) -> (D, D) {
+        (self.black_white.into_inner(), self.red.into_inner())
+    }
+}
+
+impl<D: AsRef<[u8]> + AsMut<[u8]>> FrameBufferBackend for TriColorBuffer<D> {
+    type Color = TriColor;
+    fn set(&mut self, index: usize, color: TriColor) {
+        let (black_white, red) = match color {
+            TriColor::White => (BinaryColor::On, BinaryColor::Off),
+            TriColor::Black => (BinaryColor::Off, BinaryColor::Off),
+            TriColor::Red => (BinaryColor::On, BinaryColor::On),
+        };
+        self.black_white.set(index, black_white);
+        self.red.set(index, red);
+    }
+
+    fn get(&self, index: usize) -> TriColor {
+        match (self.black_white.get(index), self.red.get(index)) {
+            (_, BinaryColor::On) => TriColor::Red,
+            (BinaryColor::On, BinaryColor::Off) => TriColor::White,
+            (BinaryColor::Off, BinaryColor::Off) => TriColor::Black,
+        }
+    }
+
+    fn nr_elements(&self) -> usize {
+        self.black_white.nr_elements()
+    }
+
+    fn fill(&mut self, color: TriColor) {
+        self.black_white.fill((color != TriColor::Black).into());
+        self.red.fill((color == TriColor::Red).into());
+    }
+}
+
+/// A backend storing 8 bit indices into a palette of up to 256 colors.
+///
+/// Takes a byte per pixel, i.e. half of the RAM of an
+/// [`Rgb565`](embedded_graphics::pixelcolor::Rgb565) array. Colors that are
+/// not in the palette are stored as the closest palette entry.
+///
+/// The palette can be changed at runtime (e.g. for palette cycling), which
+/// changes the color of all pixels with the respective index. Remember to
+/// [mark](crate::FrameBuf::mark_all_dirty) the framebuffer dirty afterwards.
+///
+/// ```rust
+/// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+/// use embedded_graphics_framebuf::{backends::IndexedBuffer, FrameBuf};
+///
+/// let palette = [Rgb565::BLACK, Rgb565::WHITE, Rgb565::RED, Rgb565::BLUE];
+/// let mut indices = [0u8; 240 * 135];
+/// let mut fbuf = FrameBuf::new(IndexedBuffer::new(&mut indices, palette), 240, 135);
+/// fbuf.set_color_at(Point::new(10, 10), Rgb565::RED);
+/// fbuf.data.palette_mut()[2] = Rgb565::GREEN;
+/// assert_eq!(fbuf.get_color_at(Point::new(10, 10)), Rgb565::GREEN);
+/// ```
+pub struct IndexedBuffer<'a, C, const N: usize> {
+    indices: &'a mut [u8],
+    palette: [C; N],
+}
+
+impl<'a, C: RgbColor, const N: usize> IndexedBuffer<'a, C, N> {
+    /// Create a backend on top of `indices`, all of which must be valid
+    /// indices into `palette`.
+    ///
+    /// # Panic
+    /// Panics if the palette is empty or has more than 256 colors.
+    pub fn new(indices: &'a mut [u8], palette: [C; N]) -> Self {
+        assert!(
+            N > 0 && N <= 256,
+            "IndexedBuffer palette size {} is not in 1..=256",
+            N
+        );
+        Self { indices, palette }
+    }
+
+    /// The palette.
+    pub fn palette(&self) -> &[C; N] {
+        &self.palette
+    }
+
+    /// The palette, for changing colors at runtime.
+    pub fn palette_mut(&mut self) -> &mut [C; N] {
+        &mut self.palette
+    }
+
+    /// The raw palette indices.
+    pub fn indices(&self) -> &[u8] {
+        self.indices
+    }
+
+    /// Return the underlying indices.
+    pub fn into_inner(self) -> &'a mut [u8] {
+        self.indices
+    }
+
+    /// Index of the palette entry closest to `color`.
+    fn index_of(&self, color: C) -> u8 {
+        let distance = |c: &C| {
+            let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+            d(c.r(), color.r()) + d(c.g(), color.g()) + d(c.b(), color.b())
+        };
+        match self.palette.iter().position(|&c| c == color) {
+            Some(index) => index as u8,
+            None => (0..N).min_by_key(|&i| distance(&self.palette[i])).unwrap() as u8,
+        }
+    }
+}
+
+impl<C: RgbColor, const N: usize> FrameBufferBackend for IndexedBuffer<'_, C, N> {
+    type Color = C;
+    fn set(&mut self, index: usize, color: C) {
+        self.indices[index] = self.index_of(color);
+    }
+
+    fn get(&self, index: usize) -> C {
+        self.palette[self.indices[index] as usize]
+    }
+
+    fn nr_elements(&self) -> usize {
+        self.indices.len()
+    }
+
+    fn fill(&mut self, color: C) {
+        let index = self.index_of(color);
+        self.indices.fill(index)
+    }
+
+    fn fill_range(&mut self, range: Range<usize>, color: C) {
+        let index = self.index_of(color);
+        self.indices[range].fill(index)
+    }
+}
+
+/// A backend accessing its memory with volatile reads and writes.
+///
+/// Use this for framebuffers in memory that is concurrently scanned out by a
+/// display peripheral (LTDC, LCD_CAM, ...). With plain accesses the compiler
+/// may elide, merge or reorder pixel writes, as it doesn't know that the
+/// peripheral reads the memory. [`VolatileBuffer::with_fence`] additionally
+/// issues a memory barrier after every write, for peripherals behind a bus
+/// that may reorder writes.
+///
+/// ```rust
+/// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+/// use embedded_graphics_framebuf::{backends::VolatileBuffer, FrameBuf};
+///
+/// // Usually a buffer at the address the LTDC scans out
+/// let mut memory = [Rgb565::BLACK; 480 * 272];
+/// let mut fbuf = FrameBuf::new(VolatileBuffer::new(&mut memory).with_fence(), 480, 272);
+/// fbuf.clear(Rgb565::BLUE).unwrap();
+/// ```
+///
+/// Memory that is not a Rust object, like external SDRAM mapped by the FMC,
+/// is wrapped with [`VolatileBuffer::from_raw_parts`]:
+/// ```rust,ignore
+/// const SDRAM: *mut Rgb565 = 0xC000_0000 as *mut Rgb565;
+/// let backend = unsafe { VolatileBuffer::from_raw_parts(SDRAM, 800 * 480) };
+/// let mut fbuf = FrameBuf::new(backend, 800, 480);
+/// ltdc.set_layer_address(SDRAM as u32);
+/// ```
+pub struct VolatileBuffer<'a, C> {
+    data: &'a mut [C],
+    fence: bool,
+}
+
+impl<'a, C: PixelColor> VolatileBuffer<'a, C> {
+    pub fn new(data: &'a mut [C]) -> Self {
+        Self { data, fence: false }
+    }
+
+    /// Create a backend on top of `len` pixels of memory at `ptr`, e.g. a
+    /// frame in SDRAM scanned out by an STM32 LTDC or the RGB peripheral of
+    /// an ESP32-S3.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be non-null, aligned for `C` and valid for reads and writes
+    /// of `len` elements of `C` for the lifetime `'a`, and every bit pattern
+    /// in the memory must be a valid `C`. Only display peripherals may access
+    /// the memory meanwhile.
+    pub unsafe fn from_raw_parts(ptr: *mut C, len: usize) -> Self {
+        assert!(!ptr.is_null(), "VolatileBuffer pointer must not be null");
+        // SAFETY: Guaranteed by the caller.
+        Self::new(unsafe { core::slice::from_raw_parts_mut(ptr, len) })
+    }
+
+    /// Issue a memory barrier after every write.
+    pub fn with_fence(self) -> Self {
+        Self {
+            fence: true,
+            ..self
+        }
+    }
+}
+
+impl<C: PixelColor> FrameBufferBackend for VolatileBuffer<'_, C> {
+    type Color = C;
+    fn set(&mut self, index: usize, color: C) {
+        // SAFETY: The reference is valid, aligned and exclusively ours.
+        unsafe { core::ptr::write_volatile(&mut self.data[index], color) };
+        if self.fence {
+            core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+        }
+    }
+
+    fn get(&self, index: usize) -> C {
+        // SAFETY: The reference is valid and aligned.
+        unsafe { core::ptr::read_volatile(&self.data[index]) }
+    }
+
+    fn nr_elements(&self) -> usize {
+        self.data.len()
+    }
+}
+
+unsafe impl<C: PixelColor> DMACapableFrameBufferBackend for VolatileBuffer<'_, C> {
+    fn data_ptr(&self) -> *const C {
+        self.data.as_ptr()
+    }
+}
+
+/// Marker types forcing the alignment of an [`AlignedBuffer`].
+#[doc(hidden)]
+pub mod align {
+    macro_rules! markers {
+        ($($name:ident = $align:literal),*) => {$(
+            #[derive(Clone, Copy)]
+            #[repr(align($align))]
+            pub struct $name;
+        )*};
+    }
+
+    markers!(
+        A1 = 1,
+        A2 = 2,
+        A4 = 4,
+        A8 = 8,
+        A16 = 16,
+        A32 = 32,
+        A64 = 64,
+        A128 = 128
+    );
+}
+
+/// The alignment `N` in bytes, see [`AlignedBuffer`].
+pub struct Align<const N: usize>;
+
+/// Implemented by the alignments an [`AlignedBuffer`] supports, which are the
+/// powers of two up to 128.
+pub trait Alignment {
+    #[doc(hidden)]
+    type Marker: Copy;
+}
+
+macro_rules! alignments {
+    ($($align:literal => $marker:ident),*) => {$(
+        impl Alignment for Align<$align> {
+            type Marker = align::$marker;
+        }
+    )*};
+}
+
+alignments!(1 => A1, 2 => A2, 4 => A4, 8 => A8, 16 => A16, 32 => A32, 64 => A64, 128 => A128);
+
+/// An array of `N` pixels aligned to `ALIGN` bytes.
+///
+/// Some DMA engines need word aligned buffers, and the cache maintenance of
+/// e.g. a Cortex-M7 works on whole 32 byte cache lines, so a framebuffer
+/// sharing a cache line with other data gets corrupted when the cache is
+/// invalidated. `AlignedBuffer` guarantees the alignment at compile time. A
+/// mutable reference to it is a DMA-capable backend, and it derefs to the
+/// array.
+///
+/// For cache maintenance, the size of the buffer should be a multiple of the
+/// cache line size as well.
+///
+/// ```rust
+/// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+/// use embedded_graphics_framebuf::{backends::AlignedBuffer, FrameBuf};
+///
+/// static mut DATA: AlignedBuffer<Rgb565, { 320 * 240 }, 32> =
+///     AlignedBuffer::new([Rgb565::BLACK; 320 * 240]);
+///
+/// // SAFETY: the only reference to `DATA`
+/// let data = unsafe { &mut *core::ptr::addr_of_mut!(DATA) };
+/// let mut fbuf = FrameBuf::new(data, 320, 240);
+/// fbuf.clear(Rgb565::BLUE).unwrap();
+/// // scb.clean_dcache_by_slice(&fbuf.data[..]); then start the DMA transfer
+/// assert_eq!(fbuf.data.as_ptr() as usize % 32, 0);
+/// ```
+#[repr(C)]
+pub struct AlignedBuffer<C, const N: usize, const ALIGN: usize>
+where
+    Align<ALIGN>: Alignment,
+{
+    _align: [<Align<ALIGN> as Alignment>::Marker; 0],
+    data: [C; N],
+}
+
+impl<C, const N: usize, const ALIGN: usize> AlignedBuffer<C, N, ALIGN>
+where
+    Align<ALIGN>: Alignment,
+{
+    /// Wrap the pixels `data`.
+    pub const fn new(data: [C; N]) -> Self {
+        Self { _align: [], data }
+    }
+
+    /// Return the pixels.
+    pub fn into_inner(self) -> [C; N] {
+        self.data
+    }
+}
+
+impl<C, const N: usize, const ALIGN: usize> core::ops::Deref for AlignedBuffer<C, N, ALIGN>
+where
+    Align<ALIGN>: Alignment,
+{
+    type Target = [C; N];
+
+    fn deref(&self) -> &[C; N] {
+        &self.data
+    }
+}
+
+impl<C, const N: usize, const ALIGN: usize> core::ops::DerefMut for AlignedBuffer<C, N, ALIGN>
+where
+    Align<ALIGN>: Alignment,
+{
+    fn deref_mut(&mut self) -> &mut [C; N] {
+        &mut self.data
+    }
+}
+
+impl<C: PixelColor, const N: usize, const ALIGN: usize> FrameBufferBackend
+    for &mut AlignedBuffer<C, N, ALIGN>
+where
+    Align<ALIGN>: Alignment,
+{
+    type Color = C;
+    fn set(&mut self, index: usize, color: C) {
+        self.data[index] = color
+    }
+
+    fn get(&self, index: usize) -> C {
+        self.data[index]
+    }
+
+    fn nr_elements(&self) -> usize {
+        N
+    }
+
+    fn fill(&mut self, color: C) {
+        self.data.fill(color)
+    }
+
+    fn fill_range(&mut self, range: Range<usize>, color: C) {
+        self.data[range].fill(color)
+    }
+
+    fn as_contiguous(&self) -> Option<&[C]> {
+        Some(&self.data)
+    }
+
+    fn as_contiguous_mut(&mut self) -> Option<&mut [C]> {
+        Some(&mut self.data)
+    }
+}
+
+impl<C: PixelColor, const N: usize, const ALIGN: usize> ContiguousBackend
+    for &mut AlignedBuffer<C, N, ALIGN>
+where
+    Align<ALIGN>: Alignment,
+{
+    fn as_slice(&self) -> &[C] {
+        &self.data
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [C] {
+        &mut self.data
+    }
+}
+
+/// # Safety:
+///
+/// Same as for the array implementation above.
+unsafe impl<C: PixelColor, const N: usize, const ALIGN: usize> DMACapableFrameBufferBackend
+    for &mut AlignedBuffer<C, N, ALIGN>
+where
+    Align<ALIGN>: Alignment,
+{
+    fn data_ptr(&self) -> *const C {
+        self.data.as_ptr()
+    }
+}
+
+/// A heap allocated, DMA-capable backend for large framebuffers.
+///
+/// The pixels are written straight into the allocation, so the buffer never
+/// lives on the stack. This avoids the stack overflows at startup one gets
+/// with e.g. a 320x240 [`Rgb565`](embedded_graphics::pixelcolor::Rgb565)
+/// array on an esp32. As the buffer is owned and its address is stable, a
+/// [`FrameBuf`](crate::FrameBuf) on top of it can be moved into `'static` DMA
+/// transfers.
+///
+/// Whether the memory ends up in internal RAM or in PSRAM is decided by the
+/// global allocator (e.g. `esp-alloc` with a PSRAM region). Some chips (like
+/// the original esp32) can't DMA out of PSRAM, and PSRAM DMA on the esp32-s3
+/// needs a larger alignment. Use [`HeapBuffer::with_alignment`] for the
+/// latter, or [`HeapBuffer::from_raw_parts`] for memory allocated through a
+/// different path (e.g. `heap_caps_malloc(.., MALLOC_CAP_DMA)`).
+///
+/// Example of flushing with esp-hal (the exact DMA API differs between esp-hal
+/// releases):
+/// ```rust,ignore
+/// let data = HeapBuffer::new(Rgb565::BLACK, 320 * 240);
+/// let mut fbuf = FrameBuf::new(data, 320, 240);
+/// loop {
+///     draw_ui(&mut fbuf);
+///     let transfer = spi.dma_write(fbuf).unwrap();
+///     (spi, fbuf) = transfer.wait().unwrap();
+/// }
+/// ```
+#[cfg(feature = "alloc")]
+pub struct HeapBuffer<C> {
+    ptr: core::ptr::NonNull<C>,
+    len: usize,
+    /// `None` if the memory is not owned by the buffer.
+    layout: Option<core::alloc::Layout>,
+}
+
+#[cfg(feature = "alloc")]
+impl<C: PixelColor> HeapBuffer<C> {
+    /// Minimal alignment of the allocation, as required by most DMA engines.
+    pub const DMA_ALIGNMENT: usize = 4;
+
+    /// Allocate a buffer of `len` pixels initialized to `color`.
+    ///
+    /// # Panic
+    /// Panics if the size of the buffer overflows `isize`.
+    pub fn new(color: C, len: usize) -> Self {
+        Self::with_alignment(color, len, Self::DMA_ALIGNMENT).expect("HeapBuffer is too large")
+    }
+
+    /// Allocate a buffer of `len` pixels initialized to `color`, aligned to
+    /// at least `align` bytes.
+    ///
+    /// Returns an error if `align` is not a power of two or the size of the
+    /// buffer overflows `isize`.
+    pub fn with_alignment(
+        color: C,
+        len: usize,
+        align: usize,
+    ) -> Result<Self, core::alloc::LayoutError> {
+        use core::alloc::Layout;
+
+        let layout = Layout::array::<C>(len)?.align_to(align)?;
+        if layout.size() == 0 {
+            return Ok(Self {
+                ptr: core::ptr::NonNull::dangling(),
+                len,
+                layout: None,
+            });
+        }
+
+        // SAFETY: The layout has a non-zero size.
+        let ptr = unsafe { alloc::alloc::alloc(layout) } as *mut C;
+        let ptr = match core::ptr::NonNull::new(ptr) {
+            Some(ptr) => ptr,
+            None => alloc::alloc::handle_alloc_error(layout),
+        };
+        for i in 0..len {
+            // SAFETY: The allocation holds `len` elements of `C`.
+            unsafe { ptr.as_ptr().add(i).write(color) };
+        }
+        Ok(Self {
+            ptr,
+            len,
+            layout: Some(layout),
+        })
+    }
 
-    /// Nr of elements in the backend
-    fn nr_elements(&self) -> usize;
+    /// Create a buffer on top of memory that was not allocated by
+    /// [`HeapBuffer`]. The memory is not freed when the buffer is dropped.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be non-null, aligned for `C` and valid for reads and writes
+    /// of `len` initialized elements of `C` for the whole lifetime of the
+    /// returned buffer. Nothing else may access the memory meanwhile.
+    pub unsafe fn from_raw_parts(ptr: *mut C, len: usize) -> Self {
+        Self {
+            ptr: core::ptr::NonNull::new(ptr).expect("HeapBuffer pointer must not be null"),
+            len,
+            layout: None,
+        }
+    }
+
+    /// The pixels as slice.
+    pub fn as_slice(&self) -> &[C] {
+        // SAFETY: `ptr` is valid for `len` initialized elements.
+        unsafe { core::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// The pixels as mutable slice.
+    pub fn as_mut_slice(&mut self) -> &mut [C] {
+        // SAFETY: `ptr` is valid for `len` initialized elements.
+        unsafe { core::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
 }
 
-impl<'a, C: PixelColor, const N: usize> FrameBufferBackend for &'a mut [C; N] {
+#[cfg(feature = "alloc")]
+impl<C> Drop for HeapBuffer<C> {
+    fn drop(&mut self) {
+        if let Some(layout) = self.layout {
+            // SAFETY: The memory was allocated in `with_alignment` with this
+            // layout. `C` is `Copy`, so there is nothing to drop in place.
+            unsafe { alloc::alloc::dealloc(self.ptr.as_ptr() as *mut u8, layout) }
+        }
+    }
+}
+
+// SAFETY: `HeapBuffer` owns (or exclusively borrows) its memory like a `Box<[C]>`.
+#[cfg(feature = "alloc")]
+unsafe impl<C: Send> Send for HeapBuffer<C> {}
+#[cfg(feature = "alloc")]
+unsafe impl<C: Sync> Sync for HeapBuffer<C> {}
+
+#[cfg(feature = "alloc")]
+impl<C: PixelColor> FrameBufferBackend for HeapBuffer<C> {
     type Color = C;
     fn set(&mut self, index: usize, color: C) {
-        self[index] = color
+        self.as_mut_slice()[index] = color
     }
 
     fn get(&self, index: usize) -> C {
-        self[index]
+        self.as_slice()[index]
     }
 
     fn nr_elements(&self) -> usize {
-        self.len()
+        self.len
+    }
+
+    fn fill(&mut self, color: C) {
+        self.as_mut_slice().fill(color)
+    }
+
+    fn fill_range(&mut self, range: Range<usize>, color: C) {
+        self.as_mut_slice()[range].fill(color)
+    }
+
+    fn as_contiguous(&self) -> Option<&[C]> {
+        Some(self.as_slice())
+    }
+
+    fn as_contiguous_mut(&mut self) -> Option<&mut [C]> {
+        Some(self.as_mut_slice())
     }
 }
 
-impl<C: PixelColor, const N: usize> FrameBufferBackend for [C; N] {
-    type Color = C;
-    fn set(&mut self, index: usize, color: C) {
-        self[index] = color
+/// # Safety:
+///
+/// The allocation is never moved or resized while the buffer exists.
+#[cfg(feature = "alloc")]
+unsafe impl<C: PixelColor> DMACapableFrameBufferBackend for HeapBuffer<C> {
+    fn data_ptr(&self) -> *const C {
+        self.ptr.as_ptr()
     }
+}
 
-    fn get(&self, index: usize) -> C {
-        self[index]
+#[cfg(feature = "alloc")]
+impl<C: PixelColor> ContiguousBackend for HeapBuffer<C> {
+    fn as_slice(&self) -> &[C] {
+        HeapBuffer::as_slice(self)
     }
 
-    fn nr_elements(&self) -> usize {
-        self.len()
+    fn as_mut_slice(&mut self) -> &mut [C] {
+        HeapBuffer::as_mut_slice(self)
     }
 }
 
-/// Warning, this can panic, if the assumed size of the framebuffer (widht * height) is larger than the slice.
-impl<C: PixelColor> FrameBufferBackend for &mut [C] {
+/// A boxed slice as backend, e.g. from `vec![Rgb565::BLACK; 320 * 240].into_boxed_slice()`.
+///
+/// Note that `Box::new([C; N])` builds the array on the stack first, which
+/// can overflow it for large framebuffers. [`HeapBuffer`] or a `Vec` created
+/// with `vec!` don't have this problem.
+#[cfg(feature = "alloc")]
+impl<C: PixelColor> FrameBufferBackend for alloc::boxed::Box<[C]> {
     type Color = C;
     fn set(&mut self, index: usize, color: C) {
         self[index] = color
@@ -89,87 +1999,364 @@ impl<C: PixelColor> FrameBufferBackend for &mut [C] {
     fn nr_elements(&self) -> usize {
         self.len()
     }
-}
 
-/// Backends implementing this Trait can be used for DMA.
-///
-/// # Safety
-///
-/// The same restrictions as for [`embedded_dma::ReadBuffer`] apply.
-pub unsafe trait DMACapableFrameBufferBackend: FrameBufferBackend {
-    fn data_ptr(&self) -> *const Self::Color;
+    fn fill(&mut self, color: C) {
+        self[..].fill(color)
+    }
+
+    fn fill_range(&mut self, range: Range<usize>, color: C) {
+        self[range].fill(color)
+    }
+
+    fn as_contiguous(&self) -> Option<&[C]> {
+        Some(&self[..])
+    }
+
+    fn as_contiguous_mut(&mut self) -> Option<&mut [C]> {
+        Some(&mut self[..])
+    }
 }
 
 /// # Safety:
 ///
-/// The implementation of the trait for all lifetimes `'a` is safe. However,
-/// this doesn't mean that the use of it is safe for all lifetimes. The
-/// requirements specified in [`embedded_dma::ReadBuffer::read_buffer`] remain.
-unsafe impl<'a, C: PixelColor, const N: usize> DMACapableFrameBufferBackend for &'a mut [C; N] {
+/// A boxed slice can't be resized, and its allocation doesn't move when the
+/// box is moved.
+#[cfg(feature = "alloc")]
+unsafe impl<C: PixelColor> DMACapableFrameBufferBackend for alloc::boxed::Box<[C]> {
     fn data_ptr(&self) -> *const C {
         self.as_ptr()
     }
 }
 
-/// Enum indicating how the bytes should be converted in the host's memory.
-#[derive(PartialEq, Eq)]
-pub enum EndianCorrection {
-    ToLittleEndian,
-    ToBigEndian,
-}
+#[cfg(feature = "alloc")]
+impl<C: PixelColor> ContiguousBackend for alloc::boxed::Box<[C]> {
+    fn as_slice(&self) -> &[C] {
+        &self[..]
+    }
 
-/// A backend for [`FrameBuf`](crate::FrameBuf) which changes the underlying
-/// byte order. This can be useful when using the buffer for DMA with
-/// peripherals that have a different endianness than the host.
-pub struct EndianCorrectedBuffer<'a, C> {
-    data: &'a mut [C],
-    endian: EndianCorrection,
-}
-impl<'a, C> EndianCorrectedBuffer<'a, C> {
-    pub fn new(data: &'a mut [C], endian: EndianCorrection) -> Self {
-        Self { data, endian }
+    fn as_mut_slice(&mut self) -> &mut [C] {
+        &mut self[..]
     }
 }
-impl<'a, C> FrameBufferBackend for EndianCorrectedBuffer<'a, C>
-where
-    // TODO: Make this generic over other
-    // types than u16 with associated
-    // type bounds once they are stable
-    C: IntoStorage<Storage = u16> + PixelColor,
-    RawU16: From<C>,
-    C: core::convert::From<RawU16>,
-{
+
+/// A vector as backend. Not DMA capable, as the vector could be reallocated
+/// through [`FrameBuf::data`](crate::FrameBuf::data).
+#[cfg(feature = "alloc")]
+impl<C: PixelColor> FrameBufferBackend for alloc::vec::Vec<C> {
     type Color = C;
     fn set(&mut self, index: usize, color: C) {
-        self.data[index] = match self.endian {
-            EndianCorrection::ToBigEndian => RawU16::new(color.into_storage().to_be()).into(),
-            EndianCorrection::ToLittleEndian => RawU16::new(color.into_storage().to_le()).into(),
-        }
+        self[index] = color
     }
 
     fn get(&self, index: usize) -> C {
-        match self.endian {
-            EndianCorrection::ToBigEndian => {
-                C::from(RawU16::new(u16::from_be(self.data[index].into_storage())))
+        self[index]
+    }
+
+    fn nr_elements(&self) -> usize {
+        self.len()
+    }
+
+    fn fill(&mut self, color: C) {
+        self[..].fill(color)
+    }
+
+    fn fill_range(&mut self, range: Range<usize>, color: C) {
+        self[range].fill(color)
+    }
+
+    fn as_contiguous(&self) -> Option<&[C]> {
+        Some(&self[..])
+    }
+
+    fn as_contiguous_mut(&mut self) -> Option<&mut [C]> {
+        Some(&mut self[..])
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<C: PixelColor> ContiguousBackend for alloc::vec::Vec<C> {
+    fn as_slice(&self) -> &[C] {
+        &self[..]
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [C] {
+        &mut self[..]
+    }
+}
+
+#[cfg(feature = "embedded-hal")]
+mod spi_ram {
+    use core::ops::Range;
+
+    use embedded_graphics::pixelcolor::{
+        raw::{RawData, ToBytes},
+        PixelColor,
+    };
+    use embedded_hal::spi::{Operation, SpiDevice};
+
+    use super::FallibleFrameBufferBackend;
+
+    const READ: u8 = 0x03;
+    const WRITE: u8 = 0x02;
+    /// Size of the chunks for bulk transfers
+    const CHUNK: usize = 64;
+
+    /// A backend storing the pixels in external SPI RAM (PSRAM like the
+    /// APS6404, or SRAM like the 23LC1024), for framebuffers larger than the
+    /// internal RAM.
+    ///
+    /// Pixels take the whole bytes of their raw data
+    /// ([`RawData::BITS_PER_PIXEL`] rounded up to bytes), big endian, starting
+    /// at a base address. The chip is accessed with the common `0x03` read
+    /// and `0x02` write commands with 24 bit addresses.
+    ///
+    /// One row of `W` pixels is cached in internal RAM: reading a pixel loads
+    /// its row, so reading neighbouring pixels (e.g. when flushing) takes one
+    /// transfer per row. Writes go through to the chip right away and update
+    /// the cache if it holds the row.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+    /// use embedded_graphics_framebuf::{backends::SpiRamBuffer, fallible::FallibleFrameBuf};
+    ///
+    /// // 480x320 Rgb565 takes 300 KiB, more than the RP2040 has
+    /// let backend: SpiRamBuffer<_, Rgb565, 480> = SpiRamBuffer::new(psram_spi, 0, 480 * 320);
+    /// let mut fbuf = FallibleFrameBuf::new(backend, 480, 320);
+    /// fbuf.clear(Rgb565::BLACK)?;
+    /// ```
+    pub struct SpiRamBuffer<SPI, C, const W: usize> {
+        spi: SPI,
+        base_address: u32,
+        nr_elements: usize,
+        cache: [C; W],
+        cached_row: Option<usize>,
+    }
+
+    impl<SPI, C, const W: usize> SpiRamBuffer<SPI, C, W>
+    where
+        SPI: SpiDevice,
+        C: PixelColor + From<C::Raw>,
+        C::Raw: From<C>,
+        <C::Raw as ToBytes>::Bytes: AsRef<[u8]>,
+    {
+        const BYTES_PER_PIXEL: usize = C::Raw::BITS_PER_PIXEL.div_ceil(8);
+
+        /// Create a backend of `nr_elements` pixels in rows of `W` pixels,
+        /// stored at `base_address` of the chip behind `spi`.
+        ///
+        /// The memory isn't initialized, clear the framebuffer before use.
+        ///
+        /// # Panic
+        /// Panics if `nr_elements` is not a multiple of `W`, or the pixels
+        /// don't fit into 24 bit addresses.
+        pub fn new(spi: SPI, base_address: u32, nr_elements: usize) -> Self {
+            assert!(
+                W > 0 && nr_elements.is_multiple_of(W),
+                "SpiRamBuffer size {} is not a multiple of the row width {}",
+                nr_elements,
+                W
+            );
+            assert!(
+                base_address as usize + nr_elements * Self::BYTES_PER_PIXEL <= 1 << 24,
+                "SpiRamBuffer exceeds the 24 bit address space"
+            );
+            Self {
+                spi,
+                base_address,
+                nr_elements,
+                cache: [C::Raw::from_u32(0).into(); W],
+                cached_row: None,
             }
-            EndianCorrection::ToLittleEndian => {
-                C::from(RawU16::new(u16::from_le(self.data[index].into_storage())))
+        }
+
+        /// Drop the cached row, e.g. after the memory was changed by someone
+        /// else (like a DMA transfer).
+        pub fn invalidate_cache(&mut self) {
+            self.cached_row = None;
+        }
+
+        /// Return the SPI device.
+        pub fn into_inner(self) -> SPI {
+            self.spi
+        }
+
+        fn command(&self, command: u8, index: usize) -> [u8; 4] {
+            let [_, a2, a1, a0] =
+                (self.base_address + (index * Self::BYTES_PER_PIXEL) as u32).to_be_bytes();
+            [command, a2, a1, a0]
+        }
+
+        /// Write `color` to the pixels in `range` on the chip.
+        fn write(&mut self, range: Range<usize>, color: C) -> Result<(), SPI::Error> {
+            let raw = C::Raw::from(color).to_be_bytes();
+            let bytes = &raw.as_ref()[raw.as_ref().len() - Self::BYTES_PER_PIXEL..];
+            let mut chunk = [0; CHUNK];
+            let pixels_per_chunk = CHUNK / Self::BYTES_PER_PIXEL;
+            for (i, byte) in chunk.iter_mut().enumerate() {
+                *byte = bytes[i % Self::BYTES_PER_PIXEL];
+            }
+            let mut start = range.start;
+            while start < range.end {
+                let pixels = pixels_per_chunk.min(range.end - start);
+                let command = self.command(WRITE, start);
+                self.spi.transaction(&mut [
+                    Operation::Write(&command),
+                    Operation::Write(&chunk[..pixels * Self::BYTES_PER_PIXEL]),
+                ])?;
+                start += pixels;
+            }
+            Ok(())
+        }
+
+        /// Load the row of `index` into the cache.
+        fn load_row(&mut self, row: usize) -> Result<(), SPI::Error> {
+            self.cached_row = None;
+            let mut chunk = [0; CHUNK];
+            let pixels_per_chunk = CHUNK / Self::BYTES_PER_PIXEL;
+            let mut x = 0;
+            while x < W {
+                let pixels = pixels_per_chunk.min(W - x);
+                let command = self.command(READ, row * W + x);
+                let bytes = &mut chunk[..pixels * Self::BYTES_PER_PIXEL];
+                self.spi
+                    .transaction(&mut [Operation::Write(&command), Operation::Read(bytes)])?;
+                for (pixel, bytes) in self.cache[x..x + pixels]
+                    .iter_mut()
+                    .zip(bytes.chunks_exact(Self::BYTES_PER_PIXEL))
+                {
+                    let value = bytes.iter().fold(0, |value, &b| (value << 8) | b as u32);
+                    *pixel = C::Raw::from_u32(value).into();
+                }
+                x += pixels;
             }
+            self.cached_row = Some(row);
+            Ok(())
         }
     }
 
-    fn nr_elements(&self) -> usize {
-        self.data.len()
+    impl<SPI, C, const W: usize> FallibleFrameBufferBackend for SpiRamBuffer<SPI, C, W>
+    where
+        SPI: SpiDevice,
+        C: PixelColor + From<C::Raw>,
+        C::Raw: From<C>,
+        <C::Raw as ToBytes>::Bytes: AsRef<[u8]>,
+    {
+        type Color = C;
+        type Error = SPI::Error;
+
+        fn try_set(&mut self, index: usize, color: C) -> Result<(), SPI::Error> {
+            self.try_fill_range(index..index + 1, color)
+        }
+
+        fn try_get(&mut self, index: usize) -> Result<C, SPI::Error> {
+            assert!(index < self.nr_elements, "index {} out of bounds", index);
+            let row = index / W;
+            if self.cached_row != Some(row) {
+                self.load_row(row)?;
+            }
+            Ok(self.cache[index % W])
+        }
+
+        fn capacity(&self) -> usize {
+            self.nr_elements
+        }
+
+        fn try_fill_range(&mut self, range: Range<usize>, color: C) -> Result<(), SPI::Error> {
+            assert!(
+                range.end <= self.nr_elements,
+                "range {:?} out of bounds",
+                range
+            );
+            if let Some(row) = self.cached_row {
+                let cached = row * W..(row + 1) * W;
+                let start = range.start.max(cached.start);
+                let end = range.end.min(cached.end);
+                if start < end {
+                    self.cache[start - cached.start..end - cached.start].fill(color);
+                }
+            }
+            self.write(range, color)
+        }
     }
-}
-unsafe impl<'a, C> DMACapableFrameBufferBackend for EndianCorrectedBuffer<'a, C>
-where
-    C: IntoStorage<Storage = u16> + PixelColor,
-    RawU16: From<C>,
-    C: core::convert::From<RawU16>,
-{
-    fn data_ptr(&self) -> *const C {
-        self.data.as_ptr()
+
+    #[cfg(test)]
+    mod tests {
+        extern crate std;
+
+        use super::*;
+        use core::convert::Infallible;
+        use embedded_graphics::{
+            draw_target::DrawTarget,
+            pixelcolor::Rgb565,
+            prelude::{Point, RgbColor, Size},
+            primitives::Rectangle,
+        };
+        use std::vec::Vec;
+
+        /// A simulated SPI RAM chip, counting the transfers.
+        struct Ram {
+            memory: Vec<u8>,
+            transactions: usize,
+        }
+
+        impl embedded_hal::spi::ErrorType for Ram {
+            type Error = Infallible;
+        }
+
+        impl SpiDevice for Ram {
+            fn transaction(
+                &mut self,
+                operations: &mut [Operation<'_, u8>],
+            ) -> Result<(), Infallible> {
+                self.transactions += 1;
+                let [Operation::Write(command), data] = operations else {
+                    panic!("unexpected transaction");
+                };
+                let address = u32::from_be_bytes([0, command[1], command[2], command[3]]) as usize;
+                match (command[0], data) {
+                    (WRITE, Operation::Write(bytes)) => {
+                        self.memory[address..address + bytes.len()].copy_from_slice(bytes)
+                    }
+                    (READ, Operation::Read(bytes)) => {
+                        let len = bytes.len();
+                        bytes.copy_from_slice(&self.memory[address..address + len])
+                    }
+                    _ => panic!("unexpected command"),
+                }
+                Ok(())
+            }
+        }
+
+        #[test]
+        fn stores_in_spi_ram() {
+            let ram = Ram {
+                memory: std::vec![0; 0x100 + 40 * 3 * 2],
+                transactions: 0,
+            };
+            let backend: SpiRamBuffer<_, Rgb565, 40> = SpiRamBuffer::new(ram, 0x100, 40 * 3);
+            let mut fbuf = crate::fallible::FallibleFrameBuf::new(backend, 40, 3);
+            let Ok(()) = fbuf.clear(Rgb565::BLUE);
+            let area = Rectangle::new(Point::new(1, 1), Size::new(38, 1));
+            let Ok(()) = fbuf.fill_solid(&area, Rgb565::RED);
+            assert_eq!(fbuf.try_get_color_at(Point::new(0, 1)), Ok(Rgb565::BLUE));
+            let Ok(()) = fbuf.try_set_color_at(Point::new(2, 1), Rgb565::GREEN);
+
+            let mut ram = fbuf.into_inner().into_inner();
+            assert_eq!(ram.memory[0x100 + 2 * 41..][..2], [0xF8, 0x00]);
+            // Clear and fill in 4 and 2 chunks, a row in 2 chunks and
+            // a pixel written through
+            assert_eq!(ram.transactions, 4 + 2 + 2 + 1);
+
+            ram.transactions = 0;
+            let mut backend: SpiRamBuffer<_, Rgb565, 40> = SpiRamBuffer::new(ram, 0x100, 40 * 3);
+            let row: Vec<_> = (40..80).map(|i| backend.try_get(i).unwrap()).collect();
+            assert_eq!(
+                row[..4],
+                [Rgb565::BLUE, Rgb565::RED, Rgb565::GREEN, Rgb565::RED]
+            );
+            assert_eq!(backend.into_inner().transactions, 2);
+        }
     }
 }
 
@@ -179,8 +2366,8 @@ mod tests {
 
     use super::*;
     use crate::FrameBuf;
-    use embedded_graphics::pixelcolor::{raw::RawU16, Rgb565};
-    use embedded_graphics::prelude::{Point, RawData, RgbColor};
+    use embedded_graphics::pixelcolor::{BinaryColor, Gray8, GrayColor, Rgb565, Rgb888};
+    use embedded_graphics::prelude::{Point, RgbColor};
 
     #[test]
     fn test_no_endian_correction() {
@@ -249,4 +2436,285 @@ mod tests {
             0b00000000_00011111
         );
     }
+
+    #[test]
+    fn test_wide_endian_correction() {
+        let mut data = [Rgb888::BLACK; 2];
+        let mut fbuf = FrameBuf::new(
+            EndianCorrectedBuffer::new(&mut data, EndianCorrection::ToBigEndian),
+            2,
+            1,
+        );
+        fbuf.set_color_at(Point::new(0, 0), Rgb888::new(0x12, 0x34, 0x56));
+        assert_eq!(
+            fbuf.get_color_at(Point::new(0, 0)),
+            Rgb888::new(0x12, 0x34, 0x56)
+        );
+        let bytes: [u8; 4] = RawU24::from(data[0]).into_inner().to_ne_bytes();
+        assert_eq!(bytes, [0x12, 0x34, 0x56, 0]);
+
+        let mut data = [Gray8::BLACK; 2];
+        let mut fbuf = FrameBuf::new(
+            EndianCorrectedBuffer::new(&mut data, EndianCorrection::ToLittleEndian),
+            2,
+            1,
+        );
+        fbuf.set_color_at(Point::new(1, 0), Gray8::new(0x12));
+        assert_eq!(data, [Gray8::BLACK, Gray8::new(0x12)]);
+    }
+
+    #[test]
+    fn test_gamma_lut() {
+        assert_eq!(
+            GammaLut::from_gamma(1.0).table(),
+            &core::array::from_fn(|i| i as u8)
+        );
+        let lut = GammaLut::from_gamma(2.2);
+        for i in 0..256 {
+            let expected = ((i as f64 / 255.0).powf(2.2) * 255.0).round() as u8;
+            assert_eq!(lut.apply(i as u8), expected, "channel {}", i);
+        }
+    }
+
+    #[test]
+    fn test_gamma_corrected() {
+        let mut data = [Rgb565::BLACK; 2];
+        let mut fbuf = FrameBuf::new(
+            GammaCorrected::new(&mut data, GammaLut::from_gamma(2.2)),
+            2,
+            1,
+        );
+        fbuf.set_color_at(Point::new(0, 0), Rgb565::new(16, 63, 0));
+        fbuf.data.fill_range(1..2, Rgb565::new(31, 32, 31));
+        assert_eq!(data, [Rgb565::new(7, 63, 0), Rgb565::new(31, 14, 31)]);
+    }
+
+    #[test]
+    fn test_dithered_buffer() {
+        use embedded_graphics::pixelcolor::Gray4;
+
+        let mut data = [Gray4::BLACK; 4 * 4];
+        let mut fbuf: FrameBuf<Gray8, _> = FrameBuf::new(DitheredBuffer::new(&mut data, 4), 4, 4);
+        fbuf.data.fill(Gray8::new(0x18));
+        // 1.41 levels of Gray4 on average, 22.6 in total
+        let sum: u32 = data.iter().map(|c| c.luma() as u32).sum();
+        assert_eq!(sum, 23);
+        let mut fbuf: FrameBuf<Gray8, _> = FrameBuf::new(DitheredBuffer::new(&mut data, 4), 4, 4);
+        fbuf.data.fill(Gray8::new(0x22));
+        assert_eq!(fbuf.get_color_at(Point::new(3, 3)), Gray8::new(0x22));
+    }
+
+    #[test]
+    fn test_byte_buffer() {
+        let mut bytes = [0u8; 2 * 3];
+        let mut fbuf: FrameBuf<Rgb888, _> = FrameBuf::new(
+            ByteBuffer::new(&mut bytes, EndianCorrection::ToLittleEndian),
+            2,
+            1,
+        );
+        fbuf.set_color_at(Point::new(1, 0), Rgb888::new(1, 2, 3));
+        assert_eq!(fbuf.get_color_at(Point::new(1, 0)), Rgb888::new(1, 2, 3));
+        assert_eq!(fbuf.data.as_bytes(), [0, 0, 0, 3, 2, 1]);
+        fbuf.data.fill(Rgb888::RED);
+        assert_eq!(bytes, [0, 0, 255, 0, 0, 255]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_byte_buffer_only_whole_bytes() {
+        ByteBuffer::<_, BinaryColor>::new([0u8; 4], EndianCorrection::ToBigEndian);
+    }
+
+    #[test]
+    fn test_endian_correctable() {
+        for endian in [
+            EndianCorrection::ToBigEndian,
+            EndianCorrection::ToLittleEndian,
+        ] {
+            let raw = RawU24::new(0x12_3456);
+            assert_eq!(RawU24::from_endian(raw.to_endian(endian), endian), raw);
+            let raw = RawU32::new(0x1234_5678);
+            assert_eq!(RawU32::from_endian(raw.to_endian(endian), endian), raw);
+        }
+        let bytes = RawU24::new(0x12_3456)
+            .to_endian(EndianCorrection::ToLittleEndian)
+            .into_inner()
+            .to_ne_bytes();
+        assert_eq!(bytes, [0x56, 0x34, 0x12, 0]);
+    }
+
+    #[test]
+    fn test_slice_backend() {
+        let mut data = [Rgb565::BLUE; 2 * 3 + 1];
+        let len = 6;
+        let mut fbuf = FrameBuf::new(&mut data[..len], 2, 3);
+        fbuf.set_color_at(Point::new(1, 2), Rgb565::RED);
+        assert_eq!(fbuf.get_color_at(Point::new(1, 2)), Rgb565::RED);
+        assert_eq!(fbuf.data.nr_elements(), 6);
+        #[cfg(feature = "embedded-dma")]
+        assert_eq!(
+            unsafe { embedded_dma::ReadBuffer::read_buffer(&fbuf) }.1,
+            12
+        );
+        assert_eq!(data[5], Rgb565::RED);
+        assert_eq!(data[6], Rgb565::BLUE);
+    }
+
+    fn check_page_buffer<D: AsRef<[u8]> + AsMut<[u8]>>(backend: PageBuffer<D>) -> D {
+        let mut fbuf = FrameBuf::new(backend, 4, 16);
+        fbuf.set_color_at(Point::new(0, 0), BinaryColor::On);
+        fbuf.set_color_at(Point::new(1, 7), BinaryColor::On);
+        fbuf.set_color_at(Point::new(3, 9), BinaryColor::On);
+        assert_eq!(fbuf.get_color_at(Point::new(3, 9)), BinaryColor::On);
+        assert_eq!(fbuf.get_color_at(Point::new(3, 8)), BinaryColor::Off);
+        fbuf.set_color_at(Point::new(0, 0), BinaryColor::Off);
+        fbuf.into_inner().into_inner()
+    }
+
+    #[test]
+    fn test_page_buffer() {
+        let expected = [0, 0b1000_0000, 0, 0, 0, 0, 0, 0b10];
+        let mut bytes = [0u8; 4 * 2];
+        check_page_buffer(PageBuffer::new(&mut bytes[..], 4));
+        assert_eq!(bytes, expected);
+        let bytes = check_page_buffer(PageBuffer::new([0u8; 4 * 2], 4));
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_page_buffer_checks_layout() {
+        let mut fbuf = FrameBuf::new(PageBuffer::new([0u8; 4 * 2], 4), 4, 16);
+        fbuf.set_layout(crate::layout::Layout::ColumnMajor);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_packed_binary_buffer_checks_stride() {
+        let backend = PackedBinaryBuffer::new([0u8; 2 * 2], 10, BitOrder::LsbFirst);
+        FrameBuf::new_with_stride(backend, 5, 2, 10);
+    }
+
+    #[test]
+    fn test_indexed_buffer() {
+        let mut indices = [0u8; 3];
+        let palette = [Rgb565::BLACK, Rgb565::new(20, 0, 0), Rgb565::BLUE];
+        let mut fbuf = FrameBuf::new(IndexedBuffer::new(&mut indices, palette), 3, 1);
+        fbuf.set_color_at(Point::new(0, 0), Rgb565::BLUE);
+        // Closest to the dark red
+        fbuf.set_color_at(Point::new(1, 0), Rgb565::RED);
+        fbuf.data.palette_mut()[0] = Rgb565::WHITE;
+        assert_eq!(fbuf.data.indices(), [2, 1, 0]);
+        assert_eq!(fbuf.get_color_at(Point::new(2, 0)), Rgb565::WHITE);
+    }
+
+    #[test]
+    fn test_packed_binary_buffer() {
+        let mut bytes = [0u8; 2 * 2];
+        let mut fbuf = FrameBuf::new(
+            PackedBinaryBuffer::new(&mut bytes, 10, BitOrder::LsbFirst),
+            10,
+            2,
+        );
+        fbuf.set_color_at(Point::new(0, 0), BinaryColor::On);
+        fbuf.set_color_at(Point::new(9, 0), BinaryColor::On);
+        fbuf.set_color_at(Point::new(3, 1), BinaryColor::On);
+        assert_eq!(fbuf.get_color_at(Point::new(9, 0)), BinaryColor::On);
+        assert_eq!(fbuf.get_color_at(Point::new(8, 0)), BinaryColor::Off);
+        assert_eq!(fbuf.data.as_bytes(), [0b1, 0b10, 0b1000, 0]);
+    }
+
+    #[test]
+    fn test_rotated_buffer() {
+        // Native 3x2, logical 2x3
+        let mut data = [0, 1, 2, 3, 4, 5].map(Gray8::new);
+        let mut fbuf = FrameBuf::new(
+            RotatedBuffer::new(&mut data, Size::new(3, 2), Rotation::Deg270),
+            2,
+            3,
+        );
+        assert_eq!(fbuf.get_color_at(Point::new(0, 0)), Gray8::new(3));
+        assert_eq!(fbuf.get_color_at(Point::new(1, 2)), Gray8::new(2));
+        fbuf.set_color_at(Point::new(1, 0), Gray8::new(9));
+        let mut backend = fbuf.into_inner();
+        backend.set_rotation(Rotation::Deg180);
+        assert_eq!(backend.logical_size(), Size::new(3, 2));
+        assert_eq!(backend.get(5), Gray8::new(9));
+        assert_eq!(data, [9, 1, 2, 3, 4, 5].map(Gray8::new));
+    }
+
+    #[test]
+    fn test_aligned_buffer() {
+        let mut data: AlignedBuffer<Gray8, 3, 32> = AlignedBuffer::new([Gray8::new(0); 3]);
+        assert_eq!(core::mem::align_of_val(&data), 32);
+        assert_eq!(data.as_ptr() as usize % 32, 0);
+        let mut fbuf = FrameBuf::new(&mut data, 3, 1);
+        fbuf.set_color_at(Point::new(1, 0), Gray8::new(7));
+        assert_eq!(data.into_inner(), [0, 7, 0].map(Gray8::new));
+    }
+
+    #[test]
+    fn test_transposed_buffer() {
+        let mut data = [Gray8::new(0); 3 * 2];
+        let mut fbuf = FrameBuf::new(TransposedBuffer::new(&mut data, 3), 3, 2);
+        for i in 0..6 {
+            fbuf.set_color_at(Point::new(i % 3, i / 3), Gray8::new(i as u8));
+        }
+        assert_eq!(fbuf.get_color_at(Point::new(2, 1)), Gray8::new(5));
+        assert_eq!(data, [0, 3, 1, 4, 2, 5].map(Gray8::new));
+    }
+
+    #[test]
+    fn test_volatile_buffer_from_raw_parts() {
+        let mut memory = [Rgb565::BLACK; 4 * 2];
+        let backend = unsafe { VolatileBuffer::from_raw_parts(memory.as_mut_ptr(), 8) };
+        let mut fbuf = FrameBuf::new(backend, 4, 2);
+        fbuf.set_color_at(Point::new(1, 1), Rgb565::RED);
+        assert_eq!(fbuf.get_color_at(Point::new(1, 1)), Rgb565::RED);
+        assert_eq!(memory[5], Rgb565::RED);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_heap_buffer() {
+        let data = HeapBuffer::with_alignment(Rgb565::BLUE, 4 * 3, 32).unwrap();
+        let mut fbuf = FrameBuf::new(data, 4, 3);
+        assert_eq!(fbuf.data.data_ptr() as usize % 32, 0);
+        fbuf.set_color_at(Point::new(3, 2), Rgb565::RED);
+        assert_eq!(fbuf.get_color_at(Point::new(3, 2)), Rgb565::RED);
+        assert_eq!(fbuf.data.as_slice()[11], Rgb565::RED);
+        assert_eq!(fbuf.data.as_slice()[0], Rgb565::BLUE);
+
+        assert!(HeapBuffer::with_alignment(Rgb565::BLUE, usize::MAX / 2 + 2, 4).is_err());
+        assert!(HeapBuffer::with_alignment(Rgb565::BLUE, 4, 3).is_err());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_boxed_slice_and_vec() {
+        let mut fbuf = FrameBuf::new(std::vec![Rgb565::BLUE; 4 * 3].into_boxed_slice(), 4, 3);
+        fbuf.set_color_at(Point::new(3, 2), Rgb565::RED);
+        assert_eq!(fbuf.data[11], Rgb565::RED);
+        #[cfg(feature = "embedded-dma")]
+        assert_eq!(
+            unsafe { embedded_dma::ReadBuffer::read_buffer(&fbuf) }.1,
+            24
+        );
+
+        let mut fbuf = FrameBuf::new(fbuf.into_inner().into_vec(), 4, 3);
+        fbuf.set_color_at(Point::new(0, 0), Rgb565::RED);
+        assert_eq!(fbuf.into_inner()[..2], [Rgb565::RED, Rgb565::BLUE]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_heap_buffer_from_raw_parts() {
+        let mut data = [Rgb565::BLUE; 2 * 2];
+        {
+            let backend = unsafe { HeapBuffer::from_raw_parts(data.as_mut_ptr(), data.len()) };
+            let mut fbuf = FrameBuf::new(backend, 2, 2);
+            fbuf.set_color_at(Point::new(1, 1), Rgb565::RED);
+        }
+        assert_eq!(data[3], Rgb565::RED);
+    }
 }
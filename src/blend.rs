@@ -0,0 +1,385 @@
+//! Alpha blending.
+//!
+//! A [`FrameBuf`] overwrites pixels, so e.g. anti-aliased text or translucent
+//! overlays can't be drawn into it directly. [`FrameBuf::blended`] returns a
+//! [`Blended`] draw target for [`Argb8888`] colors, which blends every pixel
+//! onto the current content of the framebuffer according to its alpha.
+//! [`FrameBuf::additive`] returns an [`Additive`] draw target, which adds
+//! colors to the content instead, e.g. for glowing particles.
+//!
+//! A UI layer with per-pixel alpha can be built up off-screen in a framebuffer
+//! of [`PremultipliedArgb8888`] colors. [`FrameBuf::over`] composes
+//! [`Argb8888`] colors onto such a layer, and [`FrameBuf::flatten`] blends the
+//! finished layer onto an opaque scene:
+//!
+//! ```rust
+//! use embedded_graphics::{
+//!     pixelcolor::{Rgb565, Rgb888},
+//!     prelude::*,
+//!     primitives::{PrimitiveStyle, Rectangle},
+//! };
+//! use embedded_graphics_framebuf::{
+//!     color::{Argb8888, PremultipliedArgb8888},
+//!     FrameBuf,
+//! };
+//!
+//! let mut layer_data = [PremultipliedArgb8888::TRANSPARENT; 32 * 16];
+//! let mut layer = FrameBuf::new(&mut layer_data, 32, 16);
+//! let panel = Argb8888::with_alpha(Rgb888::BLACK, 128);
+//! Rectangle::new(Point::zero(), Size::new(32, 16))
+//!     .into_styled(PrimitiveStyle::with_fill(panel))
+//!     .draw(&mut layer.over())
+//!     .unwrap();
+//! let highlight = Argb8888::with_alpha(Rgb888::WHITE, 255);
+//! layer.over().fill_solid(&Rectangle::new(Point::zero(), Size::new(32, 1)), highlight).unwrap();
+//!
+//! let mut data = [Rgb565::WHITE; 64 * 64];
+//! let mut scene = FrameBuf::new(&mut data, 64, 64);
+//! scene.flatten(&layer, Point::new(16, 40));
+//! assert_eq!(scene.get_color_at(Point::new(16, 40)), Rgb565::WHITE);
+//! assert_eq!(scene.get_color_at(Point::new(16, 41)), Rgb565::new(15, 31, 15));
+//! assert_eq!(scene.get_color_at(Point::new(0, 0)), Rgb565::WHITE);
+//! ```
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Dimensions, OriginDimensions},
+    pixelcolor::Rgb888,
+    prelude::{PixelColor, Point, RgbColor, Size},
+    primitives::{PointsIter, Rectangle},
+    Pixel,
+};
+
+use crate::{
+    backends::FrameBufferBackend,
+    color::{Argb8888, PremultipliedArgb8888},
+    FrameBuf,
+};
+
+/// Blend `fg` over `bg` with the opacity `alpha`.
+pub(crate) fn blend(bg: Rgb888, fg: Rgb888, alpha: u8) -> Rgb888 {
+    let mix = |bg: u8, fg: u8| {
+        let (bg, fg, alpha) = (bg as u32, fg as u32, alpha as u32);
+        ((fg * alpha + bg * (255 - alpha) + 127) / 255) as u8
+    };
+    Rgb888::new(
+        mix(bg.r(), fg.r()),
+        mix(bg.g(), fg.g()),
+        mix(bg.b(), fg.b()),
+    )
+}
+
+impl<C, B> FrameBuf<C, B>
+where
+    C: PixelColor + Into<Rgb888> + From<Rgb888>,
+    B: FrameBufferBackend<Color = C>,
+{
+    /// Get a [`DrawTarget`] blending [`Argb8888`] colors onto the
+    /// framebuffer.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{
+    ///     pixelcolor::{Rgb565, Rgb888},
+    ///     prelude::*,
+    ///     primitives::{PrimitiveStyle, Rectangle},
+    /// };
+    /// use embedded_graphics_framebuf::{color::Argb8888, FrameBuf};
+    ///
+    /// let mut data = [Rgb565::BLUE; 64 * 64];
+    /// let mut fbuf = FrameBuf::new(&mut data, 64, 64);
+    /// // A translucent white overlay
+    /// Rectangle::new(Point::new(8, 8), Size::new(48, 16))
+    ///     .into_styled(PrimitiveStyle::with_fill(Argb8888::with_alpha(Rgb888::WHITE, 128)))
+    ///     .draw(&mut fbuf.blended())
+    ///     .unwrap();
+    /// assert_eq!(fbuf.get_color_at(Point::new(8, 8)), Rgb565::new(16, 32, 31));
+    /// assert_eq!(fbuf.get_color_at(Point::new(8, 24)), Rgb565::BLUE);
+    /// ```
+    pub fn blended(&mut self) -> Blended<'_, C, B> {
+        Blended { fbuf: self }
+    }
+
+    /// Get a [`DrawTarget`] adding colors to the framebuffer, saturating each
+    /// channel at its maximum.
+    ///
+    /// Overlapping glows, particles or light sources get brighter instead of
+    /// covering each other.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{
+    ///     pixelcolor::Rgb565,
+    ///     prelude::*,
+    ///     primitives::{Circle, PrimitiveStyle},
+    /// };
+    /// use embedded_graphics_framebuf::FrameBuf;
+    ///
+    /// let mut data = [Rgb565::BLACK; 64 * 64];
+    /// let mut fbuf = FrameBuf::new(&mut data, 64, 64);
+    /// let glow = PrimitiveStyle::with_fill(Rgb565::new(20, 0, 16));
+    /// for center in [Point::new(20, 20), Point::new(28, 20)] {
+    ///     Circle::with_center(center, 16)
+    ///         .into_styled(glow)
+    ///         .draw(&mut fbuf.additive())
+    ///         .unwrap();
+    /// }
+    /// assert_eq!(fbuf.get_color_at(Point::new(14, 20)), Rgb565::new(20, 0, 16));
+    /// assert_eq!(fbuf.get_color_at(Point::new(24, 20)), Rgb565::new(31, 0, 31));
+    /// ```
+    pub fn additive(&mut self) -> Additive<'_, C, B> {
+        Additive { fbuf: self }
+    }
+}
+
+/// A draw target blending colors onto a framebuffer, see
+/// [`FrameBuf::blended`].
+pub struct Blended<'a, C: PixelColor, B: FrameBufferBackend<Color = C>> {
+    fbuf: &'a mut FrameBuf<C, B>,
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> OriginDimensions for Blended<'_, C, B> {
+    fn size(&self) -> Size {
+        self.fbuf.size()
+    }
+}
+
+impl<C, B> DrawTarget for Blended<'_, C, B>
+where
+    C: PixelColor + Into<Rgb888> + From<Rgb888>,
+    B: FrameBufferBackend<Color = C>,
+{
+    type Color = Argb8888;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let area = self.fbuf.bounding_box();
+        for Pixel(p, color) in pixels {
+            if color.alpha() == 0 || !area.contains(p) || !self.fbuf.is_visible(p) {
+                continue;
+            }
+            let color = match color.alpha() {
+                255 => color.rgb(),
+                alpha => blend(self.fbuf.get_color_at(p).into(), color.rgb(), alpha),
+            };
+            self.fbuf.set_color_at(p, color.into());
+        }
+        Ok(())
+    }
+}
+
+/// A draw target adding colors to a framebuffer, see
+/// [`FrameBuf::additive`].
+pub struct Additive<'a, C: PixelColor, B: FrameBufferBackend<Color = C>> {
+    fbuf: &'a mut FrameBuf<C, B>,
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> OriginDimensions for Additive<'_, C, B> {
+    fn size(&self) -> Size {
+        self.fbuf.size()
+    }
+}
+
+impl<C, B> DrawTarget for Additive<'_, C, B>
+where
+    C: PixelColor + Into<Rgb888> + From<Rgb888>,
+    B: FrameBufferBackend<Color = C>,
+{
+    type Color = C;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let area = self.fbuf.bounding_box();
+        for Pixel(p, color) in pixels {
+            if !area.contains(p) || !self.fbuf.is_visible(p) {
+                continue;
+            }
+            let (bg, fg): (Rgb888, Rgb888) = (self.fbuf.get_color_at(p).into(), color.into());
+            let sum = Rgb888::new(
+                bg.r().saturating_add(fg.r()),
+                bg.g().saturating_add(fg.g()),
+                bg.b().saturating_add(fg.b()),
+            );
+            self.fbuf.set_color_at(p, sum.into());
+        }
+        Ok(())
+    }
+}
+
+/// Compose the premultiplied `src` over `dest`.
+fn over(dest: PremultipliedArgb8888, src: PremultipliedArgb8888) -> PremultipliedArgb8888 {
+    let transparency = 255 - src.alpha() as u32;
+    let add =
+        |dest: u8, src: u8| src.saturating_add(((dest as u32 * transparency + 127) / 255) as u8);
+    let (d, s) = (dest.rgb(), src.rgb());
+    PremultipliedArgb8888::new(
+        add(dest.alpha(), src.alpha()),
+        add(d.r(), s.r()),
+        add(d.g(), s.g()),
+        add(d.b(), s.b()),
+    )
+}
+
+impl<B: FrameBufferBackend<Color = PremultipliedArgb8888>> FrameBuf<PremultipliedArgb8888, B> {
+    /// Get a [`DrawTarget`] composing [`Argb8888`] colors over the layer in
+    /// the framebuffer, see the [module documentation](self).
+    pub fn over(&mut self) -> Over<'_, B> {
+        Over { fbuf: self }
+    }
+}
+
+impl<C, B> FrameBuf<C, B>
+where
+    C: PixelColor + Into<Rgb888> + From<Rgb888>,
+    B: FrameBufferBackend<Color = C>,
+{
+    /// Blend the layer `layer` with its top left corner at `position` onto
+    /// the framebuffer, see the [module documentation](self).
+    ///
+    /// Pixels outside of the framebuffer or hidden by the
+    /// [mask](crate::mask) are skipped, and the covered area is marked dirty.
+    pub fn flatten<L>(&mut self, layer: &FrameBuf<PremultipliedArgb8888, L>, position: Point)
+    where
+        L: FrameBufferBackend<Color = PremultipliedArgb8888>,
+    {
+        let area = Rectangle::new(position, layer.size()).intersection(&self.bounding_box());
+        for p in area.points() {
+            let color = layer.get_color_at(p - position);
+            if color.alpha() == 0 || !self.is_visible(p) {
+                continue;
+            }
+            let scene = PremultipliedArgb8888::from(self.get_color_at(p).into());
+            self.set_color_at(p, over(scene, color).rgb().into());
+        }
+        self.mark_dirty(area);
+    }
+}
+
+/// A draw target composing colors over a layer of premultiplied colors, see
+/// [`FrameBuf::over`].
+pub struct Over<'a, B: FrameBufferBackend<Color = PremultipliedArgb8888>> {
+    fbuf: &'a mut FrameBuf<PremultipliedArgb8888, B>,
+}
+
+impl<B: FrameBufferBackend<Color = PremultipliedArgb8888>> OriginDimensions for Over<'_, B> {
+    fn size(&self) -> Size {
+        self.fbuf.size()
+    }
+}
+
+impl<B: FrameBufferBackend<Color = PremultipliedArgb8888>> DrawTarget for Over<'_, B> {
+    type Color = Argb8888;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let area = self.fbuf.bounding_box();
+        for Pixel(p, color) in pixels {
+            if color.alpha() == 0 || !area.contains(p) || !self.fbuf.is_visible(p) {
+                continue;
+            }
+            let color = over(self.fbuf.get_color_at(p), color.into());
+            self.fbuf.set_color_at(p, color);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::prelude::Point;
+
+    #[test]
+    fn blends() {
+        let bg = Rgb888::new(0, 100, 255);
+        assert_eq!(blend(bg, Rgb888::WHITE, 0), bg);
+        assert_eq!(blend(bg, Rgb888::WHITE, 255), Rgb888::WHITE);
+        assert_eq!(blend(bg, Rgb888::BLACK, 51), Rgb888::new(0, 80, 204));
+    }
+
+    #[test]
+    fn draws_blended() {
+        let mut data = [Rgb888::new(0, 0, 100); 3];
+        let mut fbuf = FrameBuf::new(&mut data, 3, 1);
+        fbuf.blended()
+            .draw_iter([
+                Pixel(Point::new(0, 0), Argb8888::new(255, 200, 0, 0)),
+                Pixel(Point::new(1, 0), Argb8888::new(128, 255, 255, 255)),
+                Pixel(Point::new(2, 0), Argb8888::new(0, 255, 255, 255)),
+                Pixel(Point::new(3, 0), Argb8888::new(255, 255, 255, 255)),
+            ])
+            .unwrap();
+        assert_eq!(
+            data,
+            [
+                Rgb888::new(200, 0, 0),
+                Rgb888::new(128, 128, 178),
+                Rgb888::new(0, 0, 100),
+            ]
+        );
+    }
+
+    #[test]
+    fn composes_and_flattens_layers() {
+        let mut layer_data = [PremultipliedArgb8888::TRANSPARENT; 3];
+        let mut layer = FrameBuf::new(&mut layer_data, 3, 1);
+        let red = Argb8888::new(128, 255, 0, 0);
+        layer
+            .over()
+            .draw_iter([
+                Pixel(Point::new(0, 0), red),
+                Pixel(Point::new(1, 0), red),
+                Pixel(Point::new(1, 0), red),
+            ])
+            .unwrap();
+        assert_eq!(
+            layer_data,
+            [
+                PremultipliedArgb8888::new(128, 128, 0, 0),
+                PremultipliedArgb8888::new(192, 192, 0, 0),
+                PremultipliedArgb8888::TRANSPARENT,
+            ]
+        );
+
+        let layer = FrameBuf::new(&mut layer_data, 3, 1);
+        let mut data = [Rgb888::new(0, 0, 200); 4];
+        let mut fbuf = FrameBuf::new(&mut data, 4, 1);
+        fbuf.take_dirty_area();
+        fbuf.flatten(&layer, Point::new(1, 0));
+        assert_eq!(
+            fbuf.take_dirty_area(),
+            Some(Rectangle::new(Point::new(1, 0), Size::new(3, 1)))
+        );
+        assert_eq!(
+            data,
+            [
+                Rgb888::new(0, 0, 200),
+                Rgb888::new(128, 0, 100),
+                Rgb888::new(192, 0, 49),
+                Rgb888::new(0, 0, 200),
+            ]
+        );
+    }
+
+    #[test]
+    fn adds_saturating() {
+        let mut data = [Rgb888::new(10, 200, 0), Rgb888::BLACK];
+        let mut fbuf = FrameBuf::new(&mut data, 2, 1);
+        fbuf.additive()
+            .draw_iter([
+                Pixel(Point::new(0, 0), Rgb888::new(20, 100, 5)),
+                Pixel(Point::new(2, 0), Rgb888::WHITE),
+            ])
+            .unwrap();
+        assert_eq!(data, [Rgb888::new(30, 255, 5), Rgb888::BLACK]);
+    }
+}
@@ -0,0 +1,549 @@
+//! Copying pixels between framebuffers.
+//!
+//! [`FrameBuf::blit`] copies a rectangle of one framebuffer into another, e.g.
+//! to draw sprites, to restore widgets from an off-screen cache or to compose
+//! the back buffer of a double buffered display. If both backends are
+//! [contiguous](crate::backends::FrameBufferBackend::as_contiguous), whole rows
+//! are copied at once.
+//!
+//! Sprites with transparent pixels can be copied with
+//! [`FrameBuf::blit_keyed`], where one color of the sprite is transparent, or
+//! with [`FrameBuf::blit_masked`], where a separate 1 bit plane selects the
+//! opaque pixels. [`FrameBuf::blit_scaled`] scales the copied pixels, e.g.
+//! to show a low resolution game surface on a larger panel, and
+//! [`FrameBuf::downscale_into`] averages them for smooth thumbnails.
+//! [`FrameBuf::convert_into`] copies a framebuffer into one of another color
+//! type.
+
+use embedded_graphics::{
+    geometry::Dimensions,
+    pixelcolor::{BinaryColor, Rgb888},
+    prelude::{PixelColor, Point, RgbColor},
+    primitives::{PointsIter, Rectangle},
+};
+
+use crate::{
+    backends::{FrameBufferBackend, PackedBinaryBuffer},
+    layout::Layout,
+    FrameBuf,
+};
+
+/// Clip the copy of `src_rect` of a `src_area` sized source to `dest` of a
+/// `dest_area` sized destination.
+///
+/// Returns the top left corner of the remaining source pixels and the area
+/// they are copied to.
+pub(crate) fn clip_blit(
+    src_rect: Rectangle,
+    src_area: Rectangle,
+    dest: Point,
+    dest_area: Rectangle,
+) -> (Point, Rectangle) {
+    let src_rect_clipped = src_rect.intersection(&src_area);
+    let dest = dest + (src_rect_clipped.top_left - src_rect.top_left);
+    let dest_rect = Rectangle::new(dest, src_rect_clipped.size).intersection(&dest_area);
+    (
+        src_rect_clipped.top_left + (dest_rect.top_left - dest),
+        dest_rect,
+    )
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
+    /// Copy the pixels in `src_rect` of `src` to the framebuffer, with the top
+    /// left corner of `src_rect` ending up at `dest`.
+    ///
+    /// Pixels outside of either framebuffer or hidden by the
+    /// [mask](crate::mask) are skipped, and the written area is marked dirty.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::Rgb565, prelude::*, primitives::Rectangle};
+    /// use embedded_graphics_framebuf::FrameBuf;
+    ///
+    /// let mut sprite_data = [Rgb565::RED; 8 * 8];
+    /// let sprite = FrameBuf::new(&mut sprite_data, 8, 8);
+    ///
+    /// let mut data = [Rgb565::BLACK; 64 * 64];
+    /// let mut fbuf = FrameBuf::new(&mut data, 64, 64);
+    /// fbuf.blit(&sprite, sprite.bounding_box(), Point::new(60, 10));
+    /// assert_eq!(fbuf.get_color_at(Point::new(63, 17)), Rgb565::RED);
+    /// assert_eq!(fbuf.get_color_at(Point::new(59, 10)), Rgb565::BLACK);
+    /// ```
+    pub fn blit<S>(&mut self, src: &FrameBuf<C, S>, src_rect: Rectangle, dest: Point)
+    where
+        S: FrameBufferBackend<Color = C>,
+    {
+        let (src_top_left, area) =
+            clip_blit(src_rect, src.bounding_box(), dest, self.bounding_box());
+        let rows_contiguous = self.mask.is_none()
+            && self.layout == Layout::RowMajor
+            && src.layout == Layout::RowMajor;
+        let width = area.size.width as usize;
+        match (src.data.as_contiguous(), self.data.as_contiguous_mut()) {
+            (Some(src_pixels), Some(pixels)) if rows_contiguous => {
+                for y in 0..area.size.height as i32 {
+                    let from = src.point_to_index(src_top_left + Point::new(0, y));
+                    let to = self.layout.index(
+                        area.top_left + Point::new(0, y),
+                        self.stride,
+                        self.height,
+                    );
+                    pixels[to..to + width].copy_from_slice(&src_pixels[from..from + width]);
+                }
+            }
+            _ => self.blit_pixels(src, src_top_left, area, |_, _| true),
+        }
+        self.mark_dirty(area);
+    }
+
+    /// Like [`FrameBuf::blit`], but pixels of `src` with the color `key` are
+    /// transparent.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+    /// use embedded_graphics_framebuf::FrameBuf;
+    ///
+    /// let mut sprite_data = [Rgb565::MAGENTA, Rgb565::RED, Rgb565::MAGENTA];
+    /// let sprite = FrameBuf::new(&mut sprite_data, 3, 1);
+    ///
+    /// let mut data = [Rgb565::BLACK; 16 * 16];
+    /// let mut fbuf = FrameBuf::new(&mut data, 16, 16);
+    /// fbuf.blit_keyed(&sprite, sprite.bounding_box(), Point::new(4, 4), Rgb565::MAGENTA);
+    /// assert_eq!(fbuf.get_color_at(Point::new(4, 4)), Rgb565::BLACK);
+    /// assert_eq!(fbuf.get_color_at(Point::new(5, 4)), Rgb565::RED);
+    /// ```
+    pub fn blit_keyed<S>(&mut self, src: &FrameBuf<C, S>, src_rect: Rectangle, dest: Point, key: C)
+    where
+        S: FrameBufferBackend<Color = C>,
+    {
+        let (src_top_left, area) =
+            clip_blit(src_rect, src.bounding_box(), dest, self.bounding_box());
+        self.blit_pixels(src, src_top_left, area, |_, color| color != key);
+        self.mark_dirty(area);
+    }
+
+    /// Like [`FrameBuf::blit`], but only the pixels of `src` which are
+    /// [`BinaryColor::On`] in `mask` are copied.
+    ///
+    /// `mask` is a plane with the same size as `src`, in its own coordinates
+    /// (i.e. ignoring the layout of `src`).
+    ///
+    /// # Panic
+    /// Panics if `mask` is not as wide as `src`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+    /// use embedded_graphics_framebuf::{
+    ///     backends::{BitOrder, PackedBinaryBuffer},
+    ///     FrameBuf,
+    /// };
+    ///
+    /// let mut sprite_data = [Rgb565::RED; 8 * 2];
+    /// let sprite = FrameBuf::new(&mut sprite_data, 8, 2);
+    /// let mut mask_bytes = [0b1111_0000, 0b0000_1111];
+    /// let mask = PackedBinaryBuffer::new(&mut mask_bytes, 8, BitOrder::MsbFirst);
+    ///
+    /// let mut data = [Rgb565::BLACK; 16 * 16];
+    /// let mut fbuf = FrameBuf::new(&mut data, 16, 16);
+    /// fbuf.blit_masked(&sprite, sprite.bounding_box(), Point::zero(), &mask);
+    /// assert_eq!(fbuf.get_color_at(Point::new(3, 0)), Rgb565::RED);
+    /// assert_eq!(fbuf.get_color_at(Point::new(4, 0)), Rgb565::BLACK);
+    /// assert_eq!(fbuf.get_color_at(Point::new(4, 1)), Rgb565::RED);
+    /// ```
+    pub fn blit_masked<S, D>(
+        &mut self,
+        src: &FrameBuf<C, S>,
+        src_rect: Rectangle,
+        dest: Point,
+        mask: &PackedBinaryBuffer<D>,
+    ) where
+        S: FrameBufferBackend<Color = C>,
+        D: AsRef<[u8]> + AsMut<[u8]>,
+    {
+        assert_eq!(
+            mask.width(),
+            src.width(),
+            "mask width {} doesn't match the source width {}",
+            mask.width(),
+            src.width()
+        );
+        let (src_top_left, area) =
+            clip_blit(src_rect, src.bounding_box(), dest, self.bounding_box());
+        self.blit_pixels(src, src_top_left, area, |p, _| {
+            mask.get(p.y as usize * src.width() + p.x as usize) == BinaryColor::On
+        });
+        self.mark_dirty(area);
+    }
+
+    /// Copy the pixels in `src_rect` of `src` to `dest_rect`, scaled with
+    /// nearest neighbor sampling.
+    ///
+    /// Pixels of `dest_rect` outside of the framebuffer or hidden by the
+    /// [mask](crate::mask) are skipped, as are those sampling outside of
+    /// `src`. The written area is marked dirty.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::Rgb565, prelude::*, primitives::Rectangle};
+    /// use embedded_graphics_framebuf::FrameBuf;
+    ///
+    /// let mut game_data = [Rgb565::BLACK; 160 * 120];
+    /// let mut game = FrameBuf::new(&mut game_data, 160, 120);
+    /// game.set_color_at(Point::new(1, 0), Rgb565::RED);
+    ///
+    /// let mut data = [Rgb565::BLACK; 320 * 240];
+    /// let mut fbuf = FrameBuf::new(&mut data, 320, 240);
+    /// fbuf.blit_scaled(&game, game.bounding_box(), fbuf.bounding_box());
+    /// assert_eq!(fbuf.get_color_at(Point::new(3, 1)), Rgb565::RED);
+    /// assert_eq!(fbuf.get_color_at(Point::new(4, 0)), Rgb565::BLACK);
+    /// ```
+    pub fn blit_scaled<S>(
+        &mut self,
+        src: &FrameBuf<C, S>,
+        src_rect: Rectangle,
+        dest_rect: Rectangle,
+    ) where
+        S: FrameBufferBackend<Color = C>,
+    {
+        let area = dest_rect.intersection(&self.bounding_box());
+        if area.is_zero_sized() || src_rect.is_zero_sized() {
+            return;
+        }
+        let src_area = src.bounding_box();
+        let scale = |offset: i32, src_len: u32, dest_len: u32| {
+            (offset as i64 * src_len as i64 / dest_len as i64) as i32
+        };
+        for y in area.rows() {
+            let src_y = src_rect.top_left.y
+                + scale(
+                    y - dest_rect.top_left.y,
+                    src_rect.size.height,
+                    dest_rect.size.height,
+                );
+            for x in area.columns() {
+                let src_x = src_rect.top_left.x
+                    + scale(
+                        x - dest_rect.top_left.x,
+                        src_rect.size.width,
+                        dest_rect.size.width,
+                    );
+                let (p, src_p) = (Point::new(x, y), Point::new(src_x, src_y));
+                if src_area.contains(src_p) && self.is_visible(p) {
+                    self.set_color_at(p, src.get_color_at(src_p));
+                }
+            }
+        }
+        self.mark_dirty(area);
+    }
+
+    /// Copy the pixels of `src` starting at `src_top_left` to `area` one by
+    /// one, skipping the ones rejected by `keep`.
+    fn blit_pixels<S, F>(
+        &mut self,
+        src: &FrameBuf<C, S>,
+        src_top_left: Point,
+        area: Rectangle,
+        keep: F,
+    ) where
+        S: FrameBufferBackend<Color = C>,
+        F: Fn(Point, C) -> bool,
+    {
+        for p in area.points() {
+            let src_p = src_top_left + (p - area.top_left);
+            let color = src.get_color_at(src_p);
+            if self.is_visible(p) && keep(src_p, color) {
+                self.set_color_at(p, color);
+            }
+        }
+    }
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
+    /// Copy the pixels to `dest`, converting them to its color type, e.g.
+    /// from an [`Rgb888`] compositing buffer to the [`Rgb565`] buffer of the
+    /// display.
+    ///
+    /// Pixels outside of either framebuffer or hidden by the
+    /// [mask](crate::mask) of `dest` are skipped, and the written area is
+    /// marked dirty. See [`FrameBuf::converted`] to convert while flushing.
+    ///
+    /// [`Rgb565`]: embedded_graphics::pixelcolor::Rgb565
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::{Rgb565, Rgb888}, prelude::*};
+    /// use embedded_graphics_framebuf::FrameBuf;
+    ///
+    /// let mut data = [Rgb888::new(255, 128, 0); 64 * 64];
+    /// let fbuf = FrameBuf::new(&mut data, 64, 64);
+    /// let mut display_data = [Rgb565::BLACK; 64 * 64];
+    /// let mut display_fbuf = FrameBuf::new(&mut display_data, 64, 64);
+    /// fbuf.convert_into(&mut display_fbuf);
+    /// assert_eq!(display_data[0], Rgb565::new(31, 32, 0));
+    /// ```
+    pub fn convert_into<E, D>(&self, dest: &mut FrameBuf<E, D>)
+    where
+        E: PixelColor + From<C>,
+        D: FrameBufferBackend<Color = E>,
+    {
+        let area = self.bounding_box().intersection(&dest.bounding_box());
+        let width = area.size.width as usize;
+        let rows_contiguous = dest.mask.is_none()
+            && self.layout == Layout::RowMajor
+            && dest.layout == Layout::RowMajor;
+        let (src_stride, dest_stride) = (self.stride, dest.stride);
+        match (self.data.as_contiguous(), dest.data.as_contiguous_mut()) {
+            (Some(src_pixels), Some(pixels)) if rows_contiguous => {
+                for y in area.rows() {
+                    let (from, to) = (y as usize * src_stride, y as usize * dest_stride);
+                    let src_row = &src_pixels[from..from + width];
+                    for (pixel, color) in pixels[to..to + width].iter_mut().zip(src_row) {
+                        *pixel = E::from(*color);
+                    }
+                }
+            }
+            _ => {
+                for p in area.points() {
+                    if dest.is_visible(p) {
+                        dest.set_color_at(p, self.get_color_at(p).into());
+                    }
+                }
+            }
+        }
+        dest.mark_dirty(area);
+    }
+}
+
+impl<C, B> FrameBuf<C, B>
+where
+    C: PixelColor + Into<Rgb888>,
+    B: FrameBufferBackend<Color = C>,
+{
+    /// Shrink the whole framebuffer into `dest`, with each pixel of `dest`
+    /// being the average of the block of pixels it covers.
+    ///
+    /// Unlike nearest neighbor sampling, thin lines and text don't get lost
+    /// or jagged, e.g. for screenshot thumbnails or picture-in-picture
+    /// previews. Pixels of `dest` hidden by its [mask](crate::mask) are
+    /// skipped, and `dest` is marked dirty. Nothing is drawn if either
+    /// framebuffer is empty.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+    /// use embedded_graphics_framebuf::FrameBuf;
+    ///
+    /// let mut data = [Rgb565::BLACK; 240 * 240];
+    /// let mut fbuf = FrameBuf::new(&mut data, 240, 240);
+    /// fbuf.set_color_at(Point::new(1, 0), Rgb565::WHITE);
+    /// fbuf.set_color_at(Point::new(0, 1), Rgb565::WHITE);
+    ///
+    /// let mut thumbnail_data = [Rgb565::BLACK; 120 * 120];
+    /// let mut thumbnail = FrameBuf::new(&mut thumbnail_data, 120, 120);
+    /// fbuf.downscale_into(&mut thumbnail);
+    /// assert_eq!(thumbnail.get_color_at(Point::zero()), Rgb565::new(16, 32, 16));
+    /// ```
+    pub fn downscale_into<E, D>(&self, dest: &mut FrameBuf<E, D>)
+    where
+        E: PixelColor + From<Rgb888>,
+        D: FrameBufferBackend<Color = E>,
+    {
+        if self.bounding_box().is_zero_sized() || dest.bounding_box().is_zero_sized() {
+            return;
+        }
+        let block = |i: i32, from: usize, to: usize| {
+            let start = i as usize * from / to;
+            let end = ((i as usize + 1) * from / to).max(start + 1);
+            start as i32..end as i32
+        };
+        for p in dest.bounding_box().points() {
+            if !dest.is_visible(p) {
+                continue;
+            }
+            let mut sums = [0u32; 3];
+            let mut count = 0;
+            for y in block(p.y, self.height(), dest.height()) {
+                for x in block(p.x, self.width(), dest.width()) {
+                    let color: Rgb888 = self.get_color_at(Point::new(x, y)).into();
+                    sums[0] += color.r() as u32;
+                    sums[1] += color.g() as u32;
+                    sums[2] += color.b() as u32;
+                    count += 1;
+                }
+            }
+            let [r, g, b] = sums.map(|sum| ((sum + count / 2) / count) as u8);
+            dest.set_color_at(p, Rgb888::new(r, g, b).into());
+        }
+        dest.mark_all_dirty();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::BitOrder;
+    use embedded_graphics::{pixelcolor::Gray8, prelude::Size, Pixel};
+
+    fn blit_into(layout: Layout) -> [Gray8; 4 * 3] {
+        let mut src_data = [0, 1, 2, 3, 4, 5].map(Gray8::new);
+        let src = FrameBuf::new(&mut src_data, 3, 2);
+        let mut data = [Gray8::new(9); 4 * 3];
+        let mut fbuf = FrameBuf::new(&mut data, 4, 3);
+        fbuf.set_layout(layout);
+        fbuf.take_dirty_area();
+        fbuf.blit(
+            &src,
+            Rectangle::new(Point::new(-1, 0), Size::new(4, 2)),
+            Point::new(1, 2),
+        );
+        assert_eq!(
+            fbuf.take_dirty_area(),
+            Some(Rectangle::new(Point::new(2, 2), Size::new(2, 1)))
+        );
+        let mut pixels = [Gray8::new(0); 4 * 3];
+        for (pixel, Pixel(_, color)) in pixels.iter_mut().zip(fbuf.pixels()) {
+            *pixel = color;
+        }
+        pixels
+    }
+
+    #[test]
+    fn blits_clipped() {
+        let expected = [9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 0, 1].map(Gray8::new);
+        assert_eq!(blit_into(Layout::RowMajor), expected);
+        assert_eq!(blit_into(Layout::ColumnMajor), expected);
+    }
+
+    #[test]
+    fn clips() {
+        let bounds = Rectangle::new(Point::zero(), Size::new(10, 10));
+        assert_eq!(
+            clip_blit(
+                Rectangle::new(Point::new(2, -2), Size::new(4, 4)),
+                bounds,
+                Point::new(-1, 5),
+                bounds,
+            ),
+            (
+                Point::new(3, 0),
+                Rectangle::new(Point::new(0, 7), Size::new(3, 2))
+            )
+        );
+    }
+
+    #[test]
+    fn blits_keyed_and_masked() {
+        let mut src_data = [0, 1, 2, 3, 4, 5].map(Gray8::new);
+        let src = FrameBuf::new(&mut src_data, 3, 2);
+        let mut mask_bytes = [0b101, 0b010];
+        let mask = PackedBinaryBuffer::new(&mut mask_bytes, 3, BitOrder::LsbFirst);
+        let src_rect = Rectangle::new(Point::new(1, 0), Size::new(2, 2));
+
+        let mut data = [Gray8::new(9); 2 * 2];
+        let mut fbuf = FrameBuf::new(&mut data, 2, 2);
+        fbuf.blit_keyed(&src, src_rect, Point::zero(), Gray8::new(4));
+        assert_eq!(data, [1, 2, 9, 5].map(Gray8::new));
+
+        let mut data = [Gray8::new(9); 2 * 2];
+        let mut fbuf = FrameBuf::new(&mut data, 2, 2);
+        fbuf.blit_masked(&src, src_rect, Point::zero(), &mask);
+        assert_eq!(data, [9, 2, 4, 9].map(Gray8::new));
+    }
+
+    #[test]
+    fn blits_scaled() {
+        let mut src_data = [0, 1, 2, 3, 4, 5].map(Gray8::new);
+        let src = FrameBuf::new(&mut src_data, 3, 2);
+
+        let mut data = [Gray8::new(9); 4 * 4];
+        let mut fbuf = FrameBuf::new(&mut data, 4, 4);
+        fbuf.take_dirty_area();
+        let src_rect = Rectangle::new(Point::new(1, 0), Size::new(2, 2));
+        fbuf.blit_scaled(
+            &src,
+            src_rect,
+            Rectangle::new(Point::new(-1, 1), Size::new(4, 4)),
+        );
+        assert_eq!(
+            fbuf.take_dirty_area(),
+            Some(Rectangle::new(Point::new(0, 1), Size::new(3, 3)))
+        );
+        #[rustfmt::skip]
+        let expected = [
+            9, 9, 9, 9,
+            1, 2, 2, 9,
+            1, 2, 2, 9,
+            4, 5, 5, 9,
+        ];
+        assert_eq!(data, expected.map(Gray8::new));
+
+        // Shrinking
+        let mut data = [Gray8::new(9); 2];
+        let mut fbuf = FrameBuf::new(&mut data, 2, 1);
+        fbuf.blit_scaled(&src, src.bounding_box(), fbuf.bounding_box());
+        assert_eq!(data, [0, 1].map(Gray8::new));
+    }
+
+    #[test]
+    fn downscales_averaging() {
+        let mut data = [0, 100, 200, 50, 0, 0, 60, 61, 255].map(Gray8::new);
+        let fbuf = FrameBuf::new(&mut data, 3, 3);
+        let mut dest_data = [Gray8::new(9); 2 * 2];
+        let mut dest = FrameBuf::new(&mut dest_data, 2, 2);
+        dest.take_dirty_area();
+        fbuf.downscale_into(&mut dest);
+        assert_eq!(dest.take_dirty_area(), Some(dest.bounding_box()));
+        // Blocks of 1x1, 2x1, 1x2 and 2x2 pixels
+        assert_eq!(dest_data, [0, 150, 55, 79].map(Gray8::new));
+    }
+
+    #[test]
+    fn downscales_empty_framebuffers() {
+        let mut data: [Gray8; 0] = [];
+        let fbuf = FrameBuf::new(&mut data, 0, 3);
+        let mut dest_data = [Gray8::new(9); 2];
+        let mut dest = FrameBuf::new(&mut dest_data, 2, 1);
+        dest.take_dirty_area();
+        fbuf.downscale_into(&mut dest);
+        assert_eq!(dest.take_dirty_area(), None);
+
+        let mut empty_data: [Gray8; 0] = [];
+        let mut empty = FrameBuf::new(&mut empty_data, 3, 0);
+        dest.downscale_into(&mut empty);
+        assert_eq!(dest_data, [Gray8::new(9); 2]);
+    }
+
+    #[test]
+    fn converts_colors() {
+        use embedded_graphics::pixelcolor::Rgb565;
+
+        for layout in [Layout::RowMajor, Layout::ColumnMajor] {
+            let mut data = [Rgb888::WHITE, Rgb888::RED, Rgb888::BLUE, Rgb888::BLACK];
+            let fbuf = FrameBuf::new(&mut data, 2, 2);
+            let mut dest_data = [Rgb565::GREEN; 3];
+            let mut dest = FrameBuf::new(&mut dest_data, 3, 1);
+            dest.set_layout(layout);
+            dest.take_dirty_area();
+            fbuf.convert_into(&mut dest);
+            assert_eq!(
+                dest.take_dirty_area(),
+                Some(Rectangle::new(Point::zero(), Size::new(2, 1)))
+            );
+            assert_eq!(dest_data, [Rgb565::WHITE, Rgb565::RED, Rgb565::GREEN]);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn blits_masked_only_same_width() {
+        let mut src_data = [Gray8::new(0); 3 * 2];
+        let src = FrameBuf::new(&mut src_data, 3, 2);
+        let mut mask_bytes = [0u8; 2];
+        let mask = PackedBinaryBuffer::new(&mut mask_bytes, 2, BitOrder::LsbFirst);
+        let mut data = [Gray8::new(9); 2 * 2];
+        let mut fbuf = FrameBuf::new(&mut data, 2, 2);
+        fbuf.blit_masked(&src, src.bounding_box(), Point::zero(), &mask);
+    }
+}
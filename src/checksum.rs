@@ -0,0 +1,444 @@
+//! Finding changed rows by their checksums.
+//!
+//! [Dirty tracking](crate::dirty) knows which pixels were drawn, but not
+//! whether they actually changed: a UI redrawing all widgets every frame
+//! marks everything dirty, even if only a clock changed. [`RowChecksums`]
+//! stores a checksum of every row and compares them after rendering, so only
+//! the rows which differ from the last flush are sent to the display.
+//! [`TileChecksums`] does the same for a grid of tiles, which finds small
+//! changes more precisely, e.g. for displays with partial window updates.
+//! [`FrameHash`] only hashes the whole frame, so a dashboard which rarely
+//! changes can skip the transfer entirely if nothing changed.
+
+use embedded_graphics::{
+    pixelcolor::raw::RawData,
+    prelude::{PixelColor, Point, Size},
+    primitives::{PointsIter, Rectangle},
+};
+
+use crate::{backends::FrameBufferBackend, FrameBuf};
+
+/// FNV-1a hash of the raw colors of `area` of `fbuf`.
+fn checksum<C, B>(fbuf: &FrameBuf<C, B>, area: Rectangle) -> u32
+where
+    C: PixelColor,
+    C::Raw: From<C>,
+    <C::Raw as RawData>::Storage: Into<u32>,
+    B: FrameBufferBackend<Color = C>,
+{
+    area.points().fold(0x811C_9DC5, |hash, p| {
+        let raw: u32 = C::Raw::from(fbuf.get_color_at(p)).into_inner().into();
+        raw.to_le_bytes().iter().fold(hash, |hash, &byte| {
+            (hash ^ byte as u32).wrapping_mul(0x0100_0193)
+        })
+    })
+}
+
+impl<C, B> FrameBuf<C, B>
+where
+    C: PixelColor,
+    C::Raw: From<C>,
+    <C::Raw as RawData>::Storage: Into<u32>,
+    B: FrameBufferBackend<Color = C>,
+{
+    /// An FNV-1a hash of all pixels of the framebuffer.
+    pub fn frame_hash(&self) -> u32 {
+        checksum(self, Rectangle::new(Point::zero(), self.size()))
+    }
+}
+
+/// The hash of a whole frame at the last flush.
+///
+/// # Example
+/// ```rust
+/// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+/// use embedded_graphics_framebuf::{checksum::FrameHash, FrameBuf};
+///
+/// let mut data = [Rgb565::BLACK; 320 * 240];
+/// let mut fbuf = FrameBuf::new(&mut data, 320, 240);
+/// let mut hash = FrameHash::new();
+/// // Initially, the frame has to be flushed.
+/// assert!(hash.has_changed_since_last_flush(&fbuf));
+///
+/// // Redraw the whole UI without changing anything.
+/// fbuf.clear(Rgb565::BLACK).unwrap();
+/// assert!(!hash.has_changed_since_last_flush(&fbuf));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FrameHash {
+    /// The hash at the last flush, if known
+    hash: Option<u32>,
+}
+
+impl FrameHash {
+    /// Create a frame hash. Initially the frame is considered changed.
+    pub const fn new() -> Self {
+        Self { hash: None }
+    }
+
+    /// Consider the frame changed, e.g. after the display was reset.
+    pub fn invalidate(&mut self) {
+        self.hash = None;
+    }
+
+    /// Whether `fbuf` changed since the last call, and remember its new hash.
+    ///
+    /// The frame is expected to be flushed to the display if this returns
+    /// `true`.
+    pub fn has_changed_since_last_flush<C, B>(&mut self, fbuf: &FrameBuf<C, B>) -> bool
+    where
+        C: PixelColor,
+        C::Raw: From<C>,
+        <C::Raw as RawData>::Storage: Into<u32>,
+        B: FrameBufferBackend<Color = C>,
+    {
+        let hash = fbuf.frame_hash();
+        self.hash.replace(hash) != Some(hash)
+    }
+}
+
+/// Checksums of the rows of a framebuffer at the last flush.
+///
+/// The checksums are stored in `S`, one `u32` per row, so e.g. a
+/// `[u32; 240]` for a framebuffer 240 pixels high.
+///
+/// # Example
+/// ```rust
+/// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+/// use embedded_graphics_framebuf::{checksum::RowChecksums, FrameBuf};
+///
+/// let mut data = [Rgb565::BLACK; 320 * 240];
+/// let mut fbuf = FrameBuf::new(&mut data, 320, 240);
+/// let mut checksums = RowChecksums::new([0; 240]);
+/// // Initially, all rows have to be flushed.
+/// assert_eq!(checksums.changed_rows(&fbuf).count(), 240);
+///
+/// // Redraw the whole UI, but only change one row.
+/// fbuf.clear(Rgb565::BLACK).unwrap();
+/// fbuf.set_color_at(Point::new(10, 20), Rgb565::RED);
+/// let mut changed = checksums.changed_rows(&fbuf);
+/// assert_eq!(changed.next(), Some(20));
+/// assert_eq!(changed.next(), None);
+/// ```
+pub struct RowChecksums<S> {
+    checksums: S,
+    /// Whether the checksums are from a previous flush
+    known: bool,
+}
+
+impl<S: AsRef<[u32]> + AsMut<[u32]>> RowChecksums<S> {
+    /// Create checksums for a framebuffer as high as `checksums` is long.
+    /// Initially all rows are considered changed.
+    pub fn new(checksums: S) -> Self {
+        Self {
+            checksums,
+            known: false,
+        }
+    }
+
+    /// Consider all rows changed, e.g. after the display was reset.
+    pub fn invalidate(&mut self) {
+        self.known = false;
+    }
+
+    /// Iterate over the rows of `fbuf` which changed since the last call, and
+    /// remember their new checksums.
+    ///
+    /// The rows are expected to be flushed to the display. Rows not reached
+    /// by the iterator are compared again on the next call.
+    ///
+    /// # Panic
+    /// Panics if the number of checksums doesn't match the height of `fbuf`.
+    pub fn changed_rows<'a, C, B>(&'a mut self, fbuf: &'a FrameBuf<C, B>) -> ChangedRows<'a, C, B>
+    where
+        C: PixelColor,
+        C::Raw: From<C>,
+        <C::Raw as RawData>::Storage: Into<u32>,
+        B: FrameBufferBackend<Color = C>,
+    {
+        assert_eq!(
+            self.checksums.as_ref().len(),
+            fbuf.height(),
+            "RowChecksums length doesn't match the framebuffer height"
+        );
+        let all = !self.known;
+        self.known = true;
+        ChangedRows {
+            checksums: self.checksums.as_mut(),
+            fbuf,
+            all,
+            y: 0,
+        }
+    }
+}
+
+/// Iterator over the changed rows of a framebuffer, see
+/// [`RowChecksums::changed_rows`].
+pub struct ChangedRows<'a, C, B: FrameBufferBackend<Color = C>> {
+    checksums: &'a mut [u32],
+    fbuf: &'a FrameBuf<C, B>,
+    /// Whether all rows are changed
+    all: bool,
+    y: usize,
+}
+
+impl<C, B> Iterator for ChangedRows<'_, C, B>
+where
+    C: PixelColor,
+    C::Raw: From<C>,
+    <C::Raw as RawData>::Storage: Into<u32>,
+    B: FrameBufferBackend<Color = C>,
+{
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.y < self.checksums.len() {
+            let y = self.y;
+            self.y += 1;
+            let row = Rectangle::new(
+                Point::new(0, y as i32),
+                Size::new(self.fbuf.width() as u32, 1),
+            );
+            let checksum = checksum(self.fbuf, row);
+            let changed = self.all || checksum != self.checksums[y];
+            self.checksums[y] = checksum;
+            if changed {
+                return Some(y);
+            }
+        }
+        None
+    }
+}
+
+/// The number of tiles of `tile_size` covering a framebuffer of `size`, i.e.
+/// the number of checksums of [`TileChecksums`].
+pub const fn tile_count(size: Size, tile_size: Size) -> usize {
+    (size.width.div_ceil(tile_size.width) * size.height.div_ceil(tile_size.height)) as usize
+}
+
+/// Checksums of a grid of tiles of a framebuffer at the last flush.
+///
+/// The checksums are stored in `S`, one `u32` per tile, row by row. The
+/// tiles at the right and bottom edges are smaller if the framebuffer size
+/// isn't a multiple of the tile size, see [`tile_count`].
+///
+/// # Example
+/// ```rust
+/// use embedded_graphics::{pixelcolor::Rgb565, prelude::*, primitives::Rectangle};
+/// use embedded_graphics_framebuf::{
+///     checksum::{tile_count, TileChecksums},
+///     FrameBuf,
+/// };
+///
+/// let mut data = [Rgb565::BLACK; 320 * 240];
+/// let mut fbuf = FrameBuf::new(&mut data, 320, 240);
+/// const TILES: usize = tile_count(Size::new(320, 240), Size::new(16, 16));
+/// let mut checksums = TileChecksums::new([0; TILES], Size::new(16, 16));
+/// assert_eq!(checksums.changed_tiles(&fbuf).count(), 20 * 15);
+///
+/// // Redraw the whole UI, but only change one pixel.
+/// fbuf.clear(Rgb565::BLACK).unwrap();
+/// fbuf.set_color_at(Point::new(40, 20), Rgb565::RED);
+/// let mut changed = checksums.changed_tiles(&fbuf);
+/// assert_eq!(changed.next(), Some(Rectangle::new(Point::new(32, 16), Size::new(16, 16))));
+/// assert_eq!(changed.next(), None);
+/// ```
+pub struct TileChecksums<S> {
+    checksums: S,
+    tile_size: Size,
+    /// Whether the checksums are from a previous flush
+    known: bool,
+}
+
+impl<S: AsRef<[u32]> + AsMut<[u32]>> TileChecksums<S> {
+    /// Create checksums for tiles of `tile_size`, see [`tile_count`] for the
+    /// number of checksums. Initially all tiles are considered changed.
+    ///
+    /// # Panic
+    /// Panics if `tile_size` is zero sized.
+    pub fn new(checksums: S, tile_size: Size) -> Self {
+        assert!(
+            tile_size.width > 0 && tile_size.height > 0,
+            "TileChecksums tiles can't be zero sized"
+        );
+        Self {
+            checksums,
+            tile_size,
+            known: false,
+        }
+    }
+
+    /// Consider all tiles changed, e.g. after the display was reset.
+    pub fn invalidate(&mut self) {
+        self.known = false;
+    }
+
+    /// Iterate over the areas of the tiles of `fbuf` which changed since the
+    /// last call, row by row, and remember their new checksums.
+    ///
+    /// The tiles are expected to be flushed to the display. Tiles not reached
+    /// by the iterator are compared again on the next call.
+    ///
+    /// # Panic
+    /// Panics if the number of checksums doesn't match the number of tiles of
+    /// `fbuf`.
+    pub fn changed_tiles<'a, C, B>(&'a mut self, fbuf: &'a FrameBuf<C, B>) -> ChangedTiles<'a, C, B>
+    where
+        C: PixelColor,
+        C::Raw: From<C>,
+        <C::Raw as RawData>::Storage: Into<u32>,
+        B: FrameBufferBackend<Color = C>,
+    {
+        assert_eq!(
+            self.checksums.as_ref().len(),
+            tile_count(fbuf.size(), self.tile_size),
+            "TileChecksums length doesn't match the number of tiles"
+        );
+        let all = !self.known;
+        self.known = true;
+        ChangedTiles {
+            checksums: self.checksums.as_mut(),
+            tile_size: self.tile_size,
+            fbuf,
+            all,
+            index: 0,
+        }
+    }
+}
+
+/// Iterator over the areas of the changed tiles of a framebuffer, see
+/// [`TileChecksums::changed_tiles`].
+pub struct ChangedTiles<'a, C, B: FrameBufferBackend<Color = C>> {
+    checksums: &'a mut [u32],
+    tile_size: Size,
+    fbuf: &'a FrameBuf<C, B>,
+    /// Whether all tiles are changed
+    all: bool,
+    index: usize,
+}
+
+impl<C, B> Iterator for ChangedTiles<'_, C, B>
+where
+    C: PixelColor,
+    C::Raw: From<C>,
+    <C::Raw as RawData>::Storage: Into<u32>,
+    B: FrameBufferBackend<Color = C>,
+{
+    type Item = Rectangle;
+
+    fn next(&mut self) -> Option<Rectangle> {
+        let columns = self.fbuf.width().div_ceil(self.tile_size.width as usize);
+        let bounds = Rectangle::new(Point::zero(), self.fbuf.size());
+        while self.index < self.checksums.len() {
+            let index = self.index;
+            self.index += 1;
+            let tile = Point::new((index % columns) as i32, (index / columns) as i32);
+            let area = Rectangle::new(
+                Point::new(
+                    tile.x * self.tile_size.width as i32,
+                    tile.y * self.tile_size.height as i32,
+                ),
+                self.tile_size,
+            )
+            .intersection(&bounds);
+            let checksum = checksum(self.fbuf, area);
+            let changed = self.all || checksum != self.checksums[index];
+            self.checksums[index] = checksum;
+            if changed {
+                return Some(area);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::pixelcolor::BinaryColor;
+
+    #[test]
+    fn finds_changed_rows() {
+        let mut data = [BinaryColor::Off; 3 * 4];
+        let mut fbuf = FrameBuf::new(&mut data, 3, 4);
+        let mut checksums = RowChecksums::new([0; 4]);
+        assert_eq!(checksums.changed_rows(&fbuf).count(), 4);
+        assert_eq!(checksums.changed_rows(&fbuf).next(), None);
+
+        fbuf.set_color_at(Point::new(2, 1), BinaryColor::On);
+        fbuf.set_color_at(Point::new(0, 3), BinaryColor::On);
+        assert_eq!(checksums.changed_rows(&fbuf).next(), Some(1));
+        // Row 3 wasn't reached
+        assert_eq!(checksums.changed_rows(&fbuf).next(), Some(3));
+
+        // Moving a pixel within a row changes its checksum
+        fbuf.set_color_at(Point::new(2, 1), BinaryColor::Off);
+        fbuf.set_color_at(Point::new(1, 1), BinaryColor::On);
+        assert_eq!(checksums.changed_rows(&fbuf).next(), Some(1));
+
+        checksums.invalidate();
+        assert_eq!(checksums.changed_rows(&fbuf).count(), 4);
+    }
+
+    #[test]
+    fn finds_changed_tiles() {
+        let mut data = [BinaryColor::Off; 5 * 3];
+        let mut fbuf = FrameBuf::new(&mut data, 5, 3);
+        let tile = Size::new(2, 2);
+        assert_eq!(tile_count(fbuf.size(), tile), 6);
+        let mut checksums = TileChecksums::new([0; 6], tile);
+        assert_eq!(checksums.changed_tiles(&fbuf).count(), 6);
+        assert_eq!(checksums.changed_tiles(&fbuf).next(), None);
+
+        fbuf.set_color_at(Point::new(4, 2), BinaryColor::On);
+        fbuf.set_color_at(Point::new(1, 0), BinaryColor::On);
+        fbuf.set_color_at(Point::new(2, 1), BinaryColor::On);
+        fbuf.set_color_at(Point::new(2, 1), BinaryColor::Off);
+        let mut changed = checksums.changed_tiles(&fbuf);
+        assert_eq!(
+            changed.next(),
+            Some(Rectangle::new(Point::zero(), Size::new(2, 2)))
+        );
+        assert_eq!(
+            changed.next(),
+            Some(Rectangle::new(Point::new(4, 2), Size::new(1, 1)))
+        );
+        assert_eq!(changed.next(), None);
+    }
+
+    #[test]
+    fn detects_changed_frames() {
+        let mut data = [BinaryColor::Off; 3 * 4];
+        let mut fbuf = FrameBuf::new(&mut data, 3, 4);
+        let mut hash = FrameHash::new();
+        assert!(hash.has_changed_since_last_flush(&fbuf));
+        assert!(!hash.has_changed_since_last_flush(&fbuf));
+
+        fbuf.set_color_at(Point::new(2, 1), BinaryColor::On);
+        let changed = fbuf.frame_hash();
+        assert!(hash.has_changed_since_last_flush(&fbuf));
+        fbuf.set_color_at(Point::new(2, 1), BinaryColor::Off);
+        fbuf.set_color_at(Point::new(0, 2), BinaryColor::On);
+        assert_ne!(fbuf.frame_hash(), changed);
+        assert!(hash.has_changed_since_last_flush(&fbuf));
+
+        hash.invalidate();
+        assert!(hash.has_changed_since_last_flush(&fbuf));
+    }
+
+    #[test]
+    #[should_panic]
+    fn checks_tile_count() {
+        let mut data = [BinaryColor::Off; 3 * 4];
+        let fbuf = FrameBuf::new(&mut data, 3, 4);
+        TileChecksums::new([0; 3], Size::new(2, 2)).changed_tiles(&fbuf);
+    }
+
+    #[test]
+    #[should_panic]
+    fn checks_height() {
+        let mut data = [BinaryColor::Off; 3 * 4];
+        let fbuf = FrameBuf::new(&mut data, 3, 4);
+        RowChecksums::new([0; 3]).changed_rows(&fbuf);
+    }
+}
@@ -0,0 +1,359 @@
+//! Additional color types.
+
+use embedded_graphics::pixelcolor::{
+    raw::{RawData, RawU16, RawU2, RawU32},
+    IntoStorage, PixelColor, Rgb565, Rgb888, RgbColor,
+};
+
+/// An [`Rgb565`] color stored in big endian byte order.
+///
+/// Most SPI display controllers expect the high byte of a pixel first, while
+/// most MCUs are little endian. A buffer of `Rgb565BE` can be sent to such a
+/// display (e.g. via DMA) as is, without the per-access conversion of
+/// [`EndianCorrectedBuffer`](crate::backends::EndianCorrectedBuffer).
+///
+/// The raw data and storage of the color (see [`IntoStorage`]) are the
+/// logical 16 bit value, only the in-memory representation is swapped.
+///
+/// ```rust
+/// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+/// use embedded_graphics_framebuf::{color::Rgb565BE, FrameBuf};
+///
+/// let mut data = [Rgb565BE::BLACK; 2 * 2];
+/// let mut fbuf = FrameBuf::new(&mut data, 2, 2);
+/// fbuf.set_color_at(Point::new(0, 0), Rgb565::RED.into());
+/// let bytes = unsafe { core::slice::from_raw_parts(data.as_ptr() as *const u8, 8) };
+/// assert_eq!(bytes[..2], [0xF8, 0x00]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(transparent)]
+pub struct Rgb565BE(u16);
+
+impl Rgb565BE {
+    /// Create a color from its channels, like [`Rgb565::new`].
+    pub fn new(r: u8, g: u8, b: u8) -> Self {
+        Rgb565::new(r, g, b).into()
+    }
+
+    const fn from_storage(value: u16) -> Self {
+        Self(value.to_be())
+    }
+
+    fn storage(self) -> u16 {
+        u16::from_be(self.0)
+    }
+}
+
+// SAFETY: `Rgb565BE` is a `repr(transparent)` `u16`, valid for all bit
+// patterns.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Rgb565BE {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Rgb565BE {}
+
+impl PixelColor for Rgb565BE {
+    type Raw = RawU16;
+}
+
+impl From<Rgb565> for Rgb565BE {
+    fn from(color: Rgb565) -> Self {
+        Self::from_storage(color.into_storage())
+    }
+}
+
+impl From<Rgb565BE> for Rgb565 {
+    fn from(color: Rgb565BE) -> Self {
+        RawU16::new(color.storage()).into()
+    }
+}
+
+impl From<RawU16> for Rgb565BE {
+    fn from(raw: RawU16) -> Self {
+        Rgb565::from(raw).into()
+    }
+}
+
+impl From<Rgb565BE> for RawU16 {
+    fn from(color: Rgb565BE) -> Self {
+        RawU16::new(color.storage())
+    }
+}
+
+impl RgbColor for Rgb565BE {
+    fn r(&self) -> u8 {
+        Rgb565::from(*self).r()
+    }
+
+    fn g(&self) -> u8 {
+        Rgb565::from(*self).g()
+    }
+
+    fn b(&self) -> u8 {
+        Rgb565::from(*self).b()
+    }
+
+    const MAX_R: u8 = Rgb565::MAX_R;
+    const MAX_G: u8 = Rgb565::MAX_G;
+    const MAX_B: u8 = Rgb565::MAX_B;
+
+    const BLACK: Self = Self::from_storage(0x0000);
+    const RED: Self = Self::from_storage(0xF800);
+    const GREEN: Self = Self::from_storage(0x07E0);
+    const BLUE: Self = Self::from_storage(0x001F);
+    const YELLOW: Self = Self::from_storage(0xFFE0);
+    const MAGENTA: Self = Self::from_storage(0xF81F);
+    const CYAN: Self = Self::from_storage(0x07FF);
+    const WHITE: Self = Self::from_storage(0xFFFF);
+}
+
+/// An [`Rgb888`] color with an 8 bit alpha channel.
+///
+/// An alpha of `0` is fully transparent and `255` is opaque. The raw data of
+/// the color is `0xAARRGGBB`. Draw it through a
+/// [`Blended`](crate::blend::Blended) adapter to blend it onto a framebuffer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Argb8888 {
+    alpha: u8,
+    rgb: Rgb888,
+}
+
+impl Argb8888 {
+    /// A fully transparent color.
+    pub const TRANSPARENT: Self = Self::new(0, 0, 0, 0);
+
+    /// Create a color from its alpha and color channels.
+    pub const fn new(alpha: u8, r: u8, g: u8, b: u8) -> Self {
+        Self {
+            alpha,
+            rgb: Rgb888::new(r, g, b),
+        }
+    }
+
+    /// Create a color from an [`Rgb888`] color and an alpha.
+    pub const fn with_alpha(rgb: Rgb888, alpha: u8) -> Self {
+        Self { alpha, rgb }
+    }
+
+    /// The alpha channel.
+    pub fn alpha(self) -> u8 {
+        self.alpha
+    }
+
+    /// The color without the alpha channel.
+    pub fn rgb(self) -> Rgb888 {
+        self.rgb
+    }
+}
+
+impl PixelColor for Argb8888 {
+    type Raw = RawU32;
+}
+
+impl From<Rgb888> for Argb8888 {
+    /// An opaque color.
+    fn from(rgb: Rgb888) -> Self {
+        Self::with_alpha(rgb, 255)
+    }
+}
+
+impl From<RawU32> for Argb8888 {
+    fn from(raw: RawU32) -> Self {
+        let [alpha, r, g, b] = raw.into_inner().to_be_bytes();
+        Self::new(alpha, r, g, b)
+    }
+}
+
+impl From<Argb8888> for RawU32 {
+    fn from(color: Argb8888) -> Self {
+        let Argb8888 { alpha, rgb } = color;
+        RawU32::new(u32::from_be_bytes([alpha, rgb.r(), rgb.g(), rgb.b()]))
+    }
+}
+
+/// An [`Argb8888`] color with the color channels premultiplied by alpha.
+///
+/// A framebuffer of premultiplied colors can be used as a layer with per-pixel
+/// alpha: colors are composed onto it with
+/// [`FrameBuf::over`](crate::FrameBuf::over), and it is flattened onto an
+/// opaque scene with [`FrameBuf::flatten`](crate::FrameBuf::flatten), without
+/// a division per pixel. The raw data of the color is `0xAARRGGBB`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PremultipliedArgb8888 {
+    alpha: u8,
+    rgb: Rgb888,
+}
+
+impl PremultipliedArgb8888 {
+    /// A fully transparent color.
+    pub const TRANSPARENT: Self = Self::new(0, 0, 0, 0);
+
+    /// Create a color from its alpha and premultiplied color channels, which
+    /// must not exceed `alpha`.
+    pub const fn new(alpha: u8, r: u8, g: u8, b: u8) -> Self {
+        Self {
+            alpha,
+            rgb: Rgb888::new(r, g, b),
+        }
+    }
+
+    /// The alpha channel.
+    pub fn alpha(self) -> u8 {
+        self.alpha
+    }
+
+    /// The premultiplied color channels.
+    pub fn rgb(self) -> Rgb888 {
+        self.rgb
+    }
+}
+
+impl PixelColor for PremultipliedArgb8888 {
+    type Raw = RawU32;
+}
+
+impl From<Argb8888> for PremultipliedArgb8888 {
+    fn from(color: Argb8888) -> Self {
+        let alpha = color.alpha() as u32;
+        let mul = |c: u8| ((c as u32 * alpha + 127) / 255) as u8;
+        let rgb = color.rgb();
+        Self::new(color.alpha(), mul(rgb.r()), mul(rgb.g()), mul(rgb.b()))
+    }
+}
+
+impl From<PremultipliedArgb8888> for Argb8888 {
+    fn from(color: PremultipliedArgb8888) -> Self {
+        let alpha = color.alpha() as u32;
+        let div = |c: u8| match alpha {
+            0 => 0,
+            _ => ((c as u32 * 255 + alpha / 2) / alpha).min(255) as u8,
+        };
+        let rgb = color.rgb();
+        Self::new(color.alpha(), div(rgb.r()), div(rgb.g()), div(rgb.b()))
+    }
+}
+
+impl From<Rgb888> for PremultipliedArgb8888 {
+    /// An opaque color.
+    fn from(rgb: Rgb888) -> Self {
+        Self { alpha: 255, rgb }
+    }
+}
+
+impl From<RawU32> for PremultipliedArgb8888 {
+    fn from(raw: RawU32) -> Self {
+        let [alpha, r, g, b] = raw.into_inner().to_be_bytes();
+        Self::new(alpha, r, g, b)
+    }
+}
+
+impl From<PremultipliedArgb8888> for RawU32 {
+    fn from(color: PremultipliedArgb8888) -> Self {
+        let PremultipliedArgb8888 { alpha, rgb } = color;
+        RawU32::new(u32::from_be_bytes([alpha, rgb.r(), rgb.g(), rgb.b()]))
+    }
+}
+
+/// The colors of a black, white and red (or yellow) e-paper display.
+///
+/// Stored in two planes by [`TriColorBuffer`](crate::backends::TriColorBuffer).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TriColor {
+    #[default]
+    White,
+    Black,
+    /// The third color of the panel, red or yellow.
+    Red,
+}
+
+impl PixelColor for TriColor {
+    type Raw = RawU2;
+}
+
+impl From<RawU2> for TriColor {
+    fn from(raw: RawU2) -> Self {
+        match raw.into_inner() {
+            0 => TriColor::White,
+            1 => TriColor::Black,
+            _ => TriColor::Red,
+        }
+    }
+}
+
+impl From<TriColor> for RawU2 {
+    fn from(color: TriColor) -> Self {
+        RawU2::new(color as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::pixelcolor::raw::ToBytes;
+
+    #[test]
+    fn converts_from_and_to_rgb565() {
+        for color in [
+            Rgb565::BLACK,
+            Rgb565::RED,
+            Rgb565::GREEN,
+            Rgb565::BLUE,
+            Rgb565::YELLOW,
+            Rgb565::MAGENTA,
+            Rgb565::CYAN,
+            Rgb565::WHITE,
+            Rgb565::new(1, 2, 3),
+        ] {
+            let be = Rgb565BE::from(color);
+            assert_eq!(Rgb565::from(be), color);
+            assert_eq!(be.into_storage(), color.into_storage());
+            assert_eq!((be.r(), be.g(), be.b()), (color.r(), color.g(), color.b()));
+            assert_eq!(be.to_be_bytes(), color.to_be_bytes());
+        }
+        assert_eq!(Rgb565BE::MAGENTA, Rgb565::MAGENTA.into());
+        assert_eq!(Rgb565BE::new(1, 2, 3), Rgb565::new(1, 2, 3).into());
+    }
+
+    #[test]
+    fn stores_big_endian() {
+        let color = Rgb565BE::from(Rgb565::RED);
+        let bytes: [u8; 2] = unsafe { core::mem::transmute(color) };
+        assert_eq!(bytes, [0xF8, 0x00]);
+    }
+
+    #[test]
+    fn converts_argb_from_and_to_raw() {
+        let color = Argb8888::new(0x80, 0x12, 0x34, 0x56);
+        assert_eq!(RawU32::from(color).into_inner(), 0x8012_3456);
+        assert_eq!(Argb8888::from(RawU32::new(0x8012_3456)), color);
+        assert_eq!(Argb8888::from(Rgb888::RED).alpha(), 255);
+        assert_eq!(color.rgb(), Rgb888::new(0x12, 0x34, 0x56));
+    }
+
+    #[test]
+    fn premultiplies_alpha() {
+        let color = Argb8888::new(0x80, 0xFF, 0x80, 0x00);
+        let premultiplied = PremultipliedArgb8888::from(color);
+        assert_eq!(
+            premultiplied,
+            PremultipliedArgb8888::new(0x80, 0x80, 0x40, 0)
+        );
+        assert_eq!(Argb8888::from(premultiplied), color);
+        assert_eq!(
+            Argb8888::from(PremultipliedArgb8888::TRANSPARENT),
+            Argb8888::TRANSPARENT
+        );
+        assert_eq!(RawU32::from(premultiplied).into_inner(), 0x8080_4000);
+        assert_eq!(
+            PremultipliedArgb8888::from(RawU32::new(0x8080_4000)),
+            premultiplied
+        );
+    }
+
+    #[test]
+    fn converts_tri_color_from_and_to_raw() {
+        for color in [TriColor::White, TriColor::Black, TriColor::Red] {
+            assert_eq!(TriColor::from(RawU2::from(color)), color);
+        }
+        assert_eq!(RawU2::from(TriColor::Red).into_inner(), 2);
+    }
+}
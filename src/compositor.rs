@@ -0,0 +1,292 @@
+//! Composition of layered framebuffers.
+//!
+//! Dashboards often consist of a static background, a UI redrawn now and
+//! then and a small, fast moving overlay like a cursor. Keeping them in
+//! separate framebuffers and composing them on the way out means that moving
+//! the cursor doesn't require redrawing the UI below it.
+//!
+//! A [`Compositor`] holds the [`LayerSettings`] (position, transparency,
+//! visibility) of `N` layers, ordered from the bottom to the top. The layers
+//! themselves are passed when composing, so they can be drawn into between
+//! two frames:
+//!
+//! ```rust
+//! use embedded_graphics::{pixelcolor::Rgb565, prelude::*, primitives::Rectangle};
+//! use embedded_graphics_framebuf::{
+//!     compositor::{Blending, Compositor},
+//!     FrameBuf,
+//! };
+//!
+//! let mut background_data = [Rgb565::BLUE; 64 * 32];
+//! let background = FrameBuf::new(&mut background_data, 64, 32);
+//! let mut ui_data = [Rgb565::MAGENTA; 64 * 32];
+//! let mut ui = FrameBuf::new(&mut ui_data, 64, 32);
+//! let mut cursor_data = [Rgb565::WHITE; 4 * 4];
+//! let cursor = FrameBuf::new(&mut cursor_data, 4, 4);
+//!
+//! let mut compositor = Compositor::new(Size::new(64, 32), Rgb565::BLACK);
+//! compositor.layer_mut(1).blending = Blending::ColorKey(Rgb565::MAGENTA);
+//! compositor.layer_mut(2).blending = Blending::Alpha(128);
+//!
+//! ui.set_color_at(Point::new(10, 10), Rgb565::RED);
+//! compositor.layer_mut(2).position = Point::new(9, 9);
+//!
+//! let mut data = [Rgb565::BLACK; 64 * 32];
+//! let mut output = FrameBuf::new(&mut data, 64, 32);
+//! compositor.compose_into(&[&background, &ui, &cursor], &mut output);
+//! assert_eq!(output.get_color_at(Point::new(0, 0)), Rgb565::BLUE);
+//! assert_eq!(output.get_color_at(Point::new(10, 10)), Rgb565::new(31, 32, 16));
+//!
+//! // Move the cursor, and only recompose the areas it left and entered
+//! let old = Rectangle::new(compositor.layer(2).position, cursor.size());
+//! compositor.layer_mut(2).position = Point::new(20, 9);
+//! let new = Rectangle::new(compositor.layer(2).position, cursor.size());
+//! for area in [old, new] {
+//!     compositor.compose_area_into(area, &[&background, &ui, &cursor], &mut output);
+//! }
+//! assert_eq!(output.get_color_at(Point::new(10, 10)), Rgb565::RED);
+//! ```
+
+use embedded_graphics::{
+    geometry::{Dimensions, OriginDimensions},
+    pixelcolor::Rgb888,
+    prelude::{PixelColor, Point, Size},
+    primitives::{PointsIter, Rectangle},
+    Pixel,
+};
+
+use crate::{backends::FrameBufferBackend, blend::blend, FrameBuf};
+
+/// A layer of a [`Compositor`].
+///
+/// Implemented by all framebuffers, so layers with different backends can be
+/// composed.
+pub trait Layer<C>: OriginDimensions {
+    /// The color of the pixel at `p`, which is within the layer's size.
+    fn color_at(&self, p: Point) -> C;
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> Layer<C> for FrameBuf<C, B> {
+    fn color_at(&self, p: Point) -> C {
+        self.get_color_at(p)
+    }
+}
+
+/// How a layer is combined with the layers below it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Blending<C> {
+    /// The layer covers the layers below
+    Opaque,
+    /// Pixels with this color are transparent
+    ColorKey(C),
+    /// The layer is blended with this opacity, from `0` (invisible) to `255`
+    /// (opaque)
+    Alpha(u8),
+}
+
+/// The settings of a layer of a [`Compositor`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LayerSettings<C> {
+    /// The position of the layer's top left corner in the output
+    pub position: Point,
+    pub blending: Blending<C>,
+    pub visible: bool,
+}
+
+/// Composes `N` layers into an output of a fixed size, see the
+/// [module documentation](self).
+pub struct Compositor<C, const N: usize> {
+    size: Size,
+    background: C,
+    layers: [LayerSettings<C>; N],
+}
+
+impl<C, const N: usize> Compositor<C, N>
+where
+    C: PixelColor + Into<Rgb888> + From<Rgb888>,
+{
+    /// Create a compositor for an output of `size`, where pixels not covered
+    /// by any layer are `background`.
+    ///
+    /// All layers start out visible and opaque at the top left corner.
+    pub fn new(size: Size, background: C) -> Self {
+        Self {
+            size,
+            background,
+            layers: [LayerSettings {
+                position: Point::zero(),
+                blending: Blending::Opaque,
+                visible: true,
+            }; N],
+        }
+    }
+
+    /// The settings of the layer `index`, `0` being the bottom layer.
+    pub fn layer(&self, index: usize) -> &LayerSettings<C> {
+        &self.layers[index]
+    }
+
+    /// Change the settings of the layer `index`, `0` being the bottom layer.
+    pub fn layer_mut(&mut self, index: usize) -> &mut LayerSettings<C> {
+        &mut self.layers[index]
+    }
+
+    /// The composed color of the output pixel at `p`.
+    pub fn color_at(&self, p: Point, layers: &[&dyn Layer<C>; N]) -> C {
+        let mut color = self.background;
+        for (settings, layer) in self.layers.iter().zip(layers) {
+            let local = p - settings.position;
+            if !settings.visible || !Rectangle::new(Point::zero(), layer.size()).contains(local) {
+                continue;
+            }
+            let layer_color = layer.color_at(local);
+            color = match settings.blending {
+                Blending::Opaque => layer_color,
+                Blending::ColorKey(key) if layer_color == key => color,
+                Blending::ColorKey(_) => layer_color,
+                Blending::Alpha(alpha) => blend(color.into(), layer_color.into(), alpha).into(),
+            };
+        }
+        color
+    }
+
+    /// Iterate over the composed pixels of the whole output, e.g. to pass
+    /// them to
+    /// [`DrawTarget::draw_iter`](embedded_graphics::draw_target::DrawTarget::draw_iter)
+    /// of the display.
+    pub fn pixels<'a>(&'a self, layers: &'a [&'a dyn Layer<C>; N]) -> Composited<'a, C, N> {
+        self.area_pixels(self.bounding_box(), layers)
+    }
+
+    /// Iterate over the composed pixels of `area`, clipped to the output.
+    pub fn area_pixels<'a>(
+        &'a self,
+        area: Rectangle,
+        layers: &'a [&'a dyn Layer<C>; N],
+    ) -> Composited<'a, C, N> {
+        Composited {
+            compositor: self,
+            layers,
+            points: area.intersection(&self.bounding_box()).points(),
+        }
+    }
+
+    /// Compose the whole output into `dest`.
+    pub fn compose_into<B>(&self, layers: &[&dyn Layer<C>; N], dest: &mut FrameBuf<C, B>)
+    where
+        B: FrameBufferBackend<Color = C>,
+    {
+        self.compose_area_into(self.bounding_box(), layers, dest);
+    }
+
+    /// Compose `area` of the output into `dest`, e.g. only the areas a moving
+    /// layer left and entered. Pixels hidden by the [mask](crate::mask) of
+    /// `dest` are left untouched.
+    pub fn compose_area_into<B>(
+        &self,
+        area: Rectangle,
+        layers: &[&dyn Layer<C>; N],
+        dest: &mut FrameBuf<C, B>,
+    ) where
+        B: FrameBufferBackend<Color = C>,
+    {
+        let area = area
+            .intersection(&self.bounding_box())
+            .intersection(&dest.bounding_box());
+        for p in area.points() {
+            if dest.is_visible(p) {
+                dest.set_color_at(p, self.color_at(p, layers));
+            }
+        }
+        dest.mark_dirty(area);
+    }
+}
+
+impl<C, const N: usize> OriginDimensions for Compositor<C, N> {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+/// Iterator over composed pixels, see [`Compositor::pixels`].
+pub struct Composited<'a, C, const N: usize> {
+    compositor: &'a Compositor<C, N>,
+    layers: &'a [&'a dyn Layer<C>; N],
+    points: embedded_graphics::primitives::rectangle::Points,
+}
+
+impl<C, const N: usize> Iterator for Composited<'_, C, N>
+where
+    C: PixelColor + Into<Rgb888> + From<Rgb888>,
+{
+    type Item = Pixel<C>;
+
+    fn next(&mut self) -> Option<Pixel<C>> {
+        let p = self.points.next()?;
+        Some(Pixel(p, self.compositor.color_at(p, self.layers)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::mask::Mask;
+    use embedded_graphics::pixelcolor::Gray8;
+    use embedded_graphics::prelude::GrayColor;
+    use std::vec::Vec;
+
+    #[test]
+    fn composes_layers() {
+        let mut bottom_data = [Gray8::new(10); 3];
+        let bottom = FrameBuf::new(&mut bottom_data, 3, 1);
+        let mut keyed_data = [Gray8::new(20), Gray8::BLACK];
+        let keyed = FrameBuf::new(&mut keyed_data, 2, 1);
+        let mut translucent_data = [Gray8::new(200)];
+        let translucent = FrameBuf::new(&mut translucent_data, 1, 1);
+
+        let mut compositor = Compositor::new(Size::new(4, 1), Gray8::new(1));
+        compositor.layer_mut(1).blending = Blending::ColorKey(Gray8::BLACK);
+        compositor.layer_mut(1).position = Point::new(1, 0);
+        compositor.layer_mut(2).blending = Blending::Alpha(51);
+        compositor.layer_mut(2).position = Point::new(2, 0);
+        let layers: [&dyn Layer<Gray8>; 3] = [&bottom, &keyed, &translucent];
+
+        let colors: Vec<_> = compositor.pixels(&layers).map(|p| p.1.luma()).collect();
+        assert_eq!(colors, [10, 20, 48, 1]);
+
+        compositor.layer_mut(1).visible = false;
+        assert_eq!(
+            compositor.color_at(Point::new(1, 0), &layers),
+            Gray8::new(10)
+        );
+        let mut data = [Gray8::BLACK; 4];
+        let mut output = FrameBuf::new(&mut data, 4, 1);
+        output.take_dirty_area();
+        compositor.compose_area_into(
+            Rectangle::new(Point::new(1, 0), Size::new(5, 1)),
+            &layers,
+            &mut output,
+        );
+        assert_eq!(
+            output.take_dirty_area(),
+            Some(Rectangle::new(Point::new(1, 0), Size::new(3, 1)))
+        );
+        assert_eq!(data, [0, 10, 48, 1].map(Gray8::new));
+    }
+
+    #[test]
+    fn respects_dest_mask() {
+        let mut bottom_data = [Gray8::new(10); 3];
+        let bottom = FrameBuf::new(&mut bottom_data, 3, 1);
+        let compositor = Compositor::new(Size::new(3, 1), Gray8::new(1));
+        let layers: [&dyn Layer<Gray8>; 1] = [&bottom];
+
+        let mut data = [Gray8::BLACK; 3];
+        let mut output = FrameBuf::new(&mut data, 3, 1);
+        output.set_mask(Some(Mask::Custom(|p| p.x != 1)));
+        compositor.compose_into(&layers, &mut output);
+        assert_eq!(data, [10, 0, 10].map(Gray8::new));
+    }
+}
@@ -0,0 +1,331 @@
+//! Conformance tests for [`FrameBufferBackend`] implementations.
+//!
+//! Third-party backends can run the same battery of tests as the backends of
+//! this crate by enabling the `conformance` feature (e.g. as a
+//! dev-dependency) and invoking [`framebuffer_backend_tests!`](crate::framebuffer_backend_tests):
+//!
+//! ```rust,ignore
+//! #[cfg(test)]
+//! mod tests {
+//!     use super::*;
+//!     use embedded_graphics::{pixelcolor::Rgb565, prelude::RgbColor};
+//!
+//!     embedded_graphics_framebuf::framebuffer_backend_tests!(
+//!         my_backend,
+//!         MyBackend::new(320 * 240),
+//!         Rgb565::RED,
+//!         Rgb565::BLUE
+//!     );
+//! }
+//! ```
+//!
+//! The backend expression is evaluated once per test and must be usable as a
+//! [`FrameBuf`] of `nr_elements()` x 1 pixels.
+
+use core::fmt::Debug;
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    prelude::{Point, Size},
+    primitives::Rectangle,
+};
+
+use crate::{backends::FrameBufferBackend, FrameBuf};
+
+/// Generates a test module `$name` running all conformance checks against the
+/// backend created by `$backend`. `$a` and `$b` must be two distinct colors.
+#[macro_export]
+macro_rules! framebuffer_backend_tests {
+    ($name:ident, $backend:expr, $a:expr, $b:expr) => {
+        mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #[test]
+            fn set_get() {
+                $crate::conformance::set_get($backend, $a, $b);
+            }
+
+            #[test]
+            fn independent_pixels() {
+                $crate::conformance::independent_pixels($backend, $a, $b);
+            }
+
+            #[test]
+            fn fill() {
+                $crate::conformance::fill($backend, $a, $b);
+            }
+
+            #[test]
+            fn draw_target() {
+                $crate::conformance::draw_target($backend, $a, $b);
+            }
+
+            #[test]
+            #[should_panic]
+            fn set_out_of_bounds() {
+                $crate::conformance::set_out_of_bounds($backend, $a);
+            }
+
+            #[test]
+            #[should_panic]
+            fn get_out_of_bounds() {
+                $crate::conformance::get_out_of_bounds($backend);
+            }
+        }
+    };
+}
+
+/// Every pixel reads back the color last written to it, also through the
+/// contiguous view if there is one.
+pub fn set_get<B: FrameBufferBackend>(mut backend: B, a: B::Color, b: B::Color)
+where
+    B::Color: Debug,
+{
+    assert_ne!(a, b, "conformance tests need two distinct colors");
+    assert!(backend.nr_elements() > 0, "backend is empty");
+    for color in [a, b, a] {
+        for i in 0..backend.nr_elements() {
+            backend.set(i, color);
+            assert_eq!(backend.get(i), color, "pixel {} doesn't read back", i);
+        }
+    }
+    let len = backend.nr_elements();
+    if let Some(pixels) = backend.as_contiguous_mut() {
+        assert_eq!(pixels.len(), len, "contiguous pixels have the wrong length");
+        pixels[len - 1] = b;
+        assert_eq!(
+            backend.get(len - 1),
+            b,
+            "contiguous pixels aren't the storage"
+        );
+    }
+    backend.set(0, b);
+    if let Some(pixels) = backend.as_contiguous() {
+        assert_eq!(pixels[0], b, "contiguous pixels aren't the storage");
+    }
+}
+
+/// Writing a pixel doesn't change any other pixel.
+pub fn independent_pixels<B: FrameBufferBackend>(mut backend: B, a: B::Color, b: B::Color)
+where
+    B::Color: Debug,
+{
+    let len = backend.nr_elements();
+    for i in 0..len {
+        backend.set(i, a);
+    }
+    for i in 0..len {
+        backend.set(i, b);
+        for j in [i.wrapping_sub(1), i + 1, 0, len - 1] {
+            if j < len && j != i {
+                assert_eq!(backend.get(j), a, "writing pixel {} changed pixel {}", i, j);
+            }
+        }
+        backend.set(i, a);
+    }
+}
+
+/// Filling all pixels with one color.
+pub fn fill<B: FrameBufferBackend>(mut backend: B, a: B::Color, b: B::Color)
+where
+    B::Color: Debug,
+{
+    for color in [a, b] {
+        for i in 0..backend.nr_elements() {
+            backend.set(i, color);
+        }
+        for i in 0..backend.nr_elements() {
+            assert_eq!(backend.get(i), color, "pixel {} not filled", i);
+        }
+    }
+    for color in [a, b] {
+        backend.fill(color);
+        for i in 0..backend.nr_elements() {
+            assert_eq!(backend.get(i), color, "pixel {} not bulk filled", i);
+        }
+    }
+    let len = backend.nr_elements();
+    backend.fill_range(len / 2..len, a);
+    for i in 0..len {
+        let color = if i < len / 2 { b } else { a };
+        assert_eq!(backend.get(i), color, "pixel {} not range filled", i);
+    }
+}
+
+/// The backend works as storage of a [`FrameBuf`].
+pub fn draw_target<B: FrameBufferBackend>(backend: B, a: B::Color, b: B::Color)
+where
+    B::Color: Debug,
+{
+    let width = backend.nr_elements();
+    let mut fbuf = FrameBuf::new(backend, width, 1);
+    fbuf.clear(a).unwrap();
+    let last = Point::new(width as i32 - 1, 0);
+    fbuf.fill_solid(&Rectangle::new(last, Size::new(2, 1)), b)
+        .unwrap();
+    assert_eq!(fbuf.get_color_at(last), b);
+    if width > 1 {
+        assert_eq!(fbuf.get_color_at(Point::zero()), a);
+    }
+}
+
+/// Writing past the end of the backend must panic instead of corrupting memory.
+pub fn set_out_of_bounds<B: FrameBufferBackend>(mut backend: B, a: B::Color) {
+    let len = backend.nr_elements();
+    backend.set(len, a);
+}
+
+/// Reading past the end of the backend must panic.
+pub fn get_out_of_bounds<B: FrameBufferBackend>(backend: B) {
+    let len = backend.nr_elements();
+    backend.get(len);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backends::{EndianCorrectedBuffer, EndianCorrection};
+    use embedded_graphics::pixelcolor::{BinaryColor, Rgb565};
+    use embedded_graphics::prelude::RgbColor;
+
+    framebuffer_backend_tests!(
+        array,
+        [BinaryColor::Off; 10],
+        BinaryColor::On,
+        BinaryColor::Off
+    );
+    framebuffer_backend_tests!(
+        array_ref,
+        &mut [Rgb565::BLACK; 10],
+        Rgb565::RED,
+        Rgb565::BLUE
+    );
+    framebuffer_backend_tests!(
+        slice,
+        &mut [Rgb565::BLACK; 10][..7],
+        Rgb565::RED,
+        Rgb565::BLUE
+    );
+    framebuffer_backend_tests!(
+        endian_corrected,
+        EndianCorrectedBuffer::new(&mut [Rgb565::BLACK; 10], EndianCorrection::ToBigEndian),
+        Rgb565::RED,
+        Rgb565::BLUE
+    );
+    framebuffer_backend_tests!(
+        endian_corrected_rgb888,
+        EndianCorrectedBuffer::new(
+            &mut [embedded_graphics::pixelcolor::Rgb888::BLACK; 10],
+            EndianCorrection::ToLittleEndian
+        ),
+        embedded_graphics::pixelcolor::Rgb888::RED,
+        embedded_graphics::pixelcolor::Rgb888::BLUE
+    );
+    framebuffer_backend_tests!(
+        page_buffer,
+        crate::backends::PageBuffer::new(&mut [0u8; 2], 1),
+        BinaryColor::On,
+        BinaryColor::Off
+    );
+    framebuffer_backend_tests!(
+        tri_color,
+        crate::backends::TriColorBuffer::new([0u8; 3], [0u8; 3], 20),
+        crate::color::TriColor::Red,
+        crate::color::TriColor::Black
+    );
+    framebuffer_backend_tests!(
+        packed_binary,
+        crate::backends::PackedBinaryBuffer::new(
+            &mut [0u8; 2],
+            16,
+            crate::backends::BitOrder::MsbFirst
+        ),
+        BinaryColor::On,
+        BinaryColor::Off
+    );
+    framebuffer_backend_tests!(
+        indexed,
+        crate::backends::IndexedBuffer::new(&mut [0u8; 10], [Rgb565::RED, Rgb565::BLUE]),
+        Rgb565::RED,
+        Rgb565::BLUE
+    );
+    framebuffer_backend_tests!(
+        transparent,
+        crate::backends::TransparentBuffer::new([Rgb565::BLACK; 10], Rgb565::MAGENTA),
+        Rgb565::RED,
+        Rgb565::BLUE
+    );
+    framebuffer_backend_tests!(
+        aligned,
+        &mut crate::backends::AlignedBuffer::<_, 10, 32>::new([Rgb565::BLACK; 10]),
+        Rgb565::RED,
+        Rgb565::BLUE
+    );
+    framebuffer_backend_tests!(
+        byte_buffer,
+        crate::backends::ByteBuffer::<_, Rgb565>::new([0u8; 20], EndianCorrection::ToBigEndian),
+        Rgb565::RED,
+        Rgb565::BLUE
+    );
+    framebuffer_backend_tests!(
+        dithered,
+        crate::backends::DitheredBuffer::<_, embedded_graphics::pixelcolor::Rgb888>::new(
+            [Rgb565::BLACK; 10],
+            5
+        ),
+        embedded_graphics::pixelcolor::Rgb888::RED,
+        embedded_graphics::pixelcolor::Rgb888::BLUE
+    );
+    framebuffer_backend_tests!(
+        gamma_corrected,
+        crate::backends::GammaCorrected::new(
+            [Rgb565::BLACK; 10],
+            crate::backends::GammaLut::from_gamma(2.2)
+        ),
+        Rgb565::RED,
+        Rgb565::BLUE
+    );
+    framebuffer_backend_tests!(
+        rotated,
+        crate::backends::RotatedBuffer::new(
+            [Rgb565::BLACK; 10],
+            embedded_graphics::prelude::Size::new(2, 5),
+            crate::rotation::Rotation::Deg90
+        ),
+        Rgb565::RED,
+        Rgb565::BLUE
+    );
+    framebuffer_backend_tests!(
+        transposed,
+        crate::backends::TransposedBuffer::new([Rgb565::BLACK; 10], 5),
+        Rgb565::RED,
+        Rgb565::BLUE
+    );
+    framebuffer_backend_tests!(
+        volatile,
+        crate::backends::VolatileBuffer::new(&mut [Rgb565::BLACK; 10]).with_fence(),
+        Rgb565::RED,
+        Rgb565::BLUE
+    );
+    #[cfg(feature = "alloc")]
+    framebuffer_backend_tests!(
+        boxed_slice,
+        alloc::vec![Rgb565::BLACK; 10].into_boxed_slice(),
+        Rgb565::RED,
+        Rgb565::BLUE
+    );
+    #[cfg(feature = "alloc")]
+    framebuffer_backend_tests!(
+        vec,
+        alloc::vec![Rgb565::BLACK; 10],
+        Rgb565::RED,
+        Rgb565::BLUE
+    );
+    #[cfg(feature = "alloc")]
+    framebuffer_backend_tests!(
+        heap,
+        crate::backends::HeapBuffer::new(Rgb565::BLACK, 10),
+        Rgb565::RED,
+        Rgb565::BLUE
+    );
+}
@@ -0,0 +1,198 @@
+//! A text console on top of a framebuffer.
+//!
+//! [`Console`] implements [`core::fmt::Write`], so debug output can be
+//! written to the screen with [`write!`]. Text is drawn with a
+//! [monospaced font](embedded_graphics::mono_font) into a grid of
+//! characters, wrapped at the right edge and [scrolled](crate::scroll) up
+//! when the last line is full.
+
+use core::fmt;
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    mono_font::{MonoFont, MonoTextStyle, MonoTextStyleBuilder},
+    prelude::{PixelColor, Point, Size},
+    text::{Baseline, Text},
+    Drawable,
+};
+
+use crate::{backends::FrameBufferBackend, FrameBuf};
+
+/// A text console writing to a framebuffer.
+///
+/// `'\n'` starts a new line and `'\r'` returns to the start of the line.
+///
+/// # Example
+/// ```rust
+/// use core::fmt::Write;
+/// use embedded_graphics::{mono_font::ascii::FONT_6X10, pixelcolor::Rgb565, prelude::*};
+/// use embedded_graphics_framebuf::{console::Console, FrameBuf};
+///
+/// let mut data = [Rgb565::BLACK; 128 * 64];
+/// let mut fbuf = FrameBuf::new(&mut data, 128, 64);
+/// let mut console = Console::new(&mut fbuf, &FONT_6X10, Rgb565::GREEN, Rgb565::BLACK);
+/// writeln!(console, "Booting {}...", "v1.0").unwrap();
+/// assert_eq!(console.cursor(), (0, 1));
+/// ```
+pub struct Console<'a, C: PixelColor, B: FrameBufferBackend<Color = C>> {
+    fbuf: &'a mut FrameBuf<C, B>,
+    style: MonoTextStyle<'a, C>,
+    background: C,
+    /// The size of a cell of the grid in pixels
+    cell: Size,
+    /// The size of the grid in characters
+    columns: usize,
+    rows: usize,
+    column: usize,
+    row: usize,
+}
+
+impl<'a, C: PixelColor, B: FrameBufferBackend<Color = C>> Console<'a, C, B> {
+    /// Create a console writing to `fbuf` in `font`, with the cursor at the
+    /// top left.
+    ///
+    /// The framebuffer isn't cleared, see [`Console::clear`].
+    ///
+    /// # Panic
+    /// Panics if not even one character fits into the framebuffer.
+    pub fn new(
+        fbuf: &'a mut FrameBuf<C, B>,
+        font: &'a MonoFont<'a>,
+        foreground: C,
+        background: C,
+    ) -> Self {
+        let cell = Size::new(
+            font.character_size.width + font.character_spacing,
+            font.character_size.height,
+        );
+        let columns = (fbuf.width() + font.character_spacing as usize) / cell.width as usize;
+        let rows = fbuf.height() / cell.height as usize;
+        assert!(
+            columns > 0 && rows > 0,
+            "the framebuffer is too small for the font"
+        );
+        Self {
+            fbuf,
+            style: MonoTextStyleBuilder::new()
+                .font(font)
+                .text_color(foreground)
+                .background_color(background)
+                .build(),
+            background,
+            cell,
+            columns,
+            rows,
+            column: 0,
+            row: 0,
+        }
+    }
+
+    /// The size of the console in characters, as `(columns, rows)`.
+    pub fn grid_size(&self) -> (usize, usize) {
+        (self.columns, self.rows)
+    }
+
+    /// The position the next character is written to, as `(column, row)`.
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.column, self.row)
+    }
+
+    /// Clear the framebuffer with the background color and move the cursor
+    /// to the top left.
+    pub fn clear(&mut self) {
+        let Ok(()) = self.fbuf.clear(self.background);
+        self.column = 0;
+        self.row = 0;
+    }
+
+    /// Move the cursor to the start of the next line, scrolling the contents
+    /// up if the cursor is on the last line.
+    fn new_line(&mut self) {
+        self.column = 0;
+        if self.row + 1 < self.rows {
+            self.row += 1;
+        } else {
+            self.fbuf
+                .scroll(0, -(self.cell.height as i32), self.background);
+        }
+    }
+
+    fn write_char(&mut self, c: char) {
+        match c {
+            '\n' => self.new_line(),
+            '\r' => self.column = 0,
+            c => {
+                if self.column == self.columns {
+                    self.new_line();
+                }
+                let position = Point::new(
+                    (self.column as u32 * self.cell.width) as i32,
+                    (self.row as u32 * self.cell.height) as i32,
+                );
+                let mut buf = [0; 4];
+                let Ok(_) = Text::with_baseline(
+                    c.encode_utf8(&mut buf),
+                    position,
+                    self.style,
+                    Baseline::Top,
+                )
+                .draw(self.fbuf);
+                self.column += 1;
+            }
+        }
+    }
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> fmt::Write for Console<'_, C, B> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        s.chars().for_each(|c| self.write_char(c));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::fmt::Write;
+    use embedded_graphics::{
+        mock_display::MockDisplay, mono_font::ascii::FONT_4X6, pixelcolor::BinaryColor,
+    };
+
+    #[test]
+    fn wraps_and_scrolls() {
+        let mut data = [BinaryColor::Off; 8 * 13];
+        let mut fbuf = FrameBuf::new(&mut data, 8, 13);
+        let mut console = Console::new(&mut fbuf, &FONT_4X6, BinaryColor::On, BinaryColor::Off);
+        assert_eq!(console.grid_size(), (2, 2));
+        write!(console, "ab\nc").unwrap();
+        assert_eq!(console.cursor(), (1, 1));
+        write!(console, "de").unwrap();
+        assert_eq!(console.cursor(), (1, 1));
+        write!(console, "\r|").unwrap();
+        assert_eq!(console.cursor(), (1, 1));
+
+        let mut expected = [BinaryColor::Off; 8 * 13];
+        let mut expected_fbuf = FrameBuf::new(&mut expected, 8, 13);
+        let mut expected_console = Console::new(
+            &mut expected_fbuf,
+            &FONT_4X6,
+            BinaryColor::On,
+            BinaryColor::Off,
+        );
+        write!(expected_console, "cd\n|").unwrap();
+
+        let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+        display.draw_iter(&fbuf).unwrap();
+        let mut expected_display: MockDisplay<BinaryColor> = MockDisplay::new();
+        expected_display.draw_iter(&expected_fbuf).unwrap();
+        display.assert_eq(&expected_display);
+    }
+
+    #[test]
+    #[should_panic]
+    fn needs_room_for_a_character() {
+        let mut data = [BinaryColor::Off; 3 * 6];
+        let mut fbuf = FrameBuf::new(&mut data, 3, 6);
+        Console::new(&mut fbuf, &FONT_4X6, BinaryColor::On, BinaryColor::Off);
+    }
+}
@@ -0,0 +1,124 @@
+//! 8 bit coverage masks.
+//!
+//! A [`CoverageMask`] is a framebuffer of [`Gray8`] values, where each value
+//! is the coverage (or alpha) of a pixel: `Gray8::BLACK` is transparent and
+//! `Gray8::WHITE` is opaque. As it is a [`DrawTarget`] for `Gray8`, shapes,
+//! text and images can be drawn into it like into any other framebuffer.
+//!
+//! Afterwards the mask can be stamped onto color framebuffers in any color
+//! with [`FrameBuf::stamp`], so e.g. the needle of a gauge or an icon is only
+//! rendered once and then reused in several colors.
+//!
+//! [`DrawTarget`]: embedded_graphics::draw_target::DrawTarget
+
+use embedded_graphics::{
+    pixelcolor::{Gray8, GrayColor, Rgb888},
+    prelude::{PixelColor, Point},
+    primitives::Rectangle,
+};
+
+use crate::{backends::FrameBufferBackend, blend::blend, FrameBuf};
+
+/// A framebuffer holding the coverage of each pixel.
+pub type CoverageMask<B> = FrameBuf<Gray8, B>;
+
+impl<C, B> FrameBuf<C, B>
+where
+    C: PixelColor + Into<Rgb888> + From<Rgb888>,
+    B: FrameBufferBackend<Color = C>,
+{
+    /// Paint `color` through `mask`, with the top left corner of the mask at
+    /// `top_left`.
+    ///
+    /// Each pixel is blended with `color` according to the coverage of the
+    /// corresponding mask pixel. Pixels outside of the framebuffer or hidden
+    /// by its [mask](crate::mask) are skipped.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{
+    ///     pixelcolor::{Gray8, Rgb565},
+    ///     prelude::*,
+    ///     primitives::{Circle, PrimitiveStyle},
+    /// };
+    /// use embedded_graphics_framebuf::{coverage::CoverageMask, FrameBuf};
+    ///
+    /// let mut mask_data = [Gray8::BLACK; 8 * 8];
+    /// let mut dot = CoverageMask::new(&mut mask_data, 8, 8);
+    /// Circle::new(Point::zero(), 8)
+    ///     .into_styled(PrimitiveStyle::with_fill(Gray8::WHITE))
+    ///     .draw(&mut dot)
+    ///     .unwrap();
+    ///
+    /// let mut data = [Rgb565::BLACK; 64 * 16];
+    /// let mut fbuf = FrameBuf::new(&mut data, 64, 16);
+    /// for (i, color) in [Rgb565::RED, Rgb565::GREEN, Rgb565::BLUE].into_iter().enumerate() {
+    ///     fbuf.stamp(Point::new(10 * i as i32, 4), &dot, color);
+    /// }
+    /// assert_eq!(fbuf.get_color_at(Point::new(24, 8)), Rgb565::BLUE);
+    /// ```
+    pub fn stamp<M>(&mut self, top_left: Point, mask: &CoverageMask<M>, color: C)
+    where
+        M: FrameBufferBackend<Color = Gray8>,
+    {
+        let area = Rectangle::new(top_left, mask.size())
+            .intersection(&Rectangle::new(Point::zero(), self.size()));
+        let fg = color.into();
+        for y in 0..area.size.height as i32 {
+            for x in 0..area.size.width as i32 {
+                let p = area.top_left + Point::new(x, y);
+                let alpha = mask.get_color_at(p - top_left).luma();
+                if alpha == 0 || !self.is_visible(p) {
+                    continue;
+                }
+                let color = match alpha {
+                    255 => color,
+                    _ => blend(self.get_color_at(p).into(), fg, alpha).into(),
+                };
+                let index = self.point_to_index(p);
+                self.data.set(index, color);
+            }
+        }
+        self.mark_dirty(area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::{
+        draw_target::DrawTarget,
+        prelude::{RgbColor, Size},
+    };
+
+    #[test]
+    fn stamps_with_coverage() {
+        let mut mask_data = [Gray8::BLACK; 2 * 2];
+        let mut mask = CoverageMask::new(&mut mask_data, 2, 2);
+        mask.set_color_at(Point::new(0, 0), Gray8::WHITE);
+        mask.set_color_at(Point::new(1, 0), Gray8::new(128));
+
+        let mut data = [Rgb888::BLACK; 3 * 2];
+        let mut fbuf = FrameBuf::new(&mut data, 3, 2);
+        fbuf.clear(Rgb888::new(0, 0, 100)).unwrap();
+        fbuf.take_dirty_area();
+        fbuf.stamp(Point::new(1, -1), &mask, Rgb888::new(200, 0, 0));
+        fbuf.stamp(Point::new(0, 1), &mask, Rgb888::WHITE);
+
+        assert_eq!(
+            fbuf.take_dirty_area(),
+            Some(Rectangle::new(Point::zero(), Size::new(3, 2)))
+        );
+        assert_eq!(
+            data,
+            [
+                Rgb888::new(0, 0, 100),
+                Rgb888::new(0, 0, 100),
+                Rgb888::new(0, 0, 100),
+                Rgb888::WHITE,
+                Rgb888::new(128, 128, 178),
+                Rgb888::new(0, 0, 100),
+            ]
+        );
+    }
+}
@@ -0,0 +1,137 @@
+//! Cursors and other small sprites moving over a scene.
+//!
+//! A [`Cursor`] works like the hardware cursor of a graphics card: before the
+//! sprite is drawn into the framebuffer, the pixels below it are saved, and
+//! they are restored when it moves on. So moving a pointer over a scene only
+//! touches the pixels below the old and the new position, and the scene
+//! doesn't have to be rendered again.
+//!
+//! ```rust
+//! use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+//! use embedded_graphics_framebuf::{cursor::Cursor, FrameBuf};
+//!
+//! let mut sprite_data = [Rgb565::WHITE; 8 * 8];
+//! let sprite = FrameBuf::new(&mut sprite_data, 8, 8);
+//! let mut cursor = Cursor::new(&sprite, [Rgb565::BLACK; 8 * 8], None);
+//!
+//! let mut data = [Rgb565::BLUE; 128 * 64];
+//! let mut fbuf = FrameBuf::new(&mut data, 128, 64);
+//! cursor.show(&mut fbuf, Point::new(10, 10));
+//! cursor.show(&mut fbuf, Point::new(14, 10));
+//! assert_eq!(fbuf.get_color_at(Point::new(10, 10)), Rgb565::BLUE);
+//! assert_eq!(fbuf.get_color_at(Point::new(14, 10)), Rgb565::WHITE);
+//! cursor.hide(&mut fbuf);
+//! assert_eq!(fbuf.get_color_at(Point::new(14, 10)), Rgb565::BLUE);
+//! ```
+//!
+//! The scene must not be drawn to while the cursor is shown, or the restored
+//! pixels overwrite it. Hide the cursor, draw and show it again.
+
+use embedded_graphics::{
+    geometry::Dimensions,
+    prelude::{PixelColor, Point},
+    primitives::Rectangle,
+};
+
+use crate::{backends::FrameBufferBackend, FrameBuf};
+
+/// A sprite drawn over a framebuffer, restoring the pixels below it when it
+/// is moved or hidden. See the [module documentation](self).
+pub struct Cursor<'a, C, S: FrameBufferBackend<Color = C>, U: FrameBufferBackend<Color = C>> {
+    sprite: &'a FrameBuf<C, S>,
+    key: Option<C>,
+    saved: FrameBuf<C, U>,
+    position: Option<Point>,
+}
+
+impl<'a, C, S, U> Cursor<'a, C, S, U>
+where
+    C: PixelColor,
+    S: FrameBufferBackend<Color = C>,
+    U: FrameBufferBackend<Color = C>,
+{
+    /// Create a hidden cursor showing `sprite`, with the pixels below it
+    /// saved in `save_under`. If `key` is given, pixels of the sprite with
+    /// that color are transparent.
+    ///
+    /// # Panic
+    /// Panics if `save_under` doesn't have exactly the size of the sprite.
+    pub fn new(sprite: &'a FrameBuf<C, S>, save_under: U, key: Option<C>) -> Self {
+        Self {
+            sprite,
+            key,
+            saved: FrameBuf::new(save_under, sprite.width(), sprite.height()),
+            position: None,
+        }
+    }
+
+    /// The position of the top left corner of the sprite, or `None` if the
+    /// cursor is hidden.
+    pub fn position(&self) -> Option<Point> {
+        self.position
+    }
+
+    /// Draw the sprite with its top left corner at `position`, restoring the
+    /// pixels at its previous position first.
+    ///
+    /// Both areas are marked dirty.
+    pub fn show<B: FrameBufferBackend<Color = C>>(
+        &mut self,
+        fbuf: &mut FrameBuf<C, B>,
+        position: Point,
+    ) {
+        self.hide(fbuf);
+        let area = Rectangle::new(position, self.sprite.size());
+        self.saved.blit(fbuf, area, Point::zero());
+        let sprite_area = self.sprite.bounding_box();
+        match self.key {
+            Some(key) => fbuf.blit_keyed(self.sprite, sprite_area, position, key),
+            None => fbuf.blit(self.sprite, sprite_area, position),
+        }
+        self.position = Some(position);
+    }
+
+    /// Restore the pixels below the sprite, if it is shown.
+    ///
+    /// The area is marked dirty.
+    pub fn hide<B: FrameBufferBackend<Color = C>>(&mut self, fbuf: &mut FrameBuf<C, B>) {
+        if let Some(position) = self.position.take() {
+            fbuf.blit(&self.saved, self.saved.bounding_box(), position);
+        }
+    }
+
+    /// Return the save-under backend.
+    pub fn into_inner(self) -> U {
+        self.saved.into_inner()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::{pixelcolor::Gray8, prelude::Size};
+
+    #[test]
+    fn saves_and_restores() {
+        let mut sprite_data = [9, 0, 9, 9].map(Gray8::new);
+        let sprite = FrameBuf::new(&mut sprite_data, 2, 2);
+        let mut cursor = Cursor::new(&sprite, [Gray8::new(0); 4], Some(Gray8::new(0)));
+
+        let mut data = [1, 2, 3, 4, 5, 6].map(Gray8::new);
+        let mut fbuf = FrameBuf::new(&mut data, 3, 2);
+        cursor.show(&mut fbuf, Point::new(-1, 0));
+        assert_eq!(cursor.position(), Some(Point::new(-1, 0)));
+        fbuf.take_dirty_area();
+        cursor.show(&mut fbuf, Point::new(1, 1));
+        assert_eq!(
+            fbuf.take_dirty_area(),
+            Some(Rectangle::new(Point::zero(), Size::new(3, 2)))
+        );
+        assert_eq!(data, [1, 2, 3, 4, 9, 6].map(Gray8::new));
+
+        let mut fbuf = FrameBuf::new(&mut data, 3, 2);
+        cursor.hide(&mut fbuf);
+        assert_eq!(cursor.position(), None);
+        assert_eq!(data, [1, 2, 3, 4, 5, 6].map(Gray8::new));
+    }
+}
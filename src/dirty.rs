@@ -0,0 +1,474 @@
+//! Tracking of the area changed since the last flush.
+//!
+//! Every write through the [`DrawTarget`](embedded_graphics::draw_target::DrawTarget)
+//! implementation or [`FrameBuf::set_color_at`] extends the dirty area of a
+//! [`FrameBuf`]. Code writing to the backend directly (e.g. a camera DMA
+//! capture into the buffer) can add to it with [`FrameBuf::mark_dirty`].
+//! Flush helpers like [`FrameBuf::flush_dirty_to`] then only transmit the
+//! dirty area. A new framebuffer is completely dirty, as the display content
+//! is unknown.
+//!
+//! ```rust
+//! use embedded_graphics::{pixelcolor::Rgb565, prelude::*, primitives::Rectangle};
+//! use embedded_graphics_framebuf::FrameBuf;
+//!
+//! let mut data = [Rgb565::BLACK; 240 * 135];
+//! let mut fbuf = FrameBuf::new(&mut data, 240, 135);
+//! fbuf.take_dirty_area();
+//!
+//! fbuf.data[240 * 10 + 20] = Rgb565::RED;
+//! fbuf.mark_dirty(Rectangle::new(Point::new(20, 10), Size::new(1, 1)));
+//! fbuf.set_color_at(Point::new(30, 12), Rgb565::RED);
+//! assert_eq!(
+//!     fbuf.take_dirty_area(),
+//!     Some(Rectangle::new(Point::new(20, 10), Size::new(11, 3)))
+//! );
+//! ```
+//!
+//! A single bounding box is wasteful for changes in opposite corners of the
+//! screen (e.g. a clock and a status icon). With
+//! [`FrameBuf::set_dirty_regions`] the framebuffer additionally tracks up to
+//! [`MAX_DIRTY_REGIONS`] separate rectangles, which are taken with
+//! [`FrameBuf::take_dirty_regions`].
+//!
+//! ```rust
+//! use embedded_graphics::{pixelcolor::Rgb565, prelude::*, primitives::Rectangle};
+//! use embedded_graphics_framebuf::FrameBuf;
+//!
+//! let mut data = [Rgb565::BLACK; 240 * 135];
+//! let mut fbuf = FrameBuf::new(&mut data, 240, 135);
+//! fbuf.set_dirty_regions(true);
+//! fbuf.take_dirty_regions();
+//!
+//! fbuf.set_color_at(Point::new(0, 0), Rgb565::RED);
+//! fbuf.set_color_at(Point::new(239, 134), Rgb565::RED);
+//! for area in fbuf.take_dirty_regions() {
+//!     assert_eq!(area.size, Size::new(1, 1));
+//!     // flush `area`
+//! }
+//! ```
+//!
+//! Apps keeping the previous frame around can instead compare the frames
+//! with [`FrameBuf::diff`], which also finds the areas that were redrawn
+//! without changing.
+
+use embedded_graphics::{
+    geometry::Dimensions,
+    prelude::{PixelColor, Point},
+    primitives::Rectangle,
+};
+
+use crate::{backends::FrameBufferBackend, FrameBuf};
+
+/// The maximal number of rectangles tracked with
+/// [`FrameBuf::set_dirty_regions`].
+pub const MAX_DIRTY_REGIONS: usize = 8;
+
+/// Separately tracked dirty rectangles, as inclusive corners.
+///
+/// Also the iterator returned by [`FrameBuf::take_dirty_regions`] and
+/// [`FrameBuf::diff`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DirtyRegions {
+    regions: [(Point, Point); MAX_DIRTY_REGIONS],
+    len: usize,
+}
+
+impl DirtyRegions {
+    pub(crate) const fn new() -> Self {
+        Self {
+            regions: [(Point::new(0, 0), Point::new(0, 0)); MAX_DIRTY_REGIONS],
+            len: 0,
+        }
+    }
+
+    /// Add the area with the inclusive corners `a` and `b`.
+    fn add(&mut self, a: Point, b: Point) {
+        let grow = |(min, max): &mut (Point, Point)| {
+            (*min, *max) = (min.component_min(a), max.component_max(b));
+        };
+        let regions = &mut self.regions[..self.len];
+        // Extend a region touching the area, so connected shapes end up in one
+        let touching = regions.iter_mut().find(|(min, max)| {
+            min.x - 1 <= b.x && a.x <= max.x + 1 && min.y - 1 <= b.y && a.y <= max.y + 1
+        });
+        if let Some(region) = touching {
+            grow(region);
+        } else if self.len < MAX_DIRTY_REGIONS {
+            self.regions[self.len] = (a, b);
+            self.len += 1;
+        } else {
+            // Out of regions, grow the one that grows the least
+            let area = |(min, max): (Point, Point)| {
+                (max.x - min.x + 1) as u64 * (max.y - min.y + 1) as u64
+            };
+            let region = regions
+                .iter_mut()
+                .min_by_key(|r| area((r.0.component_min(a), r.1.component_max(b))) - area(**r))
+                .unwrap();
+            grow(region);
+        }
+    }
+}
+
+impl Iterator for DirtyRegions {
+    type Item = Rectangle;
+
+    fn next(&mut self) -> Option<Rectangle> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let (min, max) = self.regions[self.len];
+        Some(Rectangle::with_corners(min, max))
+    }
+}
+
+impl<C, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
+    pub(crate) fn mark_point_dirty(&mut self, p: Point) {
+        self.dirty = Some(match self.dirty {
+            Some((min, max)) => (min.component_min(p), max.component_max(p)),
+            None => (p, p),
+        });
+        if let Some(regions) = &mut self.dirty_regions {
+            regions.add(p, p);
+        }
+    }
+
+    /// Enable or disable tracking of separate dirty rectangles. When enabled,
+    /// the whole framebuffer is marked dirty.
+    ///
+    /// The bounding box of all changes is tracked either way.
+    pub fn set_dirty_regions(&mut self, enabled: bool) {
+        self.dirty_regions = enabled.then(DirtyRegions::new);
+        self.mark_all_dirty();
+    }
+
+    /// Return the dirty rectangles and mark the framebuffer as clean.
+    ///
+    /// Without [`FrameBuf::set_dirty_regions`] enabled, this yields the
+    /// [dirty area](FrameBuf::dirty_area) if there is one. The rectangles may
+    /// overlap.
+    pub fn take_dirty_regions(&mut self) -> DirtyRegions {
+        let mut regions = DirtyRegions::new();
+        match &mut self.dirty_regions {
+            Some(tracked) => core::mem::swap(tracked, &mut regions),
+            None => {
+                if let Some(area) = self.dirty {
+                    regions.regions[0] = area;
+                    regions.len = 1;
+                }
+            }
+        }
+        self.dirty = None;
+        regions
+    }
+
+    /// Mark an area as changed, e.g. after writing to the backend directly.
+    ///
+    /// The area is clipped to the framebuffer.
+    pub fn mark_dirty(&mut self, area: Rectangle)
+    where
+        C: PixelColor,
+    {
+        let area = area.intersection(&self.bounding_box());
+        if let Some(bottom_right) = area.bottom_right() {
+            self.dirty = Some(match self.dirty {
+                Some((min, max)) => (
+                    min.component_min(area.top_left),
+                    max.component_max(bottom_right),
+                ),
+                None => (area.top_left, bottom_right),
+            });
+            if let Some(regions) = &mut self.dirty_regions {
+                regions.add(area.top_left, bottom_right);
+            }
+        }
+    }
+
+    /// Mark the whole framebuffer as changed.
+    pub fn mark_all_dirty(&mut self) {
+        self.dirty = crate::full_area(self.width, self.height);
+        if let Some(regions) = &mut self.dirty_regions {
+            *regions = DirtyRegions::new();
+            if let Some(area) = self.dirty {
+                regions.regions[0] = area;
+                regions.len = 1;
+            }
+        }
+    }
+
+    /// The bounding box of all changes since the dirty area was last taken.
+    pub fn dirty_area(&self) -> Option<Rectangle> {
+        self.dirty
+            .map(|(min, max)| Rectangle::with_corners(min, max))
+    }
+
+    /// The dirty area widened to whole bytes of 8 horizontal pixels, as
+    /// needed for the partial refresh window of e-paper controllers.
+    ///
+    /// The window is clipped to the framebuffer, so its right edge is only
+    /// unaligned if the width isn't a multiple of 8. Like
+    /// [`FrameBuf::dirty_area`] this doesn't mark the framebuffer as clean.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::BinaryColor, prelude::*, primitives::Rectangle};
+    /// use embedded_graphics_framebuf::FrameBuf;
+    ///
+    /// let mut data = [BinaryColor::Off; 200 * 200];
+    /// let mut fbuf = FrameBuf::new(&mut data, 200, 200);
+    /// fbuf.take_dirty_area();
+    /// fbuf.set_color_at(Point::new(10, 20), BinaryColor::On);
+    /// fbuf.set_color_at(Point::new(17, 30), BinaryColor::On);
+    /// assert_eq!(
+    ///     fbuf.refresh_window(),
+    ///     Some(Rectangle::new(Point::new(8, 20), Size::new(16, 11)))
+    /// );
+    /// ```
+    pub fn refresh_window(&self) -> Option<Rectangle> {
+        let (min, max) = self.dirty?;
+        let left = min.x & !7;
+        let right = ((max.x | 7) + 1).min(self.width as i32);
+        Some(Rectangle::with_corners(
+            Point::new(left, min.y),
+            Point::new(right - 1, max.y),
+        ))
+    }
+
+    /// The areas in which the framebuffer differs from `previous`, merged
+    /// into at most [`MAX_DIRTY_REGIONS`] rectangles for displays with
+    /// windowed writes (like the CASET/RASET commands of an ST7789).
+    ///
+    /// Runs of changed pixels touching each other are merged into one
+    /// rectangle. Once all rectangles are used, runs are merged into the
+    /// rectangle which grows the least. The rectangles may overlap.
+    ///
+    /// # Panic
+    /// Panics if the framebuffers differ in size.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::Rgb565, prelude::*, primitives::Rectangle};
+    /// use embedded_graphics_framebuf::FrameBuf;
+    ///
+    /// let mut previous_data = [Rgb565::BLACK; 240 * 135];
+    /// let previous = FrameBuf::new(&mut previous_data, 240, 135);
+    /// let mut data = [Rgb565::BLACK; 240 * 135];
+    /// let mut fbuf = FrameBuf::new(&mut data, 240, 135);
+    /// fbuf.set_color_at(Point::new(10, 10), Rgb565::RED);
+    /// fbuf.set_color_at(Point::new(11, 11), Rgb565::RED);
+    /// fbuf.set_color_at(Point::new(200, 100), Rgb565::RED);
+    ///
+    /// let mut areas: Vec<_> = fbuf.diff(&previous).collect();
+    /// areas.sort_by_key(|area| area.top_left.x);
+    /// assert_eq!(
+    ///     areas,
+    ///     [
+    ///         Rectangle::new(Point::new(10, 10), Size::new(2, 2)),
+    ///         Rectangle::new(Point::new(200, 100), Size::new(1, 1)),
+    ///     ]
+    /// );
+    /// ```
+    pub fn diff<P>(&self, previous: &FrameBuf<C, P>) -> DirtyRegions
+    where
+        C: PixelColor,
+        P: FrameBufferBackend<Color = C>,
+    {
+        assert_eq!(
+            self.size(),
+            previous.size(),
+            "FrameBuf diff of framebuffers with different sizes"
+        );
+        let mut regions = DirtyRegions::new();
+        for y in 0..self.height as i32 {
+            let mut run: Option<i32> = None;
+            for x in 0..=self.width as i32 {
+                let p = Point::new(x, y);
+                let changed =
+                    x < self.width as i32 && self.get_color_at(p) != previous.get_color_at(p);
+                match (run, changed) {
+                    (None, true) => run = Some(x),
+                    (Some(start), false) => {
+                        regions.add(Point::new(start, y), Point::new(x - 1, y));
+                        run = None;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        regions
+    }
+
+    /// Return the dirty area and mark the framebuffer as clean.
+    pub fn take_dirty_area(&mut self) -> Option<Rectangle> {
+        let area = self.dirty_area();
+        self.dirty = None;
+        if let Some(regions) = &mut self.dirty_regions {
+            *regions = DirtyRegions::new();
+        }
+        area
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::{
+        draw_target::DrawTarget,
+        pixelcolor::BinaryColor,
+        prelude::{Primitive, Size},
+        primitives::{Line, PrimitiveStyle},
+        Drawable,
+    };
+
+    #[test]
+    fn new_framebuffer_is_dirty() {
+        let mut data = [BinaryColor::Off; 4 * 3];
+        let mut fbuf = FrameBuf::new(&mut data, 4, 3);
+        assert_eq!(
+            fbuf.take_dirty_area(),
+            Some(Rectangle::new(Point::zero(), Size::new(4, 3)))
+        );
+        assert_eq!(fbuf.dirty_area(), None);
+    }
+
+    #[test]
+    fn tracks_drawing() {
+        let mut data = [BinaryColor::Off; 12 * 11];
+        let mut fbuf = FrameBuf::new(&mut data, 12, 11);
+        fbuf.take_dirty_area();
+
+        Line::new(Point::new(2, 2), Point::new(10, 2))
+            .into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 1))
+            .draw(&mut fbuf)
+            .unwrap();
+        // Out of bounds pixels are ignored
+        fbuf.draw_iter([embedded_graphics::Pixel(
+            Point::new(20, 20),
+            BinaryColor::On,
+        )])
+        .unwrap();
+        assert_eq!(
+            fbuf.dirty_area(),
+            Some(Rectangle::new(Point::new(2, 2), Size::new(9, 1)))
+        );
+
+        fbuf.clear(BinaryColor::Off).unwrap();
+        assert_eq!(
+            fbuf.dirty_area(),
+            Some(Rectangle::new(Point::zero(), Size::new(12, 11)))
+        );
+    }
+
+    #[test]
+    fn marks_manually() {
+        let mut data = [BinaryColor::Off; 4 * 3];
+        let mut fbuf = FrameBuf::new(&mut data, 4, 3);
+        fbuf.take_dirty_area();
+
+        fbuf.mark_dirty(Rectangle::new(Point::new(3, 2), Size::zero()));
+        assert_eq!(fbuf.dirty_area(), None);
+        fbuf.mark_dirty(Rectangle::new(Point::new(-1, 2), Size::new(2, 5)));
+        assert_eq!(
+            fbuf.dirty_area(),
+            Some(Rectangle::new(Point::new(0, 2), Size::new(1, 1)))
+        );
+        fbuf.mark_all_dirty();
+        assert_eq!(
+            fbuf.take_dirty_area(),
+            Some(Rectangle::new(Point::zero(), Size::new(4, 3)))
+        );
+    }
+
+    #[test]
+    fn tracks_separate_regions() {
+        extern crate std;
+        use std::vec::Vec;
+
+        let mut data = [BinaryColor::Off; 12 * 11];
+        let mut fbuf = FrameBuf::new(&mut data, 12, 11);
+        fbuf.set_dirty_regions(true);
+        let all: Vec<_> = fbuf.take_dirty_regions().collect();
+        assert_eq!(all, [Rectangle::new(Point::zero(), Size::new(12, 11))]);
+
+        Line::new(Point::new(2, 2), Point::new(5, 2))
+            .into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 1))
+            .draw(&mut fbuf)
+            .unwrap();
+        fbuf.set_color_at(Point::new(10, 10), BinaryColor::On);
+        fbuf.mark_dirty(Rectangle::new(Point::new(8, 7), Size::new(2, 3)));
+        let mut regions: Vec<_> = fbuf.take_dirty_regions().collect();
+        regions.sort_by_key(|r| r.top_left.x);
+        assert_eq!(
+            regions,
+            [
+                Rectangle::new(Point::new(2, 2), Size::new(4, 1)),
+                Rectangle::new(Point::new(8, 7), Size::new(3, 4)),
+            ]
+        );
+        assert_eq!(fbuf.dirty_area(), None);
+        assert_eq!(fbuf.take_dirty_regions().count(), 0);
+    }
+
+    #[test]
+    fn merges_regions_when_full() {
+        let mut data = [BinaryColor::Off; 40];
+        let mut fbuf = FrameBuf::new(&mut data, 40, 1);
+        fbuf.set_dirty_regions(true);
+        fbuf.take_dirty_regions();
+        for x in 0..=MAX_DIRTY_REGIONS as i32 {
+            fbuf.set_color_at(Point::new(3 * x, 0), BinaryColor::On);
+        }
+        let regions = fbuf.take_dirty_regions();
+        assert_eq!(regions.len, MAX_DIRTY_REGIONS);
+        let pixels: u32 = regions.map(|r| r.size.width).sum();
+        assert_eq!(pixels, MAX_DIRTY_REGIONS as u32 + 3);
+    }
+
+    #[test]
+    fn diffs_frames() {
+        let mut previous_data = [BinaryColor::Off; 5 * 4];
+        let previous = FrameBuf::new(&mut previous_data, 5, 4);
+        let mut data = [BinaryColor::Off; 5 * 4];
+        let mut fbuf = FrameBuf::new(&mut data, 5, 4);
+        assert_eq!(fbuf.diff(&previous).next(), None);
+
+        fbuf.clear(BinaryColor::Off).unwrap();
+        for p in [(1, 0), (2, 0), (2, 1), (4, 3)] {
+            fbuf.set_color_at(Point::new(p.0, p.1), BinaryColor::On);
+        }
+        let mut areas = fbuf.diff(&previous);
+        assert_eq!(
+            areas.next(),
+            Some(Rectangle::new(Point::new(4, 3), Size::new(1, 1)))
+        );
+        assert_eq!(
+            areas.next(),
+            Some(Rectangle::new(Point::new(1, 0), Size::new(2, 2)))
+        );
+        assert_eq!(areas.next(), None);
+    }
+
+    #[test]
+    fn aligns_refresh_window() {
+        let mut data = [BinaryColor::Off; 20 * 2];
+        let mut fbuf = FrameBuf::new(&mut data, 20, 2);
+        assert_eq!(
+            fbuf.refresh_window(),
+            Some(Rectangle::new(Point::zero(), Size::new(20, 2)))
+        );
+        fbuf.take_dirty_area();
+        assert_eq!(fbuf.refresh_window(), None);
+        fbuf.set_color_at(Point::new(8, 1), BinaryColor::On);
+        assert_eq!(
+            fbuf.refresh_window(),
+            Some(Rectangle::new(Point::new(8, 1), Size::new(8, 1)))
+        );
+        fbuf.set_color_at(Point::new(17, 1), BinaryColor::On);
+        assert_eq!(
+            fbuf.refresh_window(),
+            Some(Rectangle::new(Point::new(8, 1), Size::new(12, 1)))
+        );
+    }
+}
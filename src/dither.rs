@@ -0,0 +1,391 @@
+//! Dithering of framebuffers to displays with fewer colors.
+//!
+//! [`TemporalDither`] alternates the pixels of a monochrome display between
+//! frames. For ordered (spatial) dithering while drawing, e.g. of [`Rgb888`]
+//! gradients into an [`Rgb565`] or [`Gray4`] framebuffer, see
+//! [`DitheredBuffer`](crate::backends::DitheredBuffer). Error diffusion to
+//! monochrome, e.g. of photos for e-paper displays, is done by
+//! [`ErrorDiffusion`].
+
+use core::marker::PhantomData;
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Dimensions, OriginDimensions},
+    pixelcolor::{
+        Bgr555, Bgr565, Bgr666, BinaryColor, Gray2, Gray4, Gray8, GrayColor, Rgb555, Rgb565,
+        Rgb666, Rgb888, RgbColor,
+    },
+    prelude::{PixelColor, Point, Size},
+    primitives::{PointsIter, Rectangle},
+    Pixel,
+};
+
+use crate::{backends::FrameBufferBackend, FrameBuf};
+
+/// 4x4 Bayer matrix with thresholds from 0 to 15.
+pub(crate) const BAYER_4X4: [[u8; 4]; 4] =
+    [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Quantize an 8 bit channel to `0..=max`, rounding up for a share of the
+/// 16 `threshold`s proportional to the quantization error.
+fn dither_channel(value: u8, max: u8, threshold: u8) -> u8 {
+    let level = (value as u32 * max as u32 * 32 + (2 * threshold as u32 + 1) * 255) / (255 * 32);
+    level.min(max as u32) as u8
+}
+
+/// Colors with fewer levels than [`Rgb888`], which can be the stored colors
+/// of a [`DitheredBuffer`](crate::backends::DitheredBuffer).
+pub trait OrderedDither: PixelColor + Into<Rgb888> {
+    /// Quantize `color` using a threshold from `0` to `15` of the Bayer
+    /// matrix at its position.
+    fn dither(color: Rgb888, threshold: u8) -> Self;
+}
+
+macro_rules! impl_ordered_dither_rgb {
+    ($($color:ident),+) => {
+        $(
+            impl OrderedDither for $color {
+                fn dither(color: Rgb888, threshold: u8) -> Self {
+                    Self::new(
+                        dither_channel(color.r(), Self::MAX_R, threshold),
+                        dither_channel(color.g(), Self::MAX_G, threshold),
+                        dither_channel(color.b(), Self::MAX_B, threshold),
+                    )
+                }
+            }
+        )+
+    };
+}
+
+impl_ordered_dither_rgb!(Rgb555, Bgr555, Rgb565, Bgr565, Rgb666, Bgr666);
+
+macro_rules! impl_ordered_dither_gray {
+    ($($color:ident: $max:expr),+) => {
+        $(
+            impl OrderedDither for $color {
+                fn dither(color: Rgb888, threshold: u8) -> Self {
+                    Self::new(dither_channel(Gray8::from(color).luma(), $max, threshold))
+                }
+            }
+        )+
+    };
+}
+
+impl_ordered_dither_gray!(Gray2: 3, Gray4: 15);
+
+impl OrderedDither for BinaryColor {
+    fn dither(color: Rgb888, threshold: u8) -> Self {
+        BinaryColor::from(dither_channel(Gray8::from(color).luma(), 1, threshold) == 1)
+    }
+}
+
+/// Temporal dithering to a monochrome display.
+///
+/// Every frame the threshold of each pixel is shifted, so that over 16
+/// consecutive frames a pixel is lit for a share of the frames proportional
+/// to its brightness. On fast refreshing panels like OLEDs this creates the
+/// illusion of 17 levels of gray, from black to white. The initial threshold
+/// of each pixel comes from a Bayer matrix, so neighbouring pixels don't
+/// flicker in sync.
+///
+/// # Example
+/// ```rust
+/// use embedded_graphics::{mock_display::MockDisplay, pixelcolor::{BinaryColor, Gray8}, prelude::*};
+/// use embedded_graphics_framebuf::{dither::TemporalDither, FrameBuf};
+///
+/// let mut data = [Gray8::new(100); 12 * 11];
+/// let fbuf = FrameBuf::new(&mut data, 12, 11);
+/// let mut dither = TemporalDither::new();
+/// // Once per display refresh:
+/// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+/// display.draw_iter(dither.next_frame(&fbuf)).unwrap();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct TemporalDither {
+    frame: u8,
+}
+
+impl TemporalDither {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Iterate over the pixels of `fbuf` dithered for the next frame.
+    pub fn next_frame<'a, C, B>(
+        &mut self,
+        fbuf: &'a FrameBuf<C, B>,
+    ) -> TemporalDitherPixels<'a, C, B>
+    where
+        C: PixelColor + Into<Gray8>,
+        B: FrameBufferBackend<Color = C>,
+    {
+        let frame = self.frame;
+        self.frame = (self.frame + 1) % 16;
+        TemporalDitherPixels {
+            fbuf,
+            frame,
+            index: 0,
+        }
+    }
+}
+
+/// Iterator over the dithered pixels of one frame, see
+/// [`TemporalDither::next_frame`].
+pub struct TemporalDitherPixels<'a, C, B: FrameBufferBackend<Color = C>> {
+    fbuf: &'a FrameBuf<C, B>,
+    frame: u8,
+    index: usize,
+}
+
+impl<C, B> Iterator for TemporalDitherPixels<'_, C, B>
+where
+    C: PixelColor + Into<Gray8>,
+    B: FrameBufferBackend<Color = C>,
+{
+    type Item = Pixel<BinaryColor>;
+
+    fn next(&mut self) -> Option<Pixel<BinaryColor>> {
+        let width = self.fbuf.width();
+        if self.index >= width * self.fbuf.height() {
+            return None;
+        }
+        let (x, y) = (self.index % width, self.index / width);
+        self.index += 1;
+
+        let p = Point::new(x as i32, y as i32);
+        // 17 levels, so black is never lit and white is lit in every frame
+        let luma = self.fbuf.get_color_at(p).into().luma();
+        let level = ((luma as u16 * 16 + 127) / 255) as u8;
+        let threshold = (BAYER_4X4[y % 4][x % 4] + self.frame) % 16;
+        let color = BinaryColor::from(level > threshold);
+        Some(Pixel(self.fbuf.origin() + p, color))
+    }
+}
+
+impl<C: PixelColor + Into<Gray8>, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
+    /// Convert the framebuffer to monochrome into `dest` with Floyd–Steinberg
+    /// error diffusion, e.g. to show a photo on an e-paper display.
+    ///
+    /// `errors` is scratch memory of at least `dest.width() + 1` entries. Pixels
+    /// outside of `dest` are skipped, see [`ErrorDiffusion`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::Gray8, prelude::*};
+    /// use embedded_graphics_framebuf::{
+    ///     backends::{BitOrder, PackedBinaryBuffer},
+    ///     FrameBuf,
+    /// };
+    ///
+    /// let mut photo = [Gray8::new(64); 200 * 200];
+    /// let photo = FrameBuf::new(&mut photo, 200, 200);
+    ///
+    /// let mut bytes = [0u8; 200 * 200 / 8];
+    /// let mut epd = FrameBuf::new(
+    ///     PackedBinaryBuffer::new(&mut bytes, 200, BitOrder::MsbFirst),
+    ///     200,
+    ///     200,
+    /// );
+    /// let mut errors = [0; 200 + 1];
+    /// photo.diffuse_into(&mut epd, &mut errors);
+    /// // About a quarter of the pixels are lit
+    /// let lit: u32 = bytes.iter().map(|b| b.count_ones()).sum();
+    /// assert!(lit.abs_diff(200 * 200 / 4) < 200);
+    /// ```
+    pub fn diffuse_into<D>(&self, dest: &mut FrameBuf<BinaryColor, D>, errors: &mut [i16])
+    where
+        D: FrameBufferBackend<Color = BinaryColor>,
+    {
+        let area = self.bounding_box();
+        let colors = area.points().map(|p| self.get_color_at(p));
+        let Ok(()) = ErrorDiffusion::new(dest, errors).fill_contiguous(&area, colors);
+    }
+}
+
+/// A [`DrawTarget`] converting to monochrome with Floyd–Steinberg error
+/// diffusion.
+///
+/// The quantization error of every pixel is spread to its right and lower
+/// neighbours, which keeps the detail of images and gradients. Errors are
+/// diffused within areas drawn with
+/// [`fill_contiguous`](DrawTarget::fill_contiguous) (e.g. an
+/// [`ImageRaw`](embedded_graphics::image::ImageRaw)) or
+/// [`fill_solid`](DrawTarget::fill_solid), while single pixels from
+/// [`draw_iter`](DrawTarget::draw_iter) are just thresholded.
+///
+/// # Example
+/// ```rust
+/// use embedded_graphics::{
+///     image::{Image, ImageRaw},
+///     pixelcolor::{BinaryColor, Gray8},
+///     prelude::*,
+/// };
+/// use embedded_graphics_framebuf::{dither::ErrorDiffusion, FrameBuf};
+///
+/// let image: ImageRaw<Gray8> = ImageRaw::new(&[128; 16 * 16], 16);
+/// let mut data = [BinaryColor::Off; 64 * 64];
+/// let mut fbuf = FrameBuf::new(&mut data, 64, 64);
+/// let mut errors = [0; 64 + 1];
+/// Image::new(&image, Point::new(8, 8))
+///     .draw(&mut ErrorDiffusion::new(&mut fbuf, &mut errors))
+///     .unwrap();
+/// assert_eq!(data.iter().filter(|c| c.is_on()).count(), 16 * 16 / 2);
+/// ```
+pub struct ErrorDiffusion<'a, C, B: FrameBufferBackend<Color = BinaryColor>> {
+    fbuf: &'a mut FrameBuf<BinaryColor, B>,
+    /// The errors for the next row, shifted by one column
+    errors: &'a mut [i16],
+    color: PhantomData<C>,
+}
+
+impl<'a, C, B: FrameBufferBackend<Color = BinaryColor>> ErrorDiffusion<'a, C, B> {
+    /// Draw into `fbuf`, using `errors` as scratch memory.
+    ///
+    /// # Panic
+    /// Panics if `errors` has less than `fbuf.width() + 1` entries.
+    pub fn new(fbuf: &'a mut FrameBuf<BinaryColor, B>, errors: &'a mut [i16]) -> Self {
+        assert!(
+            errors.len() > fbuf.width(),
+            "error buffer of {} entries is too small for width {}",
+            errors.len(),
+            fbuf.width()
+        );
+        Self {
+            fbuf,
+            errors,
+            color: PhantomData,
+        }
+    }
+}
+
+impl<C, B: FrameBufferBackend<Color = BinaryColor>> OriginDimensions for ErrorDiffusion<'_, C, B> {
+    fn size(&self) -> Size {
+        self.fbuf.size()
+    }
+}
+
+impl<C, B> DrawTarget for ErrorDiffusion<'_, C, B>
+where
+    C: PixelColor + Into<Gray8>,
+    B: FrameBufferBackend<Color = BinaryColor>,
+{
+    type Color = C;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.fbuf.draw_iter(
+            pixels
+                .into_iter()
+                .map(|Pixel(p, color)| Pixel(p, BinaryColor::from(color.into().luma() >= 128))),
+        )
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let clip = area.intersection(&self.fbuf.bounding_box());
+        let left = clip.top_left.x;
+        self.errors.fill(0);
+        // The error for the pixel to the right, and the part of the error for
+        // the row below which goes one column further
+        let (mut right, mut diagonal) = (0, 0);
+        for (p, color) in area.points().zip(colors) {
+            if p.x == area.top_left.x {
+                (right, diagonal) = (0, 0);
+            }
+            if !clip.contains(p) {
+                continue;
+            }
+            let column = (p.x - left) as usize;
+            let value = color.into().luma() as i16 + self.errors[column + 1] + right;
+            let on = value >= 128;
+            let error = value - if on { 255 } else { 0 };
+            if self.fbuf.is_visible(p) {
+                self.fbuf.set_color_at(p, BinaryColor::from(on));
+            }
+
+            self.errors[column] += error * 3 / 16;
+            self.errors[column + 1] = error * 5 / 16 + diagonal;
+            diagonal = error / 16;
+            right = error * 7 / 16;
+        }
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.fill_contiguous(area, core::iter::repeat(color))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lights_pixels_proportionally() {
+        let mut data = [Gray8::BLACK, Gray8::new(128), Gray8::new(64), Gray8::WHITE];
+        let fbuf = FrameBuf::new(&mut data, 4, 1);
+        let mut dither = TemporalDither::new();
+
+        let mut lit = [0; 4];
+        for _ in 0..16 {
+            for (i, Pixel(_, color)) in dither.next_frame(&fbuf).enumerate() {
+                lit[i] += color.is_on() as usize;
+            }
+        }
+        assert_eq!(lit, [0, 8, 4, 16]);
+    }
+
+    #[test]
+    fn dithers_channels_proportionally() {
+        for (value, max) in [(0, 31), (128, 31), (255, 31), (100, 15), (200, 1)] {
+            let levels: u32 = (0..16).map(|t| dither_channel(value, max, t) as u32).sum();
+            let expected = value as f32 * max as f32 / 255.0 * 16.0;
+            assert!(
+                (levels as f32 - expected).abs() <= 0.5,
+                "value {} max {}: {} != {}",
+                value,
+                max,
+                levels,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn diffuses_errors() {
+        let mut src = [0, 64, 128, 192, 255].map(Gray8::new);
+        let src = FrameBuf::new(&mut src, 5, 1);
+        let mut data = [BinaryColor::Off; 5 * 2];
+        let mut fbuf = FrameBuf::new(&mut data, 5, 2);
+        let mut errors = [0; 6];
+        src.diffuse_into(&mut fbuf, &mut errors);
+        assert_eq!(
+            data[..5],
+            [0, 0, 1, 1, 1].map(|on| BinaryColor::from(on == 1))
+        );
+
+        // An average gray keeps its brightness, also when clipped
+        let mut data = [BinaryColor::Off; 5 * 2];
+        let mut fbuf = FrameBuf::new(&mut data, 5, 2);
+        let mut errors = [0; 6];
+        let mut target = ErrorDiffusion::new(&mut fbuf, &mut errors);
+        let area = Rectangle::new(Point::new(-3, 0), Size::new(8, 2));
+        target.fill_solid(&area, Gray8::new(102)).unwrap();
+        assert_eq!(data.iter().filter(|c| c.is_on()).count(), 4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn needs_room_for_errors() {
+        let mut data = [BinaryColor::Off; 5];
+        let mut fbuf = FrameBuf::new(&mut data, 5, 1);
+        let mut errors = [0; 5];
+        ErrorDiffusion::<Gray8, _>::new(&mut fbuf, &mut errors);
+    }
+}
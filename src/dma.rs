@@ -0,0 +1,344 @@
+//! Flushing by DMA with ownership transfer.
+//!
+//! [`FrameBuf::transfer`] moves the framebuffer into a [`DmaTransfer`] while a
+//! DMA channel reads it, like the one-shot DMA APIs of most HALs. The
+//! framebuffer is only given back by [`DmaTransfer::wait`], so the type system
+//! prevents drawing into it while it is being transferred.
+//!
+//! The DMA peripheral is abstracted by the [`DmaChannel`] trait, which is
+//! implemented on top of the HAL's DMA channel (and e.g. the SPI peripheral
+//! it feeds). Its [`pre_dma`](DmaChannel::pre_dma) and
+//! [`post_dma`](DmaChannel::post_dma) hooks are called around every transfer
+//! for cache maintenance:
+//!
+//! ```rust
+//! use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+//! use embedded_graphics_framebuf::{
+//!     dma::DmaChannel,
+//!     double_buffer::DoubleBuffer,
+//! };
+//!
+//! struct SpiDma {
+//!     remaining: usize,
+//! }
+//!
+//! impl DmaChannel for SpiDma {
+//!     fn start(&mut self, ptr: *const u8, len: usize) {
+//!         // Program the source address and length, and enable the channel.
+//!         self.remaining = len;
+//!     }
+//!
+//!     fn is_done(&mut self) -> bool {
+//!         // Check the transfer complete flag of the channel.
+//!         self.remaining = self.remaining.saturating_sub(4096);
+//!         self.remaining == 0
+//!     }
+//! }
+//!
+//! let a = Box::leak(Box::new([Rgb565::BLACK; 64 * 32]));
+//! let b = Box::leak(Box::new([Rgb565::BLACK; 64 * 32]));
+//! let mut buffers = DoubleBuffer::new(a, b, 64, 32);
+//! let mut channel = SpiDma { remaining: 0 };
+//! for frame in 0..3 {
+//!     buffers.back().clear(Rgb565::new(frame, 0, 0)).unwrap();
+//!     buffers.swap();
+//!     let transfer = buffers.take_front().unwrap().transfer(channel);
+//!     // Draw the next frame into `buffers.back()` meanwhile ...
+//!     let (front, dma) = transfer.wait();
+//!     buffers.return_front(front);
+//!     channel = dma;
+//! }
+//! ```
+//!
+//! # Data caches
+//!
+//! On MCUs with a data cache (e.g. Cortex-M7 based STM32F7/H7 or i.MX RT), the
+//! last pixels drawn may still be in the cache when the DMA reads the
+//! framebuffer from memory, which shows up as corrupted output. The cache has
+//! to be cleaned before each transfer, which an implementation of
+//! [`DmaChannel::pre_dma`] can do. With the `cortex-m` feature,
+//! [`DCacheCleaned`] wraps a channel to do so with the SCB, and
+//! [`FrameBuf::clean_dcache`] cleans the framebuffer for other DMA APIs.
+//! Framebuffers in cached memory should be an [`AlignedBuffer`] aligned to
+//! the cache line size.
+//!
+//! [`AlignedBuffer`]: crate::backends::AlignedBuffer
+
+use crate::{backends::DMACapableFrameBufferBackend, FrameBuf};
+
+/// A DMA channel sending a buffer to a display, see the
+/// [module documentation](self).
+pub trait DmaChannel {
+    /// Start reading `len` bytes from `ptr`.
+    ///
+    /// The memory stays valid and unchanged until [`DmaChannel::is_done`]
+    /// returns `true`.
+    fn start(&mut self, ptr: *const u8, len: usize);
+
+    /// Whether the transfer started last has completed.
+    fn is_done(&mut self) -> bool;
+
+    /// Called before `len` bytes at `ptr` are transferred, e.g. to clean
+    /// the data cache. Does nothing by default.
+    fn pre_dma(&mut self, ptr: *const u8, len: usize) {
+        let _ = (ptr, len);
+    }
+
+    /// Called once the transfer of `len` bytes at `ptr` has completed, e.g.
+    /// to invalidate the data cache. Does nothing by default.
+    fn post_dma(&mut self, ptr: *const u8, len: usize) {
+        let _ = (ptr, len);
+    }
+}
+
+impl<C, B: DMACapableFrameBufferBackend<Color = C> + 'static> FrameBuf<C, B> {
+    /// Start sending the framebuffer with `channel`, see the
+    /// [module documentation](self).
+    ///
+    /// The backend has to be `'static` (e.g. a `&'static mut` array), as a
+    /// transfer which is leaked with [`core::mem::forget`] keeps reading it.
+    pub fn transfer<D: DmaChannel>(self, mut channel: D) -> DmaTransfer<C, B, D> {
+        // The framebuffer is owned by the transfer until the channel is done,
+        // and the backend's memory doesn't move with it.
+        let (ptr, len) = self.dma_bytes();
+        channel.pre_dma(ptr, len);
+        channel.start(ptr, len);
+        DmaTransfer {
+            parts: Some((self, channel)),
+            done: false,
+        }
+    }
+
+    /// Clean the data cache of the framebuffer's memory, so a DMA transfer
+    /// reads the pixels drawn last. See the [module documentation](self).
+    #[cfg(feature = "cortex-m")]
+    pub fn clean_dcache(&self, scb: &mut cortex_m::peripheral::SCB) {
+        let (ptr, len) = self.dma_bytes();
+        scb.clean_dcache_by_address(ptr as usize, len);
+    }
+}
+
+impl<C, B: DMACapableFrameBufferBackend<Color = C>> FrameBuf<C, B> {
+    /// Split the bytes of the framebuffer into chunks of at most `max_bytes`,
+    /// for DMA channels with a maximum transfer length (e.g. 4095 bytes on
+    /// some STM32 channels). Yields the offset of each chunk into the
+    /// framebuffer's bytes and the chunk.
+    ///
+    /// `max_bytes` is rounded down to whole pixels, so a chunk never ends in
+    /// the middle of a pixel.
+    ///
+    /// # Panic
+    /// Panics if `max_bytes` is smaller than a pixel.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+    /// use embedded_graphics_framebuf::FrameBuf;
+    ///
+    /// let mut data = [Rgb565::BLACK; 320 * 240];
+    /// let fbuf = FrameBuf::new(&mut data, 320, 240);
+    /// for (offset, chunk) in fbuf.dma_chunks(4095) {
+    ///     assert_eq!(offset % 4094, 0);
+    ///     assert!(chunk.len() <= 4094);
+    ///     // dma.write(chunk).wait();
+    /// }
+    /// assert_eq!(fbuf.dma_chunks(4095).len(), 38);
+    /// ```
+    pub fn dma_chunks(&self, max_bytes: usize) -> DmaChunks<'_> {
+        let pixel = core::mem::size_of::<C>();
+        assert!(
+            max_bytes >= pixel,
+            "DMA chunks of {} bytes can't hold a pixel of {} bytes",
+            max_bytes,
+            pixel
+        );
+        // SAFETY: the memory is readable by DMA, i.e. plain bytes, and stays
+        // borrowed for the lifetime of the slice.
+        let bytes = unsafe {
+            let (ptr, len) = self.dma_bytes();
+            core::slice::from_raw_parts(ptr, len)
+        };
+        DmaChunks {
+            chunks: bytes.chunks(max_bytes / pixel * pixel),
+            offset: 0,
+        }
+    }
+}
+
+/// Iterator over the chunks of a framebuffer's bytes, see
+/// [`FrameBuf::dma_chunks`].
+pub struct DmaChunks<'a> {
+    chunks: core::slice::Chunks<'a, u8>,
+    offset: usize,
+}
+
+impl<'a> Iterator for DmaChunks<'a> {
+    type Item = (usize, &'a [u8]);
+
+    fn next(&mut self) -> Option<(usize, &'a [u8])> {
+        let chunk = self.chunks.next()?;
+        self.offset += chunk.len();
+        Some((self.offset - chunk.len(), chunk))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl ExactSizeIterator for DmaChunks<'_> {}
+
+/// A framebuffer being sent by DMA, see [`FrameBuf::transfer`].
+///
+/// Dropping the transfer blocks until it is done.
+pub struct DmaTransfer<C, B: DMACapableFrameBufferBackend<Color = C>, D: DmaChannel> {
+    /// Only `None` while the transfer is taken apart
+    parts: Option<(FrameBuf<C, B>, D)>,
+    /// Whether the channel is done and `post_dma` was called
+    done: bool,
+}
+
+impl<C, B: DMACapableFrameBufferBackend<Color = C>, D: DmaChannel> DmaTransfer<C, B, D> {
+    /// Whether the transfer has completed.
+    pub fn is_done(&mut self) -> bool {
+        if let Some((fbuf, channel)) = self.parts.as_mut().filter(|_| !self.done) {
+            if channel.is_done() {
+                let (ptr, len) = fbuf.dma_bytes();
+                channel.post_dma(ptr, len);
+                self.done = true;
+            }
+        }
+        self.done || self.parts.is_none()
+    }
+
+    /// Block until the transfer has completed, and return the framebuffer and
+    /// the channel.
+    pub fn wait(mut self) -> (FrameBuf<C, B>, D) {
+        while !self.is_done() {
+            core::hint::spin_loop();
+        }
+        self.parts.take().unwrap()
+    }
+}
+
+impl<C, B: DMACapableFrameBufferBackend<Color = C>, D: DmaChannel> Drop for DmaTransfer<C, B, D> {
+    fn drop(&mut self) {
+        while !self.is_done() {
+            core::hint::spin_loop();
+        }
+    }
+}
+
+/// A DMA channel cleaning the data cache of Cortex-M7 cores before each
+/// transfer, see the [module documentation](self).
+#[cfg(feature = "cortex-m")]
+pub struct DCacheCleaned<D> {
+    channel: D,
+    scb: cortex_m::peripheral::SCB,
+}
+
+#[cfg(feature = "cortex-m")]
+impl<D: DmaChannel> DCacheCleaned<D> {
+    /// Wrap `channel`, using `scb` for cache maintenance.
+    pub fn new(channel: D, scb: cortex_m::peripheral::SCB) -> Self {
+        Self { channel, scb }
+    }
+
+    /// Return the channel and the SCB.
+    pub fn into_inner(self) -> (D, cortex_m::peripheral::SCB) {
+        (self.channel, self.scb)
+    }
+}
+
+#[cfg(feature = "cortex-m")]
+impl<D: DmaChannel> DmaChannel for DCacheCleaned<D> {
+    fn start(&mut self, ptr: *const u8, len: usize) {
+        self.channel.start(ptr, len)
+    }
+
+    fn is_done(&mut self) -> bool {
+        self.channel.is_done()
+    }
+
+    fn pre_dma(&mut self, ptr: *const u8, len: usize) {
+        self.scb.clean_dcache_by_address(ptr as usize, len);
+        self.channel.pre_dma(ptr, len)
+    }
+
+    fn post_dma(&mut self, ptr: *const u8, len: usize) {
+        self.channel.post_dma(ptr, len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use embedded_graphics::{
+        pixelcolor::{raw::ToBytes, Rgb565},
+        prelude::RgbColor,
+    };
+    use std::{boxed::Box, vec::Vec};
+
+    #[derive(Default)]
+    struct Channel {
+        started: Option<(*const u8, usize)>,
+        polls: usize,
+        hooks: [usize; 2],
+    }
+
+    impl DmaChannel for Channel {
+        fn start(&mut self, ptr: *const u8, len: usize) {
+            self.started = Some((ptr, len));
+            self.polls = 0;
+        }
+
+        fn is_done(&mut self) -> bool {
+            self.polls += 1;
+            self.polls > 3
+        }
+
+        fn pre_dma(&mut self, _ptr: *const u8, _len: usize) {
+            assert_eq!(self.started, None);
+            self.hooks[0] += 1;
+        }
+
+        fn post_dma(&mut self, _ptr: *const u8, _len: usize) {
+            self.hooks[1] += 1;
+        }
+    }
+
+    #[test]
+    fn splits_into_chunks() {
+        let mut data = [Rgb565::new(1, 2, 3); 5];
+        let fbuf = FrameBuf::new(&mut data, 5, 1);
+        let chunks: Vec<_> = fbuf.dma_chunks(5).collect();
+        assert_eq!(
+            chunks.iter().map(|(offset, _)| *offset).collect::<Vec<_>>(),
+            [0, 4, 8]
+        );
+        assert_eq!(chunks[2].1.len(), 2);
+        assert_eq!(chunks[0].1[..2], Rgb565::new(1, 2, 3).to_ne_bytes());
+    }
+
+    #[test]
+    #[should_panic]
+    fn chunks_hold_a_pixel() {
+        let mut data = [Rgb565::BLACK; 5];
+        FrameBuf::new(&mut data, 5, 1).dma_chunks(1);
+    }
+
+    #[test]
+    fn transfers_ownership() {
+        let data = Box::leak(Box::new([Rgb565::BLACK; 4 * 2]));
+        let ptr = data.as_ptr() as *const u8;
+        let mut transfer = FrameBuf::new(data, 4, 2).transfer(Channel::default());
+        assert!(!transfer.is_done());
+        let (fbuf, channel) = transfer.wait();
+        assert_eq!(channel.started, Some((ptr, 4 * 2 * 2)));
+        assert_eq!(channel.polls, 4);
+        assert_eq!(channel.hooks, [1, 1]);
+        assert_eq!(fbuf.width(), 4);
+    }
+}
@@ -0,0 +1,287 @@
+//! Double buffering.
+//!
+//! Tearing free animations need two framebuffers: the front buffer is shown
+//! (or streamed to the display by DMA), while the next frame is drawn into the
+//! back buffer. A [`DoubleBuffer`] manages the two, and lends the front buffer
+//! out for the duration of a DMA transfer.
+//!
+//! Swapping (or starting a flush) in the middle of the panel's refresh still
+//! tears. [`DoubleBuffer::swap_on_vsync`] defers the swap until a [`Vsync`]
+//! is signaled from a vblank callback or tear effect (TE) interrupt. With
+//! the `embedded-hal` feature, [`DoubleBuffer::swap_on_te`] polls the TE pin
+//! of e.g. an ST7789 instead.
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use embedded_graphics::prelude::PixelColor;
+
+use crate::{backends::FrameBufferBackend, FrameBuf};
+
+/// A front and a back framebuffer of the same size.
+///
+/// # Example
+/// ```rust
+/// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+/// use embedded_graphics_framebuf::double_buffer::DoubleBuffer;
+///
+/// let (mut a, mut b) = ([Rgb565::BLACK; 64 * 32], [Rgb565::BLACK; 64 * 32]);
+/// let mut buffers = DoubleBuffer::new(&mut a, &mut b, 64, 32);
+/// for frame in 0..3 {
+///     buffers.back().clear(Rgb565::new(frame, 0, 0)).unwrap();
+///     buffers.swap();
+///
+///     // Hand the front buffer to the DMA, and reclaim it when it is done.
+///     let front = buffers.take_front().unwrap();
+///     for (_offset, chunk) in front.dma_chunks(4095) {
+///         // dma.write(chunk).wait();
+///     }
+///     buffers.return_front(front);
+/// }
+/// assert_eq!(buffers.front().unwrap().get_color_at(Point::zero()), Rgb565::new(2, 0, 0));
+/// ```
+pub struct DoubleBuffer<C, B: FrameBufferBackend<Color = C>> {
+    back: FrameBuf<C, B>,
+    front: Option<FrameBuf<C, B>>,
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> DoubleBuffer<C, B> {
+    /// Create a double buffer of `width` x `height` pixels from two backends.
+    ///
+    /// # Panic
+    /// Panics if the size of either backend does not match the given width
+    /// and height.
+    pub fn new(front: B, back: B, width: usize, height: usize) -> Self {
+        Self {
+            back: FrameBuf::new(back, width, height),
+            front: Some(FrameBuf::new(front, width, height)),
+        }
+    }
+
+    /// The framebuffer to draw the next frame into.
+    pub fn back(&mut self) -> &mut FrameBuf<C, B> {
+        &mut self.back
+    }
+
+    /// The framebuffer with the last complete frame, or `None` if it is lent
+    /// out with [`DoubleBuffer::take_front`].
+    pub fn front(&self) -> Option<&FrameBuf<C, B>> {
+        self.front.as_ref()
+    }
+
+    /// Make the back buffer the front buffer and vice versa.
+    ///
+    /// The new back buffer is marked completely [dirty](crate::dirty), as it
+    /// holds an older frame.
+    ///
+    /// # Panic
+    /// Panics if the front buffer is lent out.
+    pub fn swap(&mut self) {
+        let front = self
+            .front
+            .as_mut()
+            .expect("DoubleBuffer can't swap while the front buffer is lent out");
+        core::mem::swap(&mut self.back, front);
+        self.back.mark_all_dirty();
+    }
+
+    /// Wait for the next vertical blank signaled by `vsync`, then
+    /// [swap](DoubleBuffer::swap).
+    ///
+    /// # Panic
+    /// Panics if the front buffer is lent out.
+    pub fn swap_on_vsync(&mut self, vsync: &Vsync) {
+        vsync.wait();
+        self.swap();
+    }
+
+    /// Wait for a rising edge of the tear effect pin `te`, then
+    /// [swap](DoubleBuffer::swap).
+    ///
+    /// The panel raises the pin during its vertical blank, so a transfer
+    /// started right after the swap doesn't overtake the refresh.
+    ///
+    /// # Panic
+    /// Panics if the front buffer is lent out.
+    #[cfg(feature = "embedded-hal")]
+    pub fn swap_on_te<P: embedded_hal::digital::InputPin>(
+        &mut self,
+        te: &mut P,
+    ) -> Result<(), P::Error> {
+        while te.is_high()? {}
+        while te.is_low()? {}
+        self.swap();
+        Ok(())
+    }
+
+    /// Lend the front buffer out, e.g. to a DMA transfer. Returns `None` if
+    /// it is already lent out.
+    pub fn take_front(&mut self) -> Option<FrameBuf<C, B>> {
+        self.front.take()
+    }
+
+    /// Return the front buffer taken with [`DoubleBuffer::take_front`], e.g.
+    /// when the DMA transfer completed.
+    ///
+    /// # Panic
+    /// Panics if the front buffer wasn't lent out or has a different size.
+    pub fn return_front(&mut self, front: FrameBuf<C, B>) {
+        assert!(
+            self.front.is_none(),
+            "DoubleBuffer front buffer wasn't lent out"
+        );
+        assert_eq!(
+            front.size(),
+            self.back.size(),
+            "DoubleBuffer front buffer has the wrong size"
+        );
+        self.front = Some(front);
+    }
+
+    /// Return the front and the back backend.
+    ///
+    /// # Panic
+    /// Panics if the front buffer is lent out.
+    pub fn into_inner(self) -> (B, B) {
+        let front = self.front.expect("DoubleBuffer front buffer is lent out");
+        (front.into_inner(), self.back.into_inner())
+    }
+}
+
+/// A vertical blank signal, shared between the vblank callback or TE
+/// interrupt and the code waiting for it.
+///
+/// # Example
+/// ```rust,ignore
+/// static VSYNC: Vsync = Vsync::new();
+///
+/// #[interrupt]
+/// fn EXTI4() {
+///     // clear the interrupt of the TE pin ...
+///     VSYNC.signal();
+/// }
+///
+/// loop {
+///     draw_ui(buffers.back());
+///     buffers.swap_on_vsync(&VSYNC);
+///     flush(buffers.front().unwrap());
+/// }
+/// ```
+pub struct Vsync {
+    blank: AtomicBool,
+}
+
+impl Vsync {
+    /// Create a signal without a pending vertical blank.
+    pub const fn new() -> Self {
+        Self {
+            blank: AtomicBool::new(false),
+        }
+    }
+
+    /// Signal a vertical blank, e.g. from an interrupt handler.
+    pub fn signal(&self) {
+        self.blank.store(true, Ordering::Release);
+    }
+
+    /// Block until the next vertical blank is signaled.
+    pub fn wait(&self) {
+        self.blank.store(false, Ordering::Release);
+        while !self.blank.load(Ordering::Acquire) {
+            core::hint::spin_loop();
+        }
+    }
+}
+
+impl Default for Vsync {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::{
+        draw_target::DrawTarget,
+        pixelcolor::Gray8,
+        prelude::{Point, Size},
+        primitives::Rectangle,
+    };
+
+    #[test]
+    fn swaps() {
+        let mut buffers = DoubleBuffer::new([Gray8::new(0); 4], [Gray8::new(1); 4], 2, 2);
+        buffers.back().take_dirty_area();
+        buffers.back().clear(Gray8::new(2)).unwrap();
+        buffers.swap();
+        assert_eq!(
+            buffers.back().take_dirty_area(),
+            Some(Rectangle::new(Point::zero(), Size::new(2, 2)))
+        );
+        assert_eq!(buffers.back().get_color_at(Point::zero()), Gray8::new(0));
+
+        let front = buffers.take_front().unwrap();
+        assert!(buffers.front().is_none());
+        assert!(buffers.take_front().is_none());
+        buffers.return_front(front);
+        assert_eq!(
+            buffers.into_inner(),
+            ([Gray8::new(2); 4], [Gray8::new(0); 4])
+        );
+    }
+
+    #[test]
+    fn swaps_on_vsync() {
+        extern crate std;
+
+        static VSYNC: Vsync = Vsync::new();
+        static SWAPPED: AtomicBool = AtomicBool::new(false);
+        let mut buffers = DoubleBuffer::new([Gray8::new(0); 4], [Gray8::new(1); 4], 2, 2);
+        let vblank = std::thread::spawn(|| {
+            while !SWAPPED.load(Ordering::Acquire) {
+                VSYNC.signal();
+                std::thread::yield_now();
+            }
+        });
+        buffers.swap_on_vsync(&VSYNC);
+        SWAPPED.store(true, Ordering::Release);
+        vblank.join().unwrap();
+        assert_eq!(buffers.back().get_color_at(Point::zero()), Gray8::new(0));
+    }
+
+    #[cfg(feature = "embedded-hal")]
+    #[test]
+    fn swaps_on_te() {
+        use core::convert::Infallible;
+
+        struct TePin<'a>(&'a [bool]);
+        impl embedded_hal::digital::ErrorType for TePin<'_> {
+            type Error = Infallible;
+        }
+        impl embedded_hal::digital::InputPin for TePin<'_> {
+            fn is_high(&mut self) -> Result<bool, Infallible> {
+                let (level, rest) = self.0.split_first().unwrap();
+                self.0 = rest;
+                Ok(*level)
+            }
+
+            fn is_low(&mut self) -> Result<bool, Infallible> {
+                self.is_high().map(|high| !high)
+            }
+        }
+
+        let mut buffers = DoubleBuffer::new([Gray8::new(0); 4], [Gray8::new(1); 4], 2, 2);
+        let mut te = TePin(&[true, true, false, false, true]);
+        buffers.swap_on_te(&mut te).unwrap();
+        assert!(te.0.is_empty());
+        assert_eq!(buffers.back().get_color_at(Point::zero()), Gray8::new(0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn swaps_only_when_front_returned() {
+        let mut buffers = DoubleBuffer::new([Gray8::new(0); 4], [Gray8::new(1); 4], 2, 2);
+        let _front = buffers.take_front();
+        buffers.swap();
+    }
+}
@@ -0,0 +1,216 @@
+//! Adapters for display driver crates.
+//!
+//! Drivers which can write a stream of pixels into an address window implement
+//! [`WindowedDisplay`]. [`FrameBuf::flush_area_to`] then sets the window to the
+//! area to update and pushes only the pixels of that area, which makes
+//! partial updates cheap.
+//!
+//! Implementations are provided for the
+//! [`st7789`](https://crates.io/crates/st7789) (feature `st7789`) and
+//! [`st7735-lcd`](https://crates.io/crates/st7735-lcd) (feature `st7735-lcd`)
+//! crates.
+//!
+//! ```rust,ignore
+//! let mut display = ST7789::new(di, Some(rst), Some(bl), 240, 135);
+//! fbuf.flush_area_to(&mut display, Rectangle::new(Point::new(10, 10), Size::new(40, 16)))?;
+//! ```
+
+use embedded_graphics::{
+    pixelcolor::IntoStorage,
+    prelude::{PixelColor, Point, Size},
+    primitives::{PointsIter, Rectangle},
+};
+
+use crate::{backends::FrameBufferBackend, FrameBuf};
+
+/// A display that can write 16 bit pixels into an address window.
+pub trait WindowedDisplay {
+    type Error;
+
+    /// Write `colors` into the window spanning `sx..=ex` and `sy..=ey`.
+    fn set_pixels<I>(
+        &mut self,
+        sx: u16,
+        sy: u16,
+        ex: u16,
+        ey: u16,
+        colors: I,
+    ) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = u16>;
+}
+
+impl<C, B> FrameBuf<C, B>
+where
+    C: PixelColor + IntoStorage<Storage = u16>,
+    B: FrameBufferBackend<Color = C>,
+{
+    /// Write the whole framebuffer to a [`WindowedDisplay`].
+    ///
+    /// The framebuffer's origin is used as position on the display.
+    pub fn flush_to<D: WindowedDisplay>(&self, display: &mut D) -> Result<(), D::Error> {
+        self.flush_area_to(display, Rectangle::new(Point::zero(), self.size()))
+    }
+
+    /// Write the [dirty area](crate::dirty) to a [`WindowedDisplay`] and mark
+    /// the framebuffer as clean. Nothing is written if nothing changed.
+    pub fn flush_dirty_to<D: WindowedDisplay>(&mut self, display: &mut D) -> Result<(), D::Error> {
+        if let Some(area) = self.dirty_area() {
+            self.flush_area_to(display, area)?;
+            self.take_dirty_area();
+        }
+        Ok(())
+    }
+
+    /// Write an area of the framebuffer to a [`WindowedDisplay`].
+    ///
+    /// `area` is given in framebuffer coordinates and clipped to the
+    /// framebuffer.
+    pub fn flush_area_to<D: WindowedDisplay>(
+        &self,
+        display: &mut D,
+        area: Rectangle,
+    ) -> Result<(), D::Error> {
+        let area = area.intersection(&Rectangle::new(Point::zero(), self.size()));
+        let bottom_right = match area.bottom_right() {
+            Some(p) if area.size != Size::zero() => p,
+            _ => return Ok(()),
+        };
+        let start = area.top_left + self.origin();
+        let end = bottom_right + self.origin();
+        display.set_pixels(
+            start.x as u16,
+            start.y as u16,
+            end.x as u16,
+            end.y as u16,
+            area.points().map(|p| self.get_color_at(p).into_storage()),
+        )
+    }
+}
+
+#[cfg(feature = "st7789")]
+impl<DI, RST, BL> WindowedDisplay for st7789::ST7789<DI, RST, BL>
+where
+    DI: display_interface::WriteOnlyDataCommand,
+    RST: embedded_hal_02::digital::v2::OutputPin,
+    BL: embedded_hal_02::digital::v2::OutputPin<Error = RST::Error>,
+{
+    type Error = st7789::Error<RST::Error>;
+
+    fn set_pixels<I>(
+        &mut self,
+        sx: u16,
+        sy: u16,
+        ex: u16,
+        ey: u16,
+        colors: I,
+    ) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = u16>,
+    {
+        st7789::ST7789::set_pixels(self, sx, sy, ex, ey, colors)
+    }
+}
+
+#[cfg(feature = "st7735-lcd")]
+impl<SPI, DC, RST> WindowedDisplay for st7735_lcd::ST7735<SPI, DC, RST>
+where
+    SPI: embedded_hal::spi::SpiDevice,
+    DC: embedded_hal::digital::OutputPin,
+    RST: embedded_hal::digital::OutputPin,
+{
+    type Error = ();
+
+    fn set_pixels<I>(
+        &mut self,
+        sx: u16,
+        sy: u16,
+        ex: u16,
+        ey: u16,
+        colors: I,
+    ) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = u16>,
+    {
+        st7735_lcd::ST7735::set_pixels(self, sx, sy, ex, ey, colors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use embedded_graphics::pixelcolor::Rgb565;
+    use embedded_graphics::prelude::RgbColor;
+    use std::vec::Vec;
+
+    #[derive(Default)]
+    struct Display {
+        window: Option<(u16, u16, u16, u16)>,
+        pixels: Vec<u16>,
+    }
+
+    impl WindowedDisplay for Display {
+        type Error = ();
+
+        fn set_pixels<I>(&mut self, sx: u16, sy: u16, ex: u16, ey: u16, colors: I) -> Result<(), ()>
+        where
+            I: IntoIterator<Item = u16>,
+        {
+            self.window = Some((sx, sy, ex, ey));
+            self.pixels.extend(colors);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn flushes_area_with_origin() {
+        let mut data = [Rgb565::BLACK; 4 * 3];
+        let mut fbuf = FrameBuf::new_with_origin(&mut data, 4, 3, Point::new(10, 20));
+        fbuf.set_color_at(Point::new(3, 2), Rgb565::RED);
+
+        let mut display = Display::default();
+        fbuf.flush_area_to(
+            &mut display,
+            Rectangle::new(Point::new(2, 1), Size::new(10, 10)),
+        )
+        .unwrap();
+        assert_eq!(display.window, Some((12, 21, 13, 22)));
+        assert_eq!(display.pixels, [0, 0, 0, Rgb565::RED.into_storage()]);
+    }
+
+    #[test]
+    fn skips_empty_area() {
+        let mut data = [Rgb565::BLACK; 4 * 3];
+        let fbuf = FrameBuf::new(&mut data, 4, 3);
+
+        let mut display = Display::default();
+        fbuf.flush_area_to(
+            &mut display,
+            Rectangle::new(Point::new(5, 1), Size::new(2, 2)),
+        )
+        .unwrap();
+        assert_eq!(display.window, None);
+        fbuf.flush_to(&mut display).unwrap();
+        assert_eq!(display.window, Some((0, 0, 3, 2)));
+        assert_eq!(display.pixels.len(), 12);
+    }
+
+    #[test]
+    fn flushes_dirty_area() {
+        let mut data = [Rgb565::BLACK; 4 * 3];
+        let mut fbuf = FrameBuf::new(&mut data, 4, 3);
+        let mut display = Display::default();
+        fbuf.flush_dirty_to(&mut display).unwrap();
+        assert_eq!(display.window, Some((0, 0, 3, 2)));
+
+        fbuf.set_color_at(Point::new(1, 2), Rgb565::RED);
+        fbuf.flush_dirty_to(&mut display).unwrap();
+        assert_eq!(display.window, Some((1, 2, 1, 2)));
+
+        display.window = None;
+        fbuf.flush_dirty_to(&mut display).unwrap();
+        assert_eq!(display.window, None);
+    }
+}
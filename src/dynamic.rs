@@ -0,0 +1,118 @@
+//! Framebuffers behind trait objects.
+//!
+//! [`FrameBuf`] is generic over its color and backend, which makes passing it
+//! between independently compiled parts of a firmware (plugins, apps, ...)
+//! awkward. [`DynFrameBuffer`] is an object safe trait with the colors erased
+//! to their raw `u32` values (see [`RawData`]), so a
+//! `&mut dyn DynFrameBuffer` can be passed around instead.
+//!
+//! ```rust
+//! use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+//! use embedded_graphics_framebuf::{dynamic::DynFrameBuffer, FrameBuf};
+//!
+//! fn draw_cursor(fbuf: &mut dyn DynFrameBuffer) {
+//!     fbuf.set_raw(Point::new(1, 1), 0xFFFF);
+//! }
+//!
+//! let mut data = [Rgb565::BLACK; 4 * 4];
+//! let mut fbuf = FrameBuf::new(&mut data, 4, 4);
+//! draw_cursor(&mut fbuf);
+//! assert_eq!(fbuf.get_color_at(Point::new(1, 1)), Rgb565::WHITE);
+//! ```
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    pixelcolor::raw::RawData,
+    prelude::{PixelColor, Point, Size},
+    Pixel,
+};
+
+use crate::{backends::FrameBufferBackend, FrameBuf};
+
+/// Object safe interface of a framebuffer with type erased colors.
+pub trait DynFrameBuffer {
+    /// The size of the framebuffer.
+    fn size(&self) -> Size;
+
+    /// The number of bits of a raw color.
+    fn bits_per_pixel(&self) -> usize;
+
+    /// Set the pixel at `p` to the color with the raw value `raw`. Pixels
+    /// outside of the framebuffer are ignored.
+    fn set_raw(&mut self, p: Point, raw: u32);
+
+    /// Get the raw value of the color at `p`, or `None` if `p` is outside of
+    /// the framebuffer.
+    fn get_raw(&self, p: Point) -> Option<u32>;
+
+    /// Call `f` for all pixels, to flush the framebuffer. The points are in
+    /// display coordinates, like the ones of the
+    /// [`PixelIterator`](crate::PixelIterator).
+    fn for_each_raw(&self, f: &mut dyn FnMut(Point, u32));
+}
+
+impl<C, B> DynFrameBuffer for FrameBuf<C, B>
+where
+    C: PixelColor + From<C::Raw>,
+    C::Raw: From<C>,
+    <C::Raw as RawData>::Storage: Into<u32>,
+    B: FrameBufferBackend<Color = C>,
+{
+    fn size(&self) -> Size {
+        FrameBuf::size(self)
+    }
+
+    fn bits_per_pixel(&self) -> usize {
+        C::Raw::BITS_PER_PIXEL
+    }
+
+    fn set_raw(&mut self, p: Point, raw: u32) {
+        let color = C::from(C::Raw::from_u32(raw));
+        self.draw_iter([Pixel(p, color)]).unwrap();
+    }
+
+    fn get_raw(&self, p: Point) -> Option<u32> {
+        self.get(p)
+            .map(|color| C::Raw::from(color).into_inner().into())
+    }
+
+    fn for_each_raw(&self, f: &mut dyn FnMut(Point, u32)) {
+        for Pixel(p, color) in self {
+            f(p, C::Raw::from(color).into_inner().into());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::pixelcolor::{BinaryColor, Rgb565, RgbColor};
+
+    #[test]
+    fn erases_colors() {
+        let mut data = [Rgb565::BLACK; 2 * 2];
+        let mut fbuf = FrameBuf::new_with_origin(&mut data, 2, 2, Point::new(10, 0));
+        let dyn_fbuf: &mut dyn DynFrameBuffer = &mut fbuf;
+        assert_eq!(dyn_fbuf.size(), Size::new(2, 2));
+        assert_eq!(dyn_fbuf.bits_per_pixel(), 16);
+
+        dyn_fbuf.set_raw(Point::new(1, 0), 0xF800);
+        dyn_fbuf.set_raw(Point::new(2, 0), 0xFFFF);
+        assert_eq!(dyn_fbuf.get_raw(Point::new(1, 0)), Some(0xF800));
+        assert_eq!(dyn_fbuf.get_raw(Point::new(2, 0)), None);
+
+        let mut sum = (Point::zero(), 0);
+        dyn_fbuf.for_each_raw(&mut |p, raw| sum = (sum.0 + p, sum.1 + raw));
+        assert_eq!(sum, (Point::new(42, 2), 0xF800));
+    }
+
+    #[test]
+    fn erases_binary_colors() {
+        let mut data = [BinaryColor::Off; 3];
+        let mut fbuf = FrameBuf::new(&mut data, 3, 1);
+        let dyn_fbuf: &mut dyn DynFrameBuffer = &mut fbuf;
+        dyn_fbuf.set_raw(Point::new(2, 0), 1);
+        assert_eq!(dyn_fbuf.bits_per_pixel(), 1);
+        assert_eq!(dyn_fbuf.get_raw(Point::new(2, 0)), Some(1));
+    }
+}
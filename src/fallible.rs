@@ -0,0 +1,240 @@
+//! Framebuffers on backends whose accesses can fail.
+//!
+//! A [`FrameBuf`](crate::FrameBuf) needs a backend that never fails, which
+//! rules out memory that is not directly addressable, like SPI RAM or FRAM.
+//! A [`FallibleFrameBuf`] works on top of a
+//! [`FallibleFrameBufferBackend`] instead, and returns the errors of the
+//! backend from its [`DrawTarget`] implementation.
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Dimensions, OriginDimensions},
+    prelude::{PixelColor, Point, Size},
+    primitives::Rectangle,
+    Pixel,
+};
+
+use crate::backends::FallibleFrameBufferBackend;
+
+/// A framebuffer on top of a [`FallibleFrameBufferBackend`].
+///
+/// Pixels are stored row by row. Pixels outside of the framebuffer are
+/// discarded, like in [`FrameBuf`](crate::FrameBuf), while the first error of
+/// the backend aborts drawing and is returned.
+///
+/// # Example
+/// ```rust
+/// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+/// use embedded_graphics_framebuf::{backends::FallibleFrameBufferBackend, fallible::FallibleFrameBuf};
+///
+/// /// Memory on an external chip, which may not respond.
+/// struct ExternalRam {
+///     connected: bool,
+/// }
+///
+/// #[derive(Debug, PartialEq)]
+/// struct NotConnected;
+///
+/// impl FallibleFrameBufferBackend for ExternalRam {
+///     type Color = Rgb565;
+///     type Error = NotConnected;
+///
+///     fn try_set(&mut self, _index: usize, _color: Rgb565) -> Result<(), NotConnected> {
+///         self.connected.then_some(()).ok_or(NotConnected)
+///     }
+///
+///     fn try_get(&mut self, _index: usize) -> Result<Rgb565, NotConnected> {
+///         self.connected.then_some(Rgb565::BLACK).ok_or(NotConnected)
+///     }
+///
+///     fn capacity(&self) -> usize {
+///         320 * 240
+///     }
+/// }
+///
+/// let mut fbuf = FallibleFrameBuf::new(ExternalRam { connected: false }, 320, 240);
+/// assert_eq!(fbuf.clear(Rgb565::BLUE), Err(NotConnected));
+/// ```
+pub struct FallibleFrameBuf<C, B: FallibleFrameBufferBackend<Color = C>> {
+    pub data: B,
+    width: usize,
+    height: usize,
+}
+
+impl<C: PixelColor, B: FallibleFrameBufferBackend<Color = C>> FallibleFrameBuf<C, B> {
+    /// Create a new [`FallibleFrameBuf`] on top of a backend.
+    ///
+    /// # Panic
+    /// Panics if the size of the backend does not match the given width and
+    /// height.
+    pub fn new(data: B, width: usize, height: usize) -> Self {
+        assert_eq!(
+            data.capacity(),
+            width * height,
+            "FallibleFrameBuf underlying data size does not match width ({}) * height ({}) = {} but is {}",
+            width,
+            height,
+            width * height,
+            data.capacity(),
+        );
+        Self {
+            data,
+            width,
+            height,
+        }
+    }
+
+    /// Get the framebuffers width.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Get the framebuffers height.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Return the backend.
+    pub fn into_inner(self) -> B {
+        self.data
+    }
+
+    /// Set a pixel's color.
+    ///
+    /// # Panic
+    /// Panics if `p` is outside of the framebuffer.
+    pub fn try_set_color_at(&mut self, p: Point, color: C) -> Result<(), B::Error> {
+        let index = self.index(p);
+        self.data.try_set(index, color)
+    }
+
+    /// Get a pixel's color.
+    ///
+    /// # Panic
+    /// Panics if `p` is outside of the framebuffer.
+    pub fn try_get_color_at(&mut self, p: Point) -> Result<C, B::Error> {
+        let index = self.index(p);
+        self.data.try_get(index)
+    }
+
+    fn index(&self, p: Point) -> usize {
+        assert!(
+            self.bounding_box().contains(p),
+            "point {:?} is outside of the framebuffer",
+            p
+        );
+        p.y as usize * self.width + p.x as usize
+    }
+}
+
+impl<C: PixelColor, B: FallibleFrameBufferBackend<Color = C>> OriginDimensions
+    for FallibleFrameBuf<C, B>
+{
+    fn size(&self) -> Size {
+        Size::new(self.width as u32, self.height as u32)
+    }
+}
+
+impl<C: PixelColor, B: FallibleFrameBufferBackend<Color = C>> DrawTarget
+    for FallibleFrameBuf<C, B>
+{
+    type Color = C;
+    type Error = B::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let area = self.bounding_box();
+        for Pixel(p, color) in pixels {
+            if area.contains(p) {
+                self.try_set_color_at(p, color)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&self.bounding_box());
+        let width = area.size.width as usize;
+        for y in area.rows() {
+            let start = self.index(Point::new(area.top_left.x, y));
+            self.data.try_fill_range(start..start + width, color)?;
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.data.try_fill_range(0..self.width * self.height, color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::pixelcolor::BinaryColor;
+
+    /// A backend failing at one index.
+    struct Flaky {
+        data: [BinaryColor; 4 * 3],
+        broken: usize,
+    }
+
+    impl FallibleFrameBufferBackend for Flaky {
+        type Color = BinaryColor;
+        type Error = usize;
+
+        fn try_set(&mut self, index: usize, color: BinaryColor) -> Result<(), usize> {
+            if index == self.broken {
+                return Err(index);
+            }
+            self.data[index] = color;
+            Ok(())
+        }
+
+        fn try_get(&mut self, index: usize) -> Result<BinaryColor, usize> {
+            match index == self.broken {
+                true => Err(index),
+                false => Ok(self.data[index]),
+            }
+        }
+
+        fn capacity(&self) -> usize {
+            self.data.len()
+        }
+    }
+
+    #[test]
+    fn propagates_errors() {
+        let backend = Flaky {
+            data: [BinaryColor::Off; 4 * 3],
+            broken: 6,
+        };
+        let mut fbuf = FallibleFrameBuf::new(backend, 4, 3);
+        let area = Rectangle::new(Point::new(1, 0), Size::new(2, 3));
+        assert_eq!(fbuf.fill_solid(&area, BinaryColor::On), Err(6));
+        assert_eq!(fbuf.try_get_color_at(Point::new(1, 0)), Ok(BinaryColor::On));
+        assert_eq!(fbuf.try_get_color_at(Point::new(1, 1)), Ok(BinaryColor::On));
+        assert_eq!(fbuf.try_get_color_at(Point::new(2, 1)), Err(6));
+        assert_eq!(
+            fbuf.try_get_color_at(Point::new(1, 2)),
+            Ok(BinaryColor::Off)
+        );
+        assert_eq!(
+            fbuf.draw_iter([
+                Pixel(Point::new(-1, 0), BinaryColor::On),
+                Pixel(Point::new(3, 2), BinaryColor::On)
+            ]),
+            Ok(())
+        );
+        assert_eq!(fbuf.data.data[11], BinaryColor::On);
+    }
+
+    #[test]
+    fn works_on_infallible_backends() {
+        let mut data = [BinaryColor::Off; 2 * 2];
+        let mut fbuf = FallibleFrameBuf::new(&mut data, 2, 2);
+        let Ok(()) = fbuf.clear(BinaryColor::On);
+        assert_eq!(data, [BinaryColor::On; 4]);
+    }
+}
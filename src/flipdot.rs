@@ -0,0 +1,260 @@
+//! Flushing to flip-dot panels.
+//!
+//! Flipping a dot is slow and loud, so only dots whose state differs from what
+//! the panel currently shows should be flipped. [`FlipDotPanel`] remembers
+//! the panel state (one bit per dot) and yields the changes of a monochrome
+//! [`FrameBuf`] since the last flush, either dot by dot for controllers
+//! addressing single dots by row and column, or as packed columns for
+//! controllers that take a whole column at once.
+//!
+//! ```rust
+//! use embedded_graphics::{pixelcolor::BinaryColor, prelude::Point};
+//! use embedded_graphics_framebuf::{flipdot::FlipDotPanel, FrameBuf};
+//!
+//! let mut data = [BinaryColor::Off; 28 * 7];
+//! let mut fbuf = FrameBuf::new(&mut data, 28, 7);
+//! let mut state = [0u8; 28 * 7 / 8 + 1];
+//! let mut panel = FlipDotPanel::new(&mut state, 28, 7);
+//!
+//! fbuf.set_color_at(Point::new(3, 4), BinaryColor::On);
+//! for column in panel.changed_columns(&fbuf) {
+//!     // controller.write_column(column.x, column.bits);
+//! }
+//! // Nothing changed since the last flush
+//! assert_eq!(panel.changes(&fbuf).count(), 0);
+//! ```
+
+use embedded_graphics::{pixelcolor::BinaryColor, prelude::Point};
+
+use crate::{backends::FrameBufferBackend, FrameBuf};
+
+/// A dot that has to be flipped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DotChange {
+    pub point: Point,
+    pub color: BinaryColor,
+}
+
+/// A column containing at least one dot that has to be flipped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ColumnUpdate {
+    pub x: usize,
+    /// The state of all dots of the column, the least significant bit being
+    /// the top dot.
+    pub bits: u32,
+}
+
+/// The state of a flip-dot panel, stored with one bit per dot.
+pub struct FlipDotPanel<'a> {
+    shown: &'a mut [u8],
+    width: usize,
+    height: usize,
+    /// `false` until the whole panel was written once.
+    known: bool,
+}
+
+impl<'a> FlipDotPanel<'a> {
+    /// Create the state of a `width` x `height` panel.
+    ///
+    /// The panel state is unknown initially, so the first flush flips every
+    /// dot.
+    ///
+    /// # Panic
+    /// Panics if `shown` has less than `width * height` bits.
+    pub fn new(shown: &'a mut [u8], width: usize, height: usize) -> Self {
+        assert!(
+            shown.len() * 8 >= width * height,
+            "FlipDotPanel needs {} bytes of state but got {}",
+            (width * height).div_ceil(8),
+            shown.len()
+        );
+        Self {
+            shown,
+            width,
+            height,
+            known: false,
+        }
+    }
+
+    /// Forget the panel state, e.g. after the panel was power cycled. The next
+    /// flush flips every dot.
+    pub fn invalidate(&mut self) {
+        self.known = false;
+    }
+
+    fn shown(&self, index: usize) -> bool {
+        self.shown[index / 8] & (1 << (index % 8)) != 0
+    }
+
+    fn set_shown(&mut self, index: usize, on: bool) {
+        if on {
+            self.shown[index / 8] |= 1 << (index % 8);
+        } else {
+            self.shown[index / 8] &= !(1 << (index % 8));
+        }
+    }
+
+    fn check_size<B: FrameBufferBackend<Color = BinaryColor>>(
+        &self,
+        fbuf: &FrameBuf<BinaryColor, B>,
+    ) {
+        assert!(
+            fbuf.width() == self.width && fbuf.height() == self.height,
+            "FrameBuf size does not match the flip-dot panel"
+        );
+    }
+
+    /// Iterate over all dots that differ from the panel state, in row-major
+    /// order. The panel state is updated as the changes are consumed. An
+    /// unknown panel state only becomes known once an iteration completes.
+    pub fn changes<'b, B: FrameBufferBackend<Color = BinaryColor>>(
+        &'b mut self,
+        fbuf: &'b FrameBuf<BinaryColor, B>,
+    ) -> DotChanges<'a, 'b, B> {
+        self.check_size(fbuf);
+        let force = !self.known;
+        DotChanges {
+            panel: self,
+            fbuf,
+            index: 0,
+            force,
+        }
+    }
+
+    /// Iterate over all columns with at least one dot that differs from the
+    /// panel state. The panel state is updated as the columns are consumed.
+    ///
+    /// # Panic
+    /// Panics if the panel is higher than 32 dots.
+    pub fn changed_columns<'b, B: FrameBufferBackend<Color = BinaryColor>>(
+        &'b mut self,
+        fbuf: &'b FrameBuf<BinaryColor, B>,
+    ) -> ColumnChanges<'a, 'b, B> {
+        self.check_size(fbuf);
+        assert!(self.height <= 32, "Columns are limited to 32 dots");
+        let force = !self.known;
+        ColumnChanges {
+            panel: self,
+            fbuf,
+            x: 0,
+            force,
+        }
+    }
+}
+
+/// Iterator over the dots to flip, see [`FlipDotPanel::changes`].
+pub struct DotChanges<'a, 'b, B: FrameBufferBackend<Color = BinaryColor>> {
+    panel: &'b mut FlipDotPanel<'a>,
+    fbuf: &'b FrameBuf<BinaryColor, B>,
+    index: usize,
+    force: bool,
+}
+
+impl<B: FrameBufferBackend<Color = BinaryColor>> Iterator for DotChanges<'_, '_, B> {
+    type Item = DotChange;
+
+    fn next(&mut self) -> Option<DotChange> {
+        let width = self.panel.width;
+        while self.index < width * self.panel.height {
+            let index = self.index;
+            self.index += 1;
+            let point = Point::new((index % width) as i32, (index / width) as i32);
+            let color = self.fbuf.get_color_at(point);
+            if self.force || self.panel.shown(index) != color.is_on() {
+                self.panel.set_shown(index, color.is_on());
+                return Some(DotChange { point, color });
+            }
+        }
+        self.panel.known = true;
+        None
+    }
+}
+
+/// Iterator over the columns to write, see [`FlipDotPanel::changed_columns`].
+pub struct ColumnChanges<'a, 'b, B: FrameBufferBackend<Color = BinaryColor>> {
+    panel: &'b mut FlipDotPanel<'a>,
+    fbuf: &'b FrameBuf<BinaryColor, B>,
+    x: usize,
+    force: bool,
+}
+
+impl<B: FrameBufferBackend<Color = BinaryColor>> Iterator for ColumnChanges<'_, '_, B> {
+    type Item = ColumnUpdate;
+
+    fn next(&mut self) -> Option<ColumnUpdate> {
+        let width = self.panel.width;
+        while self.x < width {
+            let x = self.x;
+            self.x += 1;
+            let mut bits = 0;
+            let mut changed = self.force;
+            for y in 0..self.panel.height {
+                let on = self
+                    .fbuf
+                    .get_color_at(Point::new(x as i32, y as i32))
+                    .is_on();
+                let index = y * width + x;
+                changed |= self.panel.shown(index) != on;
+                self.panel.set_shown(index, on);
+                bits |= (on as u32) << y;
+            }
+            if changed {
+                return Some(ColumnUpdate { x, bits });
+            }
+        }
+        self.panel.known = true;
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn yields_changed_dots_only() {
+        let mut data = [BinaryColor::Off; 4 * 3];
+        let mut fbuf = FrameBuf::new(&mut data, 4, 3);
+        let mut state = [0u8; 2];
+        let mut panel = FlipDotPanel::new(&mut state, 4, 3);
+
+        assert_eq!(panel.changes(&fbuf).count(), 12);
+        assert_eq!(panel.changes(&fbuf).count(), 0);
+
+        fbuf.set_color_at(Point::new(2, 1), BinaryColor::On);
+        let mut changes = panel.changes(&fbuf);
+        assert_eq!(
+            changes.next(),
+            Some(DotChange {
+                point: Point::new(2, 1),
+                color: BinaryColor::On
+            })
+        );
+        assert_eq!(changes.next(), None);
+
+        panel.invalidate();
+        assert_eq!(panel.changes(&fbuf).count(), 12);
+    }
+
+    #[test]
+    fn packs_changed_columns() {
+        let mut data = [BinaryColor::Off; 3 * 7];
+        let mut fbuf = FrameBuf::new(&mut data, 3, 7);
+        let mut state = [0u8; 3];
+        let mut panel = FlipDotPanel::new(&mut state, 3, 7);
+        assert_eq!(panel.changed_columns(&fbuf).count(), 3);
+
+        fbuf.set_color_at(Point::new(1, 0), BinaryColor::On);
+        fbuf.set_color_at(Point::new(1, 6), BinaryColor::On);
+        let mut columns = panel.changed_columns(&fbuf);
+        assert_eq!(
+            columns.next(),
+            Some(ColumnUpdate {
+                x: 1,
+                bits: 0b100_0001
+            })
+        );
+        assert_eq!(columns.next(), None);
+        assert_eq!(panel.changes(&fbuf).count(), 0);
+    }
+}
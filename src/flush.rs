@@ -0,0 +1,1885 @@
+//! Helpers for pushing the content of a [`FrameBuf`] to a
+//! display.
+//!
+//! The iterators in here yield [`Pixel`]s in display coordinates (offset by
+//! the framebuffer's origin), to be passed to
+//! [`DrawTarget::draw_iter`](embedded_graphics::draw_target::DrawTarget::draw_iter)
+//! of the display.
+//!
+//! With the `embedded-hal` feature, [`SpiFlusher`] streams the framebuffer
+//! straight to an SPI display controller, without a driver crate in between.
+//! With the `embedded-hal-async` feature, [`AsyncSpiFlusher`] does the same
+//! for async SPI devices, so e.g. embassy executors aren't blocked during the
+//! transfer. With the `smart-leds` feature, [`LedColors`] feeds LED matrices
+//! driven through `SmartLedsWrite`.
+//!
+//! [`IncrementalFlush`] sends the framebuffer a limited number of pixels or
+//! for a limited time at a time, for main loops which have to feed a watchdog
+//! or do other work in between.
+
+use core::marker::PhantomData;
+
+use embedded_graphics::{
+    pixelcolor::{raw::ToBytes, Rgb888},
+    prelude::{PixelColor, Point, RgbColor, Size},
+    primitives::{rectangle::Points, PointsIter, Rectangle},
+    Pixel,
+};
+
+use crate::{
+    backends::{EndianCorrection, FrameBufferBackend},
+    FrameBuf, PixelIterator,
+};
+
+#[cfg(feature = "smart-leds")]
+pub use self::smart_leds::LedColors;
+#[cfg(feature = "embedded-hal")]
+pub use self::spi::{FlushError, SpiFlusher};
+#[cfg(feature = "embedded-hal-async")]
+pub use self::spi_async::AsyncSpiFlusher;
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
+    /// Iterate over all pixels, first of the even rows and then of the odd
+    /// rows.
+    ///
+    /// On slow links the whole picture changes after half of the transfer,
+    /// which makes the update appear faster. This also matches controllers
+    /// with an interlaced RAM write mode.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{mock_display::MockDisplay, pixelcolor::BinaryColor, prelude::*};
+    /// use embedded_graphics_framebuf::FrameBuf;
+    /// let mut data = [BinaryColor::Off; 12 * 11];
+    /// let fbuf = FrameBuf::new(&mut data, 12, 11);
+    /// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// display.draw_iter(fbuf.interlaced()).unwrap();
+    /// ```
+    pub fn interlaced(&self) -> InterlacedPixels<'_, C, B> {
+        InterlacedPixels {
+            fbuf: self,
+            row: 0,
+            x: 0,
+        }
+    }
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
+    /// Iterate over the framebuffer from coarse to fine.
+    ///
+    /// The first pass yields one block of `block_size` x `block_size` pixels
+    /// filled with the color of its top left pixel. Every following pass
+    /// halves the block size, skipping blocks whose top left pixel was sent
+    /// before, until single pixels are reached. Drawing the items with
+    /// [`DrawTarget::fill_solid`](embedded_graphics::draw_target::DrawTarget::fill_solid)
+    /// shows a usable approximation after a fraction of the transfer, which
+    /// helps with remote or radio linked displays. In total exactly one item
+    /// per pixel is yielded.
+    ///
+    /// # Panic
+    /// Panics if `block_size` is not a power of two.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{mock_display::MockDisplay, pixelcolor::BinaryColor, prelude::*};
+    /// use embedded_graphics_framebuf::FrameBuf;
+    /// let mut data = [BinaryColor::Off; 12 * 11];
+    /// let fbuf = FrameBuf::new(&mut data, 12, 11);
+    /// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// display.set_allow_overdraw(true);
+    /// for (area, color) in fbuf.progressive(8) {
+    ///     display.fill_solid(&area, color).unwrap();
+    /// }
+    /// ```
+    pub fn progressive(&self, block_size: u32) -> ProgressiveBlocks<'_, C, B> {
+        assert!(
+            block_size.is_power_of_two(),
+            "block size must be a power of two"
+        );
+        ProgressiveBlocks {
+            fbuf: self,
+            first: block_size,
+            size: block_size,
+            x: 0,
+            y: 0,
+        }
+    }
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
+    /// Iterate over all pixels of a display of size `display`, with the
+    /// framebuffer centered on it and the remaining border filled with
+    /// `border`.
+    ///
+    /// This way one UI layout can drive panels of several sizes. A
+    /// framebuffer larger than the display is cropped evenly on both sides.
+    /// The pixels are yielded row by row in display coordinates, ignoring the
+    /// framebuffer's origin, so they can also be used with
+    /// [`DrawTarget::fill_contiguous`](embedded_graphics::draw_target::DrawTarget::fill_contiguous).
+    /// Pixels hidden by the [mask](crate::mask) show the border color.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{mock_display::MockDisplay, pixelcolor::BinaryColor, prelude::*};
+    /// use embedded_graphics_framebuf::FrameBuf;
+    /// let mut data = [BinaryColor::On; 12 * 11];
+    /// let fbuf = FrameBuf::new(&mut data, 12, 11);
+    /// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// display
+    ///     .draw_iter(fbuf.letterboxed(Size::new(16, 15), BinaryColor::Off))
+    ///     .unwrap();
+    /// assert_eq!(display.get_pixel(Point::new(1, 1)), Some(BinaryColor::Off));
+    /// assert_eq!(display.get_pixel(Point::new(2, 2)), Some(BinaryColor::On));
+    /// ```
+    pub fn letterboxed(&self, display: Size, border: C) -> LetterboxedPixels<'_, C, B> {
+        let offset = Point::new(
+            (display.width as i32 - self.width as i32) / 2,
+            (display.height as i32 - self.height as i32) / 2,
+        );
+        LetterboxedPixels {
+            fbuf: self,
+            display,
+            offset,
+            border,
+            index: 0,
+        }
+    }
+}
+
+/// Iterator over the pixels of a display with a centered framebuffer, see
+/// [`FrameBuf::letterboxed`].
+pub struct LetterboxedPixels<'a, C, B: FrameBufferBackend<Color = C>> {
+    fbuf: &'a FrameBuf<C, B>,
+    display: Size,
+    /// Position of the framebuffer's top left corner on the display
+    offset: Point,
+    border: C,
+    index: u32,
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> Iterator for LetterboxedPixels<'_, C, B> {
+    type Item = Pixel<C>;
+
+    fn next(&mut self) -> Option<Pixel<C>> {
+        let Size { width, height } = self.display;
+        if self.index >= width * height {
+            return None;
+        }
+        let p = Point::new((self.index % width) as i32, (self.index / width) as i32);
+        self.index += 1;
+
+        let src = p - self.offset;
+        let inside = src.x >= 0
+            && src.y >= 0
+            && (src.x as usize) < self.fbuf.width()
+            && (src.y as usize) < self.fbuf.height();
+        let color = if inside && self.fbuf.is_visible(src) {
+            self.fbuf.get_color_at(src)
+        } else {
+            self.border
+        };
+        Some(Pixel(p, color))
+    }
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
+    /// Iterate over the pixels of the framebuffer scaled to `size`, using the
+    /// nearest pixel for each target pixel.
+    ///
+    /// Any ratio is supported, including non-integer and different ratios per
+    /// axis. For smoother results with RGB colors see
+    /// [`FrameBuf::scaled_bilinear`]. Target pixels whose nearest pixel is
+    /// hidden by the [mask](crate::mask) are skipped.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{mock_display::MockDisplay, pixelcolor::BinaryColor, prelude::*};
+    /// use embedded_graphics_framebuf::FrameBuf;
+    /// let mut data = [BinaryColor::Off; 40 * 32];
+    /// let fbuf = FrameBuf::new(&mut data, 40, 32);
+    /// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// display.draw_iter(fbuf.scaled(Size::new(60, 60))).unwrap();
+    /// ```
+    pub fn scaled(&self, size: Size) -> ScaledPixels<'_, C, B> {
+        ScaledPixels {
+            fbuf: self,
+            size,
+            index: 0,
+        }
+    }
+
+    /// Iterate over the pixels of the framebuffer with each pixel repeated
+    /// `factor` x `factor` times.
+    ///
+    /// Like [`FrameBuf::scaled`] with an integer ratio, but cheaper, so a
+    /// framebuffer of a quarter of the panel's size (with a `factor` of 2)
+    /// can be kept in RAM and still fill the whole panel.
+    ///
+    /// Pixels hidden by the [mask](crate::mask) are skipped, so the colors
+    /// can only be passed to `fill_contiguous` without a mask.
+    ///
+    /// # Panic
+    /// Panics if `factor` is 0.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{
+    ///     mock_display::MockDisplay, pixelcolor::Rgb565, prelude::*, primitives::Rectangle,
+    /// };
+    /// use embedded_graphics_framebuf::FrameBuf;
+    /// // Half the resolution of a 64x64 panel
+    /// let mut data = [Rgb565::BLACK; 32 * 32];
+    /// let mut fbuf = FrameBuf::new(&mut data, 32, 32);
+    /// fbuf.set_color_at(Point::new(1, 0), Rgb565::RED);
+    /// let mut display: MockDisplay<Rgb565> = MockDisplay::new();
+    /// let panel = Rectangle::new(Point::zero(), Size::new(64, 64));
+    /// display.fill_contiguous(&panel, fbuf.magnified(2).map(|p| p.1)).unwrap();
+    /// assert_eq!(display.get_pixel(Point::new(3, 1)), Some(Rgb565::RED));
+    /// ```
+    pub fn magnified(&self, factor: u32) -> MagnifiedPixels<'_, C, B> {
+        assert!(factor > 0, "FrameBuf can't be magnified by 0");
+        MagnifiedPixels {
+            fbuf: self,
+            factor: factor as i32,
+            point: Point::zero(),
+        }
+    }
+
+    /// Like [`FrameBuf::scaled`], but interpolating between the four nearest
+    /// pixels.
+    ///
+    /// Target pixels are skipped like with [`FrameBuf::scaled`], but the
+    /// interpolation doesn't check the [mask](crate::mask), so pixels at its
+    /// edge are blended with the stored colors of hidden pixels.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{mock_display::MockDisplay, pixelcolor::Rgb565, prelude::*};
+    /// use embedded_graphics_framebuf::FrameBuf;
+    /// // A UI designed for 160x128, shown on a 240x240 panel.
+    /// let mut data = [Rgb565::BLACK; 160 * 128];
+    /// let fbuf = FrameBuf::new(&mut data, 160, 128);
+    /// let mut display: MockDisplay<Rgb565> = MockDisplay::new();
+    /// display.set_allow_out_of_bounds_drawing(true);
+    /// display.draw_iter(fbuf.scaled_bilinear(Size::new(240, 240))).unwrap();
+    /// ```
+    pub fn scaled_bilinear(&self, size: Size) -> BilinearPixels<'_, C, B>
+    where
+        C: Into<Rgb888> + From<Rgb888>,
+    {
+        BilinearPixels {
+            fbuf: self,
+            size,
+            index: 0,
+        }
+    }
+}
+
+/// Position in the source of the center of target pixel `x`, in 1/256
+/// pixels.
+fn scale_coordinate(x: u32, from: usize, to: u32) -> u32 {
+    ((2 * x as u64 + 1) * from as u64 * 128 / to as u64) as u32
+}
+
+/// The source pixel nearest to the target pixel (`x`, `y`) when scaling `fbuf`
+/// to `size`.
+fn nearest<C: PixelColor, B: FrameBufferBackend<Color = C>>(
+    fbuf: &FrameBuf<C, B>,
+    size: Size,
+    x: u32,
+    y: u32,
+) -> Point {
+    Point::new(
+        (scale_coordinate(x, fbuf.width(), size.width) / 256) as i32,
+        (scale_coordinate(y, fbuf.height(), size.height) / 256) as i32,
+    )
+}
+
+/// Iterator over the pixels of a scaled framebuffer, see
+/// [`FrameBuf::scaled`].
+pub struct ScaledPixels<'a, C, B: FrameBufferBackend<Color = C>> {
+    fbuf: &'a FrameBuf<C, B>,
+    size: Size,
+    index: u32,
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> Iterator for ScaledPixels<'_, C, B> {
+    type Item = Pixel<C>;
+
+    fn next(&mut self) -> Option<Pixel<C>> {
+        let Size { width, height } = self.size;
+        loop {
+            if self.index >= width * height || self.fbuf.width() == 0 || self.fbuf.height() == 0 {
+                return None;
+            }
+            let (x, y) = (self.index % width, self.index / width);
+            self.index += 1;
+
+            let src = nearest(self.fbuf, self.size, x, y);
+            if self.fbuf.is_visible(src) {
+                let p = Point::new(x as i32, y as i32);
+                return Some(Pixel(self.fbuf.origin() + p, self.fbuf.get_color_at(src)));
+            }
+        }
+    }
+}
+
+/// Iterator over the pixels of a magnified framebuffer, see
+/// [`FrameBuf::magnified`].
+pub struct MagnifiedPixels<'a, C, B: FrameBufferBackend<Color = C>> {
+    fbuf: &'a FrameBuf<C, B>,
+    factor: i32,
+    /// The next target pixel
+    point: Point,
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> Iterator for MagnifiedPixels<'_, C, B> {
+    type Item = Pixel<C>;
+
+    fn next(&mut self) -> Option<Pixel<C>> {
+        let width = self.fbuf.width() as i32 * self.factor;
+        let height = self.fbuf.height() as i32 * self.factor;
+        loop {
+            if self.point.y >= height || width == 0 {
+                return None;
+            }
+            let p = self.point;
+            self.point.x += 1;
+            if self.point.x == width {
+                self.point = Point::new(0, p.y + 1);
+            }
+            let src = p / self.factor;
+            if self.fbuf.is_visible(src) {
+                return Some(Pixel(self.fbuf.origin() + p, self.fbuf.get_color_at(src)));
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let width = self.fbuf.width() * self.factor as usize;
+        let height = self.fbuf.height() * self.factor as usize;
+        let done = self.point.y as usize * width + self.point.x as usize;
+        let len = (width * height).saturating_sub(done);
+        match self.fbuf.mask {
+            None => (len, Some(len)),
+            Some(_) => (0, Some(len)),
+        }
+    }
+}
+
+/// Iterator over the pixels of a scaled framebuffer with bilinear
+/// interpolation, see [`FrameBuf::scaled_bilinear`].
+pub struct BilinearPixels<'a, C, B: FrameBufferBackend<Color = C>> {
+    fbuf: &'a FrameBuf<C, B>,
+    size: Size,
+    index: u32,
+}
+
+impl<C, B> Iterator for BilinearPixels<'_, C, B>
+where
+    C: PixelColor + Into<Rgb888> + From<Rgb888>,
+    B: FrameBufferBackend<Color = C>,
+{
+    type Item = Pixel<C>;
+
+    fn next(&mut self) -> Option<Pixel<C>> {
+        let Size { width, height } = self.size;
+        let (x, y) = loop {
+            if self.index >= width * height || self.fbuf.width() == 0 || self.fbuf.height() == 0 {
+                return None;
+            }
+            let (x, y) = (self.index % width, self.index / width);
+            self.index += 1;
+            if self.fbuf.is_visible(nearest(self.fbuf, self.size, x, y)) {
+                break (x, y);
+            }
+        };
+
+        // Positions relative to the center of the top left source pixel
+        let sx = scale_coordinate(x, self.fbuf.width(), width).saturating_sub(128);
+        let sy = scale_coordinate(y, self.fbuf.height(), height).saturating_sub(128);
+        let (x0, y0) = ((sx / 256) as usize, (sy / 256) as usize);
+        let x1 = (x0 + 1).min(self.fbuf.width() - 1);
+        let y1 = (y0 + 1).min(self.fbuf.height() - 1);
+        let (fx, fy) = (sx % 256, sy % 256);
+
+        let color = |x: usize, y: usize| -> Rgb888 {
+            self.fbuf
+                .get_color_at(Point::new(x as i32, y as i32))
+                .into()
+        };
+        let corners = [
+            (color(x0, y0), (256 - fx) * (256 - fy)),
+            (color(x1, y0), fx * (256 - fy)),
+            (color(x0, y1), (256 - fx) * fy),
+            (color(x1, y1), fx * fy),
+        ];
+        let channel = |f: fn(&Rgb888) -> u8| -> u8 {
+            let sum: u32 = corners.iter().map(|(c, w)| f(c) as u32 * w).sum();
+            ((sum + (1 << 15)) >> 16) as u8
+        };
+        let color = Rgb888::new(channel(Rgb888::r), channel(Rgb888::g), channel(Rgb888::b));
+
+        let p = Point::new(x as i32, y as i32);
+        Some(Pixel(self.fbuf.origin() + p, color.into()))
+    }
+}
+
+/// Iterator over blocks of decreasing size, see [`FrameBuf::progressive`].
+pub struct ProgressiveBlocks<'a, C, B: FrameBufferBackend<Color = C>> {
+    fbuf: &'a FrameBuf<C, B>,
+    first: u32,
+    /// Block size of the current pass, 0 when done
+    size: u32,
+    x: u32,
+    y: u32,
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> Iterator for ProgressiveBlocks<'_, C, B> {
+    type Item = (Rectangle, C);
+
+    fn next(&mut self) -> Option<(Rectangle, C)> {
+        let Size { width, height } = self.fbuf.size();
+        while self.size > 0 {
+            if self.x >= width {
+                self.x = 0;
+                self.y += self.size;
+            }
+            if self.y >= height {
+                self.size /= 2;
+                self.x = 0;
+                self.y = 0;
+                continue;
+            }
+            let (x, y, size) = (self.x, self.y, self.size);
+            self.x += size;
+            let sent_before = size < self.first && x % (2 * size) == 0 && y % (2 * size) == 0;
+            if !sent_before {
+                let p = Point::new(x as i32, y as i32);
+                let area = Rectangle::new(
+                    self.fbuf.origin() + p,
+                    Size::new(size.min(width - x), size.min(height - y)),
+                );
+                return Some((area, self.fbuf.get_color_at(p)));
+            }
+        }
+        None
+    }
+}
+
+/// Iterator over the pixels of a framebuffer with interlaced rows, see
+/// [`FrameBuf::interlaced`].
+pub struct InterlacedPixels<'a, C, B: FrameBufferBackend<Color = C>> {
+    fbuf: &'a FrameBuf<C, B>,
+    /// Index of the row in transfer order
+    row: usize,
+    x: usize,
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> Iterator for InterlacedPixels<'_, C, B> {
+    type Item = Pixel<C>;
+
+    fn next(&mut self) -> Option<Pixel<C>> {
+        let height = self.fbuf.height();
+        let even_rows = height.div_ceil(2);
+        loop {
+            if self.row >= height || self.fbuf.width() == 0 {
+                return None;
+            }
+            let y = if self.row < even_rows {
+                2 * self.row
+            } else {
+                2 * (self.row - even_rows) + 1
+            };
+            let p = Point::new(self.x as i32, y as i32);
+            self.x += 1;
+            if self.x == self.fbuf.width() {
+                self.x = 0;
+                self.row += 1;
+            }
+            if self.fbuf.is_visible(p) {
+                return Some(Pixel(self.fbuf.origin() + p, self.fbuf.get_color_at(p)));
+            }
+        }
+    }
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
+    /// Iterate over the bytes of all pixels row by row, in the requested byte
+    /// order.
+    ///
+    /// The bytes can be written to a display controller as they are, e.g. in
+    /// chunks with `spi.write()`, without storing the framebuffer in the byte
+    /// order of the display (see
+    /// [`EndianCorrectedBuffer`](crate::backends::EndianCorrectedBuffer)).
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+    /// use embedded_graphics_framebuf::{backends::EndianCorrection, FrameBuf};
+    ///
+    /// let mut data = [Rgb565::BLACK; 4 * 4];
+    /// let mut fbuf = FrameBuf::new(&mut data, 4, 4);
+    /// fbuf.set_color_at(Point::zero(), Rgb565::RED);
+    /// let mut chunk = [0u8; 8];
+    /// for (byte, value) in chunk.iter_mut().zip(fbuf.bytes(EndianCorrection::ToBigEndian)) {
+    ///     *byte = value;
+    /// }
+    /// assert_eq!(chunk[..2], [0xF8, 0x00]);
+    /// assert_eq!(fbuf.bytes(EndianCorrection::ToBigEndian).count(), 4 * 4 * 2);
+    /// ```
+    pub fn bytes(&self, endian: EndianCorrection) -> FrameBytes<'_, C, B>
+    where
+        C: ToBytes,
+    {
+        FrameBytes {
+            fbuf: self,
+            points: Rectangle::new(Point::zero(), self.size()).points(),
+            endian,
+            bytes: None,
+            index: 0,
+        }
+    }
+}
+
+/// Iterator over the bytes of the pixels of a framebuffer, see
+/// [`FrameBuf::bytes`].
+pub struct FrameBytes<'a, C: ToBytes, B: FrameBufferBackend<Color = C>> {
+    fbuf: &'a FrameBuf<C, B>,
+    points: Points,
+    endian: EndianCorrection,
+    /// Bytes of the current pixel
+    bytes: Option<C::Bytes>,
+    index: usize,
+}
+
+impl<C, B> Iterator for FrameBytes<'_, C, B>
+where
+    C: PixelColor + ToBytes,
+    C::Bytes: AsRef<[u8]>,
+    B: FrameBufferBackend<Color = C>,
+{
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        loop {
+            if let Some(&byte) = self.bytes.as_ref().and_then(|b| b.as_ref().get(self.index)) {
+                self.index += 1;
+                return Some(byte);
+            }
+            let color = self.fbuf.get_color_at(self.points.next()?);
+            self.bytes = Some(match self.endian {
+                EndianCorrection::ToBigEndian => color.to_be_bytes(),
+                EndianCorrection::ToLittleEndian => color.to_le_bytes(),
+            });
+            self.index = 0;
+        }
+    }
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
+    /// Iterate over all pixels with their color channels scaled by
+    /// `brightness`, from `0` (black) to `255` (unchanged).
+    ///
+    /// The stored frame is not modified, so the brightness can follow e.g.
+    /// an ambient light sensor without re-rendering and without losing color
+    /// precision. See [`Dimmed`] to dim other pixel iterators.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{mock_display::MockDisplay, pixelcolor::Rgb565, prelude::*};
+    /// use embedded_graphics_framebuf::FrameBuf;
+    /// let mut data = [Rgb565::WHITE; 12 * 11];
+    /// let fbuf = FrameBuf::new(&mut data, 12, 11);
+    /// let mut display: MockDisplay<Rgb565> = MockDisplay::new();
+    /// display.draw_iter(fbuf.dimmed(128)).unwrap();
+    /// assert_eq!(display.get_pixel(Point::zero()), Some(Rgb565::new(16, 32, 16)));
+    /// assert_eq!(data[0], Rgb565::WHITE);
+    /// ```
+    pub fn dimmed(&self, brightness: u8) -> Dimmed<PixelIterator<'_, C, B>>
+    where
+        C: Into<Rgb888> + From<Rgb888>,
+    {
+        Dimmed::new(self.pixels(), brightness)
+    }
+}
+
+/// Iterator adapter scaling the color channels of pixels by a brightness,
+/// see [`FrameBuf::dimmed`].
+///
+/// It can wrap any of the pixel iterators in this module, e.g.
+/// `Dimmed::new(fbuf.interlaced(), 64)`.
+pub struct Dimmed<I> {
+    pixels: I,
+    brightness: u8,
+}
+
+impl<I> Dimmed<I> {
+    /// Dim `pixels` to `brightness`, from `0` (black) to `255` (unchanged).
+    pub fn new(pixels: I, brightness: u8) -> Self {
+        Self { pixels, brightness }
+    }
+}
+
+impl<C, I> Iterator for Dimmed<I>
+where
+    C: PixelColor + Into<Rgb888> + From<Rgb888>,
+    I: Iterator<Item = Pixel<C>>,
+{
+    type Item = Pixel<C>;
+
+    fn next(&mut self) -> Option<Pixel<C>> {
+        let Pixel(p, color) = self.pixels.next()?;
+        if self.brightness == u8::MAX {
+            return Some(Pixel(p, color));
+        }
+        let scale = |channel: u8| ((channel as u32 * self.brightness as u32 + 127) / 255) as u8;
+        let color: Rgb888 = color.into();
+        let color = Rgb888::new(scale(color.r()), scale(color.g()), scale(color.b()));
+        Some(Pixel(p, color.into()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.pixels.size_hint()
+    }
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
+    /// Iterate over all pixels with their colors passed through `shader`,
+    /// without changing the stored colors.
+    ///
+    /// The shader gets the point in display coordinates and the stored color,
+    /// and returns the color to send, e.g. for inverted colors, a night mode
+    /// tint, highlights or dimmed scanlines. See [`Shaded`] to shade other
+    /// pixel iterators.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{mock_display::MockDisplay, pixelcolor::Rgb565, prelude::*};
+    /// use embedded_graphics_framebuf::FrameBuf;
+    /// let mut data = [Rgb565::WHITE; 12 * 11];
+    /// let fbuf = FrameBuf::new(&mut data, 12, 11);
+    /// let mut display: MockDisplay<Rgb565> = MockDisplay::new();
+    /// // Dim every second line
+    /// let scanlines = |p: Point, c: Rgb565| match p.y % 2 {
+    ///     0 => c,
+    ///     _ => Rgb565::new(c.r() / 2, c.g() / 2, c.b() / 2),
+    /// };
+    /// display.draw_iter(fbuf.shaded(scanlines)).unwrap();
+    /// assert_eq!(display.get_pixel(Point::new(0, 1)), Some(Rgb565::new(15, 31, 15)));
+    /// assert_eq!(data[12], Rgb565::WHITE);
+    /// ```
+    pub fn shaded<F>(&self, shader: F) -> Shaded<PixelIterator<'_, C, B>, F>
+    where
+        F: FnMut(Point, C) -> C,
+    {
+        Shaded::new(self.pixels(), shader)
+    }
+}
+
+/// Iterator adapter changing the colors of pixels with a closure, see
+/// [`FrameBuf::shaded`].
+///
+/// Like [`Dimmed`] it can wrap any of the pixel iterators in this module.
+pub struct Shaded<I, F> {
+    pixels: I,
+    shader: F,
+}
+
+impl<I, F> Shaded<I, F> {
+    /// Pass the colors of `pixels` through `shader`.
+    pub fn new(pixels: I, shader: F) -> Self {
+        Self { pixels, shader }
+    }
+}
+
+impl<C, I, F> Iterator for Shaded<I, F>
+where
+    C: PixelColor,
+    I: Iterator<Item = Pixel<C>>,
+    F: FnMut(Point, C) -> C,
+{
+    type Item = Pixel<C>;
+
+    fn next(&mut self) -> Option<Pixel<C>> {
+        let Pixel(p, color) = self.pixels.next()?;
+        Some(Pixel(p, (self.shader)(p, color)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.pixels.size_hint()
+    }
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
+    /// Start flushing the dirty area in steps, and mark the framebuffer as
+    /// clean. See [`IncrementalFlush`].
+    pub fn incremental_flush(&mut self) -> IncrementalFlush {
+        IncrementalFlush::new(self.take_dirty_area().unwrap_or_default())
+    }
+}
+
+/// A flush of an area of a framebuffer which can be resumed, see
+/// [`FrameBuf::incremental_flush`].
+///
+/// Every call of [`IncrementalFlush::step`] sends up to a given number of
+/// pixels, and every call of [`IncrementalFlush::step_while`] sends pixels
+/// while the time budget lasts. The pixels are sent row segment by row
+/// segment through
+/// [`DrawTarget::fill_contiguous`](embedded_graphics::draw_target::DrawTarget::fill_contiguous),
+/// and remembers where it stopped. The flush doesn't borrow the framebuffer,
+/// so it can be kept e.g. in the state of a cooperative task.
+///
+/// # Example
+/// ```rust
+/// use embedded_graphics::{mock_display::MockDisplay, pixelcolor::Rgb565, prelude::*};
+/// use embedded_graphics_framebuf::FrameBuf;
+///
+/// let mut data = [Rgb565::BLUE; 12 * 11];
+/// let mut fbuf = FrameBuf::new(&mut data, 12, 11);
+/// let mut display: MockDisplay<Rgb565> = MockDisplay::new();
+///
+/// let mut flush = fbuf.incremental_flush();
+/// let mut steps = 0;
+/// while !flush.step(&fbuf, &mut display, 32).unwrap() {
+///     // watchdog.feed(); poll_buttons(); ...
+///     steps += 1;
+/// }
+/// assert_eq!(steps, 4);
+/// assert_eq!(display.get_pixel(Point::new(11, 10)), Some(Rgb565::BLUE));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IncrementalFlush {
+    /// The area in framebuffer coordinates
+    area: Rectangle,
+    /// Index of the next pixel in `area`
+    index: u32,
+}
+
+impl IncrementalFlush {
+    /// Flush `area` of a framebuffer, in framebuffer coordinates.
+    pub fn new(area: Rectangle) -> Self {
+        Self { area, index: 0 }
+    }
+
+    /// The number of pixels left to send.
+    pub fn remaining(&self) -> u32 {
+        self.area.size.width * self.area.size.height - self.index
+    }
+
+    /// Whether all pixels were sent.
+    pub fn is_done(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Send up to `max_pixels` pixels of `fbuf` to `display`, in display
+    /// coordinates (offset by the framebuffer's origin). Returns whether the
+    /// flush is done.
+    ///
+    /// The area is clipped to `fbuf`. If sending fails, the step is repeated
+    /// by the next call.
+    pub fn step<C, B, D>(
+        &mut self,
+        fbuf: &FrameBuf<C, B>,
+        display: &mut D,
+        max_pixels: u32,
+    ) -> Result<bool, D::Error>
+    where
+        C: PixelColor,
+        B: FrameBufferBackend<Color = C>,
+        D: embedded_graphics::draw_target::DrawTarget<Color = C>,
+    {
+        let bounds = Rectangle::new(Point::zero(), fbuf.size());
+        let mut budget = max_pixels;
+        while budget > 0 && !self.is_done() {
+            let width = self.area.size.width;
+            let (x, y) = (self.index % width, self.index / width);
+            let len = budget.min(width - x);
+            let segment = Rectangle::new(
+                self.area.top_left + Point::new(x as i32, y as i32),
+                Size::new(len, 1),
+            )
+            .intersection(&bounds);
+            if !segment.is_zero_sized() {
+                let colors = segment
+                    .columns()
+                    .map(|x| fbuf.get_color_at(Point::new(x, segment.top_left.y)));
+                let target = Rectangle::new(fbuf.origin() + segment.top_left, segment.size);
+                display.fill_contiguous(&target, colors)?;
+            }
+            self.index += len;
+            budget -= len;
+        }
+        Ok(self.is_done())
+    }
+
+    /// Send chunks of up to `chunk` pixels of `fbuf` to `display` as long as
+    /// `has_time` returns `true`, e.g. while a timer hasn't reached the
+    /// deadline of the step. Returns whether the flush is done.
+    ///
+    /// At least one chunk is sent per call, so the flush makes progress even
+    /// if the budget is exhausted already. See [`IncrementalFlush::step`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{mock_display::MockDisplay, pixelcolor::Rgb565, prelude::*};
+    /// use embedded_graphics_framebuf::FrameBuf;
+    ///
+    /// let mut data = [Rgb565::BLUE; 12 * 11];
+    /// let mut fbuf = FrameBuf::new(&mut data, 12, 11);
+    /// let mut display: MockDisplay<Rgb565> = MockDisplay::new();
+    ///
+    /// let mut flush = fbuf.incremental_flush();
+    /// // let deadline = timer.now() + Duration::from_millis(2);
+    /// let mut ticks = 0;
+    /// flush.step_while(&fbuf, &mut display, 16, || {
+    ///     ticks += 1;
+    ///     ticks < 3 // timer.now() < deadline
+    /// })
+    /// .unwrap();
+    /// assert_eq!(flush.remaining(), 12 * 11 - 3 * 16);
+    /// ```
+    pub fn step_while<C, B, D, F>(
+        &mut self,
+        fbuf: &FrameBuf<C, B>,
+        display: &mut D,
+        chunk: u32,
+        mut has_time: F,
+    ) -> Result<bool, D::Error>
+    where
+        C: PixelColor,
+        B: FrameBufferBackend<Color = C>,
+        D: embedded_graphics::draw_target::DrawTarget<Color = C>,
+        F: FnMut() -> bool,
+    {
+        while !self.step(fbuf, display, chunk)? {
+            if !has_time() {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(feature = "embedded-hal")]
+mod spi {
+    use embedded_graphics::{
+        pixelcolor::raw::ToBytes,
+        prelude::{PixelColor, Point, Size},
+        primitives::{PointsIter, Rectangle},
+    };
+    use embedded_hal::{digital::OutputPin, spi::SpiDevice};
+
+    use crate::{
+        backends::{EndianCorrection, FrameBufferBackend},
+        FrameBuf,
+    };
+
+    /// Errors of the [`SpiFlusher`].
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum FlushError<S, P> {
+        /// Error of the SPI device
+        Spi(S),
+        /// Error of the data/command pin
+        Pin(P),
+    }
+
+    /// Blocking flusher writing framebuffers to an SPI display controller
+    /// (ST7789, ILI9341, ...).
+    ///
+    /// The pixels are converted into the requested byte order and sent in
+    /// chunks of `N` bytes. Setting up the controller's RAM window is left to
+    /// a closure, as it differs between controllers.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let mut flusher: SpiFlusher<_, _> = SpiFlusher::new(spi, dc);
+    /// flusher.flush(&fbuf, EndianCorrection::ToBigEndian, |f, area| {
+    ///     let (x0, x1) = (area.top_left.x as u16, (area.top_left.x + area.size.width as i32 - 1) as u16);
+    ///     let (y0, y1) = (area.top_left.y as u16, (area.top_left.y + area.size.height as i32 - 1) as u16);
+    ///     f.command(0x2A, &[(x0 >> 8) as u8, x0 as u8, (x1 >> 8) as u8, x1 as u8])?; // CASET
+    ///     f.command(0x2B, &[(y0 >> 8) as u8, y0 as u8, (y1 >> 8) as u8, y1 as u8])?; // RASET
+    ///     f.command(0x2C, &[]) // RAMWR
+    /// })?;
+    /// ```
+    pub struct SpiFlusher<SPI, DC, const N: usize = 512> {
+        spi: SPI,
+        dc: DC,
+        buf: [u8; N],
+    }
+
+    type Error<SPI, DC> = FlushError<
+        <SPI as embedded_hal::spi::ErrorType>::Error,
+        <DC as embedded_hal::digital::ErrorType>::Error,
+    >;
+
+    impl<SPI: SpiDevice, DC: OutputPin, const N: usize> SpiFlusher<SPI, DC, N> {
+        pub fn new(spi: SPI, dc: DC) -> Self {
+            assert!(N > 0, "SpiFlusher needs a chunk size larger than 0");
+            Self {
+                spi,
+                dc,
+                buf: [0; N],
+            }
+        }
+
+        /// Return the SPI device and the data/command pin.
+        pub fn release(self) -> (SPI, DC) {
+            (self.spi, self.dc)
+        }
+
+        /// Send a command byte followed by its parameters.
+        pub fn command(&mut self, command: u8, params: &[u8]) -> Result<(), Error<SPI, DC>> {
+            self.dc.set_low().map_err(FlushError::Pin)?;
+            self.spi.write(&[command]).map_err(FlushError::Spi)?;
+            if !params.is_empty() {
+                self.data(params)?;
+            }
+            Ok(())
+        }
+
+        /// Send raw data bytes.
+        pub fn data(&mut self, data: &[u8]) -> Result<(), Error<SPI, DC>> {
+            self.dc.set_high().map_err(FlushError::Pin)?;
+            self.spi.write(data).map_err(FlushError::Spi)
+        }
+
+        /// Write the whole framebuffer to the display.
+        ///
+        /// `set_window` is called with the target area in display coordinates
+        /// (i.e. offset by the framebuffer's origin) and has to prepare the
+        /// controller for receiving the pixel data.
+        pub fn flush<C, B, F>(
+            &mut self,
+            fbuf: &FrameBuf<C, B>,
+            endian: EndianCorrection,
+            set_window: F,
+        ) -> Result<(), Error<SPI, DC>>
+        where
+            C: PixelColor + ToBytes,
+            <C as ToBytes>::Bytes: AsRef<[u8]>,
+            B: FrameBufferBackend<Color = C>,
+            F: FnOnce(&mut Self, Rectangle) -> Result<(), Error<SPI, DC>>,
+        {
+            let area = Rectangle::new(Point::zero(), fbuf.size());
+            self.flush_area(fbuf, area, endian, set_window)
+        }
+
+        /// Write the [dirty area](crate::dirty) of the framebuffer to the
+        /// display and mark the framebuffer as clean. Nothing is written if
+        /// nothing changed. See [`SpiFlusher::flush`] for `set_window`.
+        pub fn flush_dirty<C, B, F>(
+            &mut self,
+            fbuf: &mut FrameBuf<C, B>,
+            endian: EndianCorrection,
+            set_window: F,
+        ) -> Result<(), Error<SPI, DC>>
+        where
+            C: PixelColor + ToBytes,
+            <C as ToBytes>::Bytes: AsRef<[u8]>,
+            B: FrameBufferBackend<Color = C>,
+            F: FnOnce(&mut Self, Rectangle) -> Result<(), Error<SPI, DC>>,
+        {
+            if let Some(area) = fbuf.dirty_area() {
+                self.flush_area(fbuf, area, endian, set_window)?;
+                fbuf.take_dirty_area();
+            }
+            Ok(())
+        }
+
+        /// Write an area of the framebuffer to the display.
+        ///
+        /// `area` is given in framebuffer coordinates and clipped to the
+        /// framebuffer. See [`SpiFlusher::flush`] for `set_window`.
+        pub fn flush_area<C, B, F>(
+            &mut self,
+            fbuf: &FrameBuf<C, B>,
+            area: Rectangle,
+            endian: EndianCorrection,
+            set_window: F,
+        ) -> Result<(), Error<SPI, DC>>
+        where
+            C: PixelColor + ToBytes,
+            <C as ToBytes>::Bytes: AsRef<[u8]>,
+            B: FrameBufferBackend<Color = C>,
+            F: FnOnce(&mut Self, Rectangle) -> Result<(), Error<SPI, DC>>,
+        {
+            self.flush_area_with_yield(fbuf, area, endian, set_window, || {})
+        }
+
+        /// Like [`SpiFlusher::flush_area`], but calling `yield_fn` after every
+        /// chunk of `N` bytes.
+        ///
+        /// Long blocking transfers can starve the rest of bare-metal firmware,
+        /// `yield_fn` gives it a chance to e.g. pet a watchdog, poll USB or run
+        /// a scheduler tick.
+        pub fn flush_area_with_yield<C, B, F, Y>(
+            &mut self,
+            fbuf: &FrameBuf<C, B>,
+            area: Rectangle,
+            endian: EndianCorrection,
+            set_window: F,
+            mut yield_fn: Y,
+        ) -> Result<(), Error<SPI, DC>>
+        where
+            C: PixelColor + ToBytes,
+            <C as ToBytes>::Bytes: AsRef<[u8]>,
+            B: FrameBufferBackend<Color = C>,
+            F: FnOnce(&mut Self, Rectangle) -> Result<(), Error<SPI, DC>>,
+            Y: FnMut(),
+        {
+            let area = area.intersection(&Rectangle::new(Point::zero(), fbuf.size()));
+            if area.size == Size::zero() {
+                return Ok(());
+            }
+            set_window(
+                self,
+                Rectangle::new(area.top_left + fbuf.origin(), area.size),
+            )?;
+            self.dc.set_high().map_err(FlushError::Pin)?;
+
+            let mut len = 0;
+            for p in area.points() {
+                let color = fbuf.get_color_at(p);
+                let bytes = match endian {
+                    EndianCorrection::ToBigEndian => color.to_be_bytes(),
+                    EndianCorrection::ToLittleEndian => color.to_le_bytes(),
+                };
+                for &byte in bytes.as_ref() {
+                    if len == N {
+                        self.spi.write(&self.buf).map_err(FlushError::Spi)?;
+                        yield_fn();
+                        len = 0;
+                    }
+                    self.buf[len] = byte;
+                    len += 1;
+                }
+            }
+            self.spi.write(&self.buf[..len]).map_err(FlushError::Spi)?;
+            yield_fn();
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        extern crate std;
+
+        use super::*;
+        use core::convert::Infallible;
+        use embedded_graphics::pixelcolor::Rgb565;
+        use embedded_graphics::prelude::RgbColor;
+        use embedded_hal::spi::Operation;
+        use std::vec::Vec;
+
+        #[derive(Default)]
+        struct Log {
+            /// (data/command pin level, bytes) for each SPI write
+            writes: Vec<(bool, Vec<u8>)>,
+            dc: bool,
+        }
+
+        struct Spi<'a>(&'a core::cell::RefCell<Log>);
+        struct Dc<'a>(&'a core::cell::RefCell<Log>);
+
+        impl embedded_hal::spi::ErrorType for Spi<'_> {
+            type Error = Infallible;
+        }
+        impl SpiDevice for Spi<'_> {
+            fn transaction(
+                &mut self,
+                operations: &mut [Operation<'_, u8>],
+            ) -> Result<(), Infallible> {
+                let mut log = self.0.borrow_mut();
+                for op in operations {
+                    if let Operation::Write(data) = op {
+                        let dc = log.dc;
+                        log.writes.push((dc, data.to_vec()));
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl embedded_hal::digital::ErrorType for Dc<'_> {
+            type Error = Infallible;
+        }
+        impl OutputPin for Dc<'_> {
+            fn set_low(&mut self) -> Result<(), Infallible> {
+                self.0.borrow_mut().dc = false;
+                Ok(())
+            }
+            fn set_high(&mut self) -> Result<(), Infallible> {
+                self.0.borrow_mut().dc = true;
+                Ok(())
+            }
+        }
+
+        #[test]
+        fn flushes_in_chunks() {
+            let log = core::cell::RefCell::new(Log::default());
+            let mut data = [Rgb565::BLACK; 3 * 2];
+            let mut fbuf = FrameBuf::new(&mut data, 3, 2);
+            fbuf.set_color_at(Point::new(0, 0), Rgb565::RED);
+
+            let mut flusher: SpiFlusher<_, _, 4> = SpiFlusher::new(Spi(&log), Dc(&log));
+            flusher
+                .flush(&fbuf, EndianCorrection::ToBigEndian, |f, area| {
+                    assert_eq!(area, Rectangle::new(Point::zero(), Size::new(3, 2)));
+                    f.command(0x2C, &[])
+                })
+                .unwrap();
+
+            let writes = &log.borrow().writes;
+            assert_eq!(writes[0], (false, std::vec![0x2C]));
+            assert_eq!(writes[1], (true, std::vec![0xF8, 0x00, 0x00, 0x00]));
+            assert_eq!(writes[2], (true, std::vec![0; 4]));
+            assert_eq!(writes[3], (true, std::vec![0; 4]));
+            assert_eq!(writes.len(), 4);
+        }
+
+        #[test]
+        fn flushes_clipped_area() {
+            let log = core::cell::RefCell::new(Log::default());
+            let mut data = [Rgb565::BLACK; 3 * 2];
+            let mut fbuf = FrameBuf::new_with_origin(&mut data, 3, 2, Point::new(10, 20));
+            fbuf.set_color_at(Point::new(2, 1), Rgb565::BLUE);
+
+            let mut flusher: SpiFlusher<_, _> = SpiFlusher::new(Spi(&log), Dc(&log));
+            flusher
+                .flush_area(
+                    &fbuf,
+                    Rectangle::new(Point::new(2, 1), Size::new(5, 5)),
+                    EndianCorrection::ToLittleEndian,
+                    |_, area| {
+                        assert_eq!(area, Rectangle::new(Point::new(12, 21), Size::new(1, 1)));
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            assert_eq!(log.borrow().writes, [(true, std::vec![0x1F, 0x00])]);
+        }
+
+        #[test]
+        fn flushes_dirty_area() {
+            let log = core::cell::RefCell::new(Log::default());
+            let mut data = [Rgb565::BLACK; 3 * 2];
+            let mut fbuf = FrameBuf::new(&mut data, 3, 2);
+            fbuf.take_dirty_area();
+            fbuf.set_color_at(Point::new(1, 1), Rgb565::RED);
+
+            let mut flusher: SpiFlusher<_, _> = SpiFlusher::new(Spi(&log), Dc(&log));
+            flusher
+                .flush_dirty(&mut fbuf, EndianCorrection::ToBigEndian, |_, area| {
+                    assert_eq!(area, Rectangle::new(Point::new(1, 1), Size::new(1, 1)));
+                    Ok(())
+                })
+                .unwrap();
+            flusher
+                .flush_dirty(&mut fbuf, EndianCorrection::ToBigEndian, |_, _| {
+                    panic!("nothing to flush")
+                })
+                .unwrap();
+            assert_eq!(log.borrow().writes, [(true, std::vec![0xF8, 0x00])]);
+        }
+
+        #[test]
+        fn yields_between_chunks() {
+            let log = core::cell::RefCell::new(Log::default());
+            let mut data = [Rgb565::BLACK; 3 * 2];
+            let fbuf = FrameBuf::new(&mut data, 3, 2);
+
+            let mut yields = Vec::new();
+            let mut flusher: SpiFlusher<_, _, 4> = SpiFlusher::new(Spi(&log), Dc(&log));
+            flusher
+                .flush_area_with_yield(
+                    &fbuf,
+                    Rectangle::new(Point::zero(), fbuf.size()),
+                    EndianCorrection::ToBigEndian,
+                    |_, _| Ok(()),
+                    || yields.push(log.borrow().writes.len()),
+                )
+                .unwrap();
+            assert_eq!(yields, [1, 2, 3]);
+        }
+    }
+}
+
+#[cfg(feature = "embedded-hal-async")]
+mod spi_async {
+    use embedded_graphics::{
+        pixelcolor::raw::ToBytes,
+        prelude::{PixelColor, Point, Size},
+        primitives::{PointsIter, Rectangle},
+    };
+    use embedded_hal::digital::OutputPin;
+    use embedded_hal_async::spi::SpiDevice;
+
+    use super::FlushError;
+    use crate::{
+        backends::{EndianCorrection, FrameBufferBackend},
+        FrameBuf,
+    };
+
+    /// Async flusher writing framebuffers to an SPI display controller, the
+    /// async counterpart of [`SpiFlusher`](super::SpiFlusher).
+    ///
+    /// As async closures can't borrow the flusher, the RAM window is set up
+    /// with [`AsyncSpiFlusher::command`] before writing the pixels.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let mut flusher: AsyncSpiFlusher<_, _> = AsyncSpiFlusher::new(spi, dc);
+    /// loop {
+    ///     draw_ui(&mut fbuf);
+    ///     if let Some(area) = fbuf.take_dirty_area() {
+    ///         flusher.command(0x2A, &column_params(area)).await?; // CASET
+    ///         flusher.command(0x2B, &row_params(area)).await?; // RASET
+    ///         flusher.command(0x2C, &[]).await?; // RAMWR
+    ///         flusher.write_area(&fbuf, area, EndianCorrection::ToBigEndian).await?;
+    ///     }
+    ///     Timer::after_millis(16).await;
+    /// }
+    /// ```
+    pub struct AsyncSpiFlusher<SPI, DC, const N: usize = 512> {
+        spi: SPI,
+        dc: DC,
+        buf: [u8; N],
+    }
+
+    type Error<SPI, DC> = FlushError<
+        <SPI as embedded_hal_async::spi::ErrorType>::Error,
+        <DC as embedded_hal::digital::ErrorType>::Error,
+    >;
+
+    impl<SPI: SpiDevice, DC: OutputPin, const N: usize> AsyncSpiFlusher<SPI, DC, N> {
+        pub fn new(spi: SPI, dc: DC) -> Self {
+            assert!(N > 0, "AsyncSpiFlusher needs a chunk size larger than 0");
+            Self {
+                spi,
+                dc,
+                buf: [0; N],
+            }
+        }
+
+        /// Return the SPI device and the data/command pin.
+        pub fn release(self) -> (SPI, DC) {
+            (self.spi, self.dc)
+        }
+
+        /// Send a command byte followed by its parameters.
+        pub async fn command(&mut self, command: u8, params: &[u8]) -> Result<(), Error<SPI, DC>> {
+            self.dc.set_low().map_err(FlushError::Pin)?;
+            self.spi.write(&[command]).await.map_err(FlushError::Spi)?;
+            if !params.is_empty() {
+                self.data(params).await?;
+            }
+            Ok(())
+        }
+
+        /// Send raw data bytes.
+        pub async fn data(&mut self, data: &[u8]) -> Result<(), Error<SPI, DC>> {
+            self.dc.set_high().map_err(FlushError::Pin)?;
+            self.spi.write(data).await.map_err(FlushError::Spi)
+        }
+
+        /// Write the pixels of the whole framebuffer, after the RAM window was
+        /// set up for it.
+        pub async fn write<C, B>(
+            &mut self,
+            fbuf: &FrameBuf<C, B>,
+            endian: EndianCorrection,
+        ) -> Result<(), Error<SPI, DC>>
+        where
+            C: PixelColor + ToBytes,
+            <C as ToBytes>::Bytes: AsRef<[u8]>,
+            B: FrameBufferBackend<Color = C>,
+        {
+            let area = Rectangle::new(Point::zero(), fbuf.size());
+            self.write_area(fbuf, area, endian).await
+        }
+
+        /// Write the pixels of an area of the framebuffer, after the RAM
+        /// window was set up for it.
+        ///
+        /// `area` is given in framebuffer coordinates and clipped to the
+        /// framebuffer.
+        pub async fn write_area<C, B>(
+            &mut self,
+            fbuf: &FrameBuf<C, B>,
+            area: Rectangle,
+            endian: EndianCorrection,
+        ) -> Result<(), Error<SPI, DC>>
+        where
+            C: PixelColor + ToBytes,
+            <C as ToBytes>::Bytes: AsRef<[u8]>,
+            B: FrameBufferBackend<Color = C>,
+        {
+            let area = area.intersection(&Rectangle::new(Point::zero(), fbuf.size()));
+            if area.size == Size::zero() {
+                return Ok(());
+            }
+            self.dc.set_high().map_err(FlushError::Pin)?;
+
+            let mut len = 0;
+            for p in area.points() {
+                let color = fbuf.get_color_at(p);
+                let bytes = match endian {
+                    EndianCorrection::ToBigEndian => color.to_be_bytes(),
+                    EndianCorrection::ToLittleEndian => color.to_le_bytes(),
+                };
+                for &byte in bytes.as_ref() {
+                    if len == N {
+                        self.spi.write(&self.buf).await.map_err(FlushError::Spi)?;
+                        len = 0;
+                    }
+                    self.buf[len] = byte;
+                    len += 1;
+                }
+            }
+            self.spi
+                .write(&self.buf[..len])
+                .await
+                .map_err(FlushError::Spi)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        extern crate std;
+
+        use super::*;
+        use core::{
+            convert::Infallible,
+            future::Future,
+            pin::pin,
+            task::{Context, Poll, Waker},
+        };
+        use embedded_graphics::pixelcolor::Rgb565;
+        use embedded_graphics::prelude::RgbColor;
+        use embedded_hal_async::spi::Operation;
+        use std::vec::Vec;
+
+        /// The mocks never return `Pending`, so polling once suffices.
+        fn block_on<F: Future>(future: F) -> F::Output {
+            match pin!(future).poll(&mut Context::from_waker(Waker::noop())) {
+                Poll::Ready(output) => output,
+                Poll::Pending => panic!("mock future pending"),
+            }
+        }
+
+        #[derive(Default)]
+        struct Spi(Vec<Vec<u8>>);
+        struct Dc;
+
+        impl embedded_hal_async::spi::ErrorType for Spi {
+            type Error = Infallible;
+        }
+        impl SpiDevice for Spi {
+            async fn transaction(
+                &mut self,
+                operations: &mut [Operation<'_, u8>],
+            ) -> Result<(), Infallible> {
+                for op in operations {
+                    if let Operation::Write(data) = op {
+                        self.0.push(data.to_vec());
+                    }
+                }
+                Ok(())
+            }
+        }
+        impl embedded_hal::digital::ErrorType for Dc {
+            type Error = Infallible;
+        }
+        impl OutputPin for Dc {
+            fn set_low(&mut self) -> Result<(), Infallible> {
+                Ok(())
+            }
+            fn set_high(&mut self) -> Result<(), Infallible> {
+                Ok(())
+            }
+        }
+
+        #[test]
+        fn writes_in_chunks() {
+            let mut data = [Rgb565::BLACK; 3 * 2];
+            let mut fbuf = FrameBuf::new(&mut data, 3, 2);
+            fbuf.set_color_at(Point::new(1, 1), Rgb565::RED);
+
+            let mut flusher: AsyncSpiFlusher<_, _, 4> = AsyncSpiFlusher::new(Spi::default(), Dc);
+            block_on(flusher.command(0x2C, &[])).unwrap();
+            block_on(flusher.write(&fbuf, EndianCorrection::ToBigEndian)).unwrap();
+            block_on(flusher.write_area(
+                &fbuf,
+                Rectangle::new(Point::new(1, 1), Size::new(9, 9)),
+                EndianCorrection::ToLittleEndian,
+            ))
+            .unwrap();
+
+            let (spi, _) = flusher.release();
+            assert_eq!(
+                spi.0,
+                [
+                    std::vec![0x2C],
+                    std::vec![0; 4],
+                    std::vec![0; 4],
+                    std::vec![0xF8, 0x00, 0, 0],
+                    std::vec![0x00, 0xF8, 0, 0],
+                ]
+            );
+        }
+    }
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
+    /// Iterate over all pixels converted to the color type `D`, e.g. to flush
+    /// an [`Rgb888`] framebuffer to an
+    /// [`Rgb565`](embedded_graphics::pixelcolor::Rgb565) display. See
+    /// [`Converted`] to convert other pixel iterators.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{
+    ///     mock_display::MockDisplay,
+    ///     pixelcolor::{Rgb565, Rgb888},
+    ///     prelude::*,
+    /// };
+    /// use embedded_graphics_framebuf::FrameBuf;
+    /// let mut data = [Rgb888::RED; 12 * 11];
+    /// let fbuf = FrameBuf::new(&mut data, 12, 11);
+    /// let mut display: MockDisplay<Rgb565> = MockDisplay::new();
+    /// display.draw_iter(fbuf.converted::<Rgb565>()).unwrap();
+    /// assert_eq!(display.get_pixel(Point::zero()), Some(Rgb565::RED));
+    /// ```
+    pub fn converted<D>(&self) -> Converted<PixelIterator<'_, C, B>, D>
+    where
+        D: PixelColor + From<C>,
+    {
+        Converted::new(self.pixels())
+    }
+}
+
+/// Iterator adapter converting the colors of pixels to `D`, see
+/// [`FrameBuf::converted`].
+pub struct Converted<I, D> {
+    pixels: I,
+    color: PhantomData<D>,
+}
+
+impl<I, D> Converted<I, D> {
+    /// Convert the colors of `pixels`.
+    pub fn new(pixels: I) -> Self {
+        Self {
+            pixels,
+            color: PhantomData,
+        }
+    }
+}
+
+impl<C, D, I> Iterator for Converted<I, D>
+where
+    C: PixelColor,
+    D: PixelColor + From<C>,
+    I: Iterator<Item = Pixel<C>>,
+{
+    type Item = Pixel<D>;
+
+    fn next(&mut self) -> Option<Pixel<D>> {
+        let Pixel(p, color) = self.pixels.next()?;
+        Some(Pixel(p, color.into()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.pixels.size_hint()
+    }
+}
+
+#[cfg(feature = "smart-leds")]
+mod smart_leds {
+    use embedded_graphics::{
+        pixelcolor::Rgb888,
+        prelude::{PixelColor, Point, RgbColor},
+    };
+    use smart_leds_trait::RGB8;
+
+    use crate::{
+        backends::{FrameBufferBackend, GammaLut},
+        FrameBuf,
+    };
+
+    impl<C: PixelColor, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
+        /// Iterate over the colors of an LED matrix in the order of the strip,
+        /// to be passed to `SmartLedsWrite::write`.
+        ///
+        /// The LEDs are expected row by row, see [`LedColors::serpentine`]
+        /// for matrices wired in a zigzag.
+        ///
+        /// # Example
+        /// ```rust
+        /// use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+        /// use embedded_graphics_framebuf::{backends::GammaLut, FrameBuf};
+        ///
+        /// static GAMMA: GammaLut = GammaLut::from_gamma(2.8);
+        ///
+        /// let mut data = [Rgb888::BLACK; 16 * 16];
+        /// let mut fbuf = FrameBuf::new(&mut data, 16, 16);
+        /// fbuf.set_color_at(Point::new(0, 1), Rgb888::WHITE);
+        /// let colors = fbuf.led_colors().serpentine().gamma(&GAMMA).brightness(64);
+        /// // ws2812.write(colors)?;
+        /// let colors: Vec<_> = colors.collect();
+        /// assert_eq!(colors[31], smart_leds_trait::RGB8::new(64, 64, 64));
+        /// ```
+        pub fn led_colors(&self) -> LedColors<'_, C, B>
+        where
+            C: Into<Rgb888>,
+        {
+            LedColors {
+                fbuf: self,
+                index: 0,
+                serpentine: false,
+                gamma: None,
+                brightness: u8::MAX,
+            }
+        }
+    }
+
+    /// Iterator over the [`RGB8`] colors of an LED matrix, see
+    /// [`FrameBuf::led_colors`].
+    ///
+    /// The gamma correction is applied before the brightness, so limiting the
+    /// brightness scales the power draw linearly.
+    pub struct LedColors<'a, C, B: FrameBufferBackend<Color = C>> {
+        fbuf: &'a FrameBuf<C, B>,
+        index: usize,
+        serpentine: bool,
+        gamma: Option<&'a GammaLut>,
+        brightness: u8,
+    }
+
+    impl<'a, C, B: FrameBufferBackend<Color = C>> LedColors<'a, C, B> {
+        /// Reverse every second row, for matrices whose strip runs back and
+        /// forth.
+        pub fn serpentine(mut self) -> Self {
+            self.serpentine = true;
+            self
+        }
+
+        /// Correct the channels with `lut`.
+        pub fn gamma(mut self, lut: &'a GammaLut) -> Self {
+            self.gamma = Some(lut);
+            self
+        }
+
+        /// Scale the channels to `brightness`, from `0` (off) to `255`
+        /// (unchanged).
+        pub fn brightness(mut self, brightness: u8) -> Self {
+            self.brightness = brightness;
+            self
+        }
+    }
+
+    impl<C, B> Iterator for LedColors<'_, C, B>
+    where
+        C: PixelColor + Into<Rgb888>,
+        B: FrameBufferBackend<Color = C>,
+    {
+        type Item = RGB8;
+
+        fn next(&mut self) -> Option<RGB8> {
+            let (width, height) = (self.fbuf.width(), self.fbuf.height());
+            if self.index >= width * height {
+                return None;
+            }
+            let (y, mut x) = (self.index / width, self.index % width);
+            if self.serpentine && y % 2 == 1 {
+                x = width - 1 - x;
+            }
+            self.index += 1;
+            let color: Rgb888 = self
+                .fbuf
+                .get_color_at(Point::new(x as i32, y as i32))
+                .into();
+            let correct = |channel: u8| {
+                let channel = self.gamma.map_or(channel, |lut| lut.apply(channel));
+                ((channel as u32 * self.brightness as u32 + 127) / 255) as u8
+            };
+            Some(RGB8::new(
+                correct(color.r()),
+                correct(color.g()),
+                correct(color.b()),
+            ))
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            let len = self.fbuf.width() * self.fbuf.height() - self.index;
+            (len, Some(len))
+        }
+    }
+
+    impl<C, B> ExactSizeIterator for LedColors<'_, C, B>
+    where
+        C: PixelColor + Into<Rgb888>,
+        B: FrameBufferBackend<Color = C>,
+    {
+    }
+
+    #[cfg(test)]
+    mod tests {
+        extern crate std;
+
+        use super::*;
+        use embedded_graphics::pixelcolor::Gray8;
+        use std::vec::Vec;
+
+        #[test]
+        fn yields_led_colors() {
+            let mut data = [0, 1, 2, 3, 4, 255].map(Gray8::new);
+            let fbuf = FrameBuf::new(&mut data, 3, 2);
+            let order: Vec<_> = fbuf.led_colors().serpentine().map(|c| c.r).collect();
+            assert_eq!(order, [0, 1, 2, 255, 4, 3]);
+
+            let lut = GammaLut::new([0; 256]);
+            assert!(fbuf.led_colors().gamma(&lut).all(|c| c == RGB8::default()));
+            let dimmed: Vec<_> = fbuf.led_colors().brightness(128).collect();
+            assert_eq!(dimmed[5], RGB8::new(128, 128, 128));
+            assert_eq!(dimmed[2], RGB8::new(1, 1, 1));
+            assert_eq!(fbuf.led_colors().len(), 6);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        pixelcolor::{BinaryColor, Rgb565},
+    };
+    use std::vec::Vec;
+
+    #[test]
+    fn streams_bytes() {
+        let mut data = [Rgb565::new(1, 2, 3), Rgb565::new(4, 5, 6)];
+        let fbuf = FrameBuf::new(&mut data, 2, 1);
+        let be: Vec<_> = fbuf.bytes(EndianCorrection::ToBigEndian).collect();
+        let le: Vec<_> = fbuf.bytes(EndianCorrection::ToLittleEndian).collect();
+        assert_eq!(be, [0x08, 0x43, 0x20, 0xA6]);
+        assert_eq!(le, [0x43, 0x08, 0xA6, 0x20]);
+    }
+
+    #[test]
+    fn interlaces_rows() {
+        let mut data = [BinaryColor::Off; 2 * 5];
+        let mut fbuf = FrameBuf::new_with_origin(&mut data, 2, 5, Point::new(0, 10));
+        fbuf.set_color_at(Point::new(1, 3), BinaryColor::On);
+
+        let pixels: Vec<_> = fbuf.interlaced().collect();
+        let rows: Vec<_> = pixels.iter().step_by(2).map(|p| p.0.y).collect();
+        assert_eq!(rows, [10, 12, 14, 11, 13]);
+        assert_eq!(pixels.len(), 10);
+        assert_eq!(pixels[9], Pixel(Point::new(1, 13), BinaryColor::On));
+    }
+
+    #[test]
+    fn refines_progressively() {
+        let mut data = [BinaryColor::Off; 5 * 3];
+        let mut fbuf = FrameBuf::new(&mut data, 5, 3);
+        fbuf.set_color_at(Point::new(4, 0), BinaryColor::On);
+
+        let blocks: Vec<_> = fbuf.progressive(4).collect();
+        assert_eq!(blocks.len(), 15);
+        assert_eq!(
+            blocks[0].0,
+            Rectangle::new(Point::new(0, 0), Size::new(4, 3))
+        );
+        assert_eq!(
+            blocks[1],
+            (
+                Rectangle::new(Point::new(4, 0), Size::new(1, 3)),
+                BinaryColor::On
+            )
+        );
+        assert_eq!(
+            blocks[2].0,
+            Rectangle::new(Point::new(2, 0), Size::new(2, 2))
+        );
+
+        // Every pixel is sent exactly once as top left corner of a block
+        let mut corners: Vec<_> = blocks
+            .iter()
+            .map(|b| (b.0.top_left.y, b.0.top_left.x))
+            .collect();
+        corners.sort();
+        corners.dedup();
+        assert_eq!(corners.len(), 15);
+    }
+
+    #[test]
+    fn centers_on_larger_and_crops_to_smaller_display() {
+        use embedded_graphics::{draw_target::DrawTarget, mock_display::MockDisplay};
+
+        let mut data = [BinaryColor::On; 2 * 3];
+        let mut fbuf = FrameBuf::new(&mut data, 2, 3);
+        fbuf.set_color_at(Point::new(0, 0), BinaryColor::Off);
+
+        let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+        display
+            .draw_iter(fbuf.letterboxed(Size::new(5, 4), BinaryColor::Off))
+            .unwrap();
+        display.assert_pattern(&["..#..", ".##..", ".##..", "....."]);
+
+        let pixels: Vec<_> = fbuf
+            .letterboxed(Size::new(1, 1), BinaryColor::Off)
+            .collect();
+        assert_eq!(pixels, [Pixel(Point::new(0, 0), BinaryColor::On)]);
+    }
+
+    #[test]
+    fn scales_to_nearest() {
+        let mut data = [BinaryColor::Off, BinaryColor::On];
+        let fbuf = FrameBuf::new(&mut data, 2, 1);
+        let colors: Vec<_> = fbuf.scaled(Size::new(5, 2)).map(|p| p.1).collect();
+        let row = [false, false, true, true, true].map(BinaryColor::from);
+        assert_eq!(colors, [row, row].concat());
+        assert_eq!(fbuf.scaled(Size::new(1, 1)).count(), 1);
+    }
+
+    #[test]
+    fn magnifies() {
+        let mut data = [BinaryColor::Off, BinaryColor::On];
+        let fbuf = FrameBuf::new_with_origin(&mut data, 2, 1, Point::new(0, 5));
+        let pixels: Vec<_> = fbuf.magnified(3).collect();
+        let row = [false, false, false, true, true, true].map(BinaryColor::from);
+        let colors: Vec<_> = pixels.iter().map(|p| p.1).collect();
+        assert_eq!(colors, [row, row, row].concat());
+        assert_eq!(pixels[17].0, Point::new(5, 7));
+        assert_eq!(fbuf.magnified(1).size_hint(), (2, Some(2)));
+    }
+
+    #[test]
+    fn magnifying_skips_masked_pixels() {
+        use crate::mask::Mask;
+
+        let mut data = [BinaryColor::Off, BinaryColor::On];
+        let mut fbuf = FrameBuf::new(&mut data, 2, 1);
+        fbuf.set_mask(Some(Mask::Custom(|p| p.x != 0)));
+        let points: Vec<_> = fbuf.magnified(2).map(|p| p.0).collect();
+        let expected = [(2, 0), (3, 0), (2, 1), (3, 1)].map(|(x, y)| Point::new(x, y));
+        assert_eq!(points, expected);
+        assert_eq!(fbuf.magnified(2).size_hint(), (0, Some(8)));
+    }
+
+    #[test]
+    fn scales_bilinear() {
+        use embedded_graphics::pixelcolor::{Rgb888, RgbColor};
+
+        let mut data = [Rgb888::BLACK, Rgb888::WHITE];
+        let fbuf = FrameBuf::new(&mut data, 2, 1);
+        let reds: Vec<_> = fbuf
+            .scaled_bilinear(Size::new(4, 1))
+            .map(|p| p.1.r())
+            .collect();
+        assert_eq!(reds, [0, 64, 191, 255]);
+        // Scaling 1:1 keeps the picture
+        let same: Vec<_> = fbuf.scaled_bilinear(Size::new(2, 1)).map(|p| p.1).collect();
+        assert_eq!(same, data);
+    }
+
+    #[test]
+    fn scaling_skips_masked_pixels() {
+        use crate::mask::Mask;
+        use embedded_graphics::pixelcolor::Rgb888;
+
+        let mut data = [Rgb888::new(10, 0, 0), Rgb888::new(20, 0, 0)];
+        let mut fbuf = FrameBuf::new(&mut data, 2, 1);
+        fbuf.set_mask(Some(Mask::Custom(|p| p.x != 0)));
+        let scaled: Vec<_> = fbuf.scaled(Size::new(4, 1)).collect();
+        assert_eq!(
+            scaled,
+            [2, 3].map(|x| Pixel(Point::new(x, 0), Rgb888::new(20, 0, 0)))
+        );
+        let points: Vec<_> = fbuf
+            .scaled_bilinear(Size::new(4, 1))
+            .map(|p| p.0.x)
+            .collect();
+        assert_eq!(points, [2, 3]);
+    }
+
+    #[test]
+    fn dims() {
+        use embedded_graphics::pixelcolor::{Rgb888, RgbColor};
+
+        let data = [Rgb888::new(255, 100, 1), Rgb888::BLACK];
+        let fbuf = FrameBuf::new(data, 2, 1);
+        let colors = |brightness| -> Vec<_> { fbuf.dimmed(brightness).map(|p| p.1).collect() };
+        assert_eq!(colors(255), data);
+        assert_eq!(colors(0), [Rgb888::BLACK; 2]);
+        assert_eq!(colors(128), [Rgb888::new(128, 50, 1), Rgb888::BLACK]);
+        let interlaced = Dimmed::new(fbuf.interlaced(), 51).next();
+        assert_eq!(
+            interlaced,
+            Some(Pixel(Point::zero(), Rgb888::new(51, 20, 0)))
+        );
+    }
+
+    #[test]
+    fn converts() {
+        use embedded_graphics::pixelcolor::Rgb888;
+
+        let mut data = [BinaryColor::On, BinaryColor::Off];
+        let fbuf = FrameBuf::new(&mut data, 2, 1);
+        let pixels: Vec<_> = fbuf.converted::<Rgb888>().collect();
+        assert_eq!(
+            pixels,
+            [
+                Pixel(Point::new(0, 0), Rgb888::WHITE),
+                Pixel(Point::new(1, 0), Rgb888::BLACK)
+            ]
+        );
+    }
+
+    #[test]
+    fn flushes_incrementally() {
+        let mut data = [BinaryColor::On; 4 * 3];
+        let mut fbuf = FrameBuf::new_with_origin(&mut data, 4, 3, Point::new(1, 1));
+        fbuf.take_dirty_area();
+        assert!(fbuf.incremental_flush().is_done());
+
+        let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+        let mut flush = IncrementalFlush::new(Rectangle::new(Point::new(2, 1), Size::new(3, 2)));
+        assert_eq!(flush.remaining(), 6);
+        assert!(!flush.step(&fbuf, &mut display, 4).unwrap());
+        display.assert_pattern(&["     ", "     ", "   ##", "   # "]);
+        assert_eq!(flush.remaining(), 2);
+        assert!(flush.step(&fbuf, &mut display, 4).unwrap());
+        display.assert_pattern(&["     ", "     ", "   ##", "   ##"]);
+
+        let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+        let mut flush = IncrementalFlush::new(Rectangle::new(Point::new(2, 1), Size::new(3, 2)));
+        assert!(!flush.step_while(&fbuf, &mut display, 2, || false).unwrap());
+        assert_eq!(flush.remaining(), 4);
+        let mut checks = 0;
+        let done = flush.step_while(&fbuf, &mut display, 1, || {
+            checks += 1;
+            true
+        });
+        assert!(done.unwrap());
+        assert_eq!(checks, 3);
+        display.assert_pattern(&["     ", "     ", "   ##", "   ##"]);
+    }
+
+    #[test]
+    fn shades() {
+        let mut data = [BinaryColor::Off; 3 * 2];
+        let fbuf = FrameBuf::new_with_origin(&mut data, 3, 2, Point::new(10, 0));
+        let highlight = |p: Point, c: BinaryColor| match p.x {
+            11 => c.invert(),
+            _ => c,
+        };
+        let pixels: Vec<_> = fbuf.shaded(highlight).map(|p| p.1).collect();
+        assert_eq!(pixels.iter().filter(|c| c.is_on()).count(), 2);
+        assert_eq!(pixels[1], BinaryColor::On);
+        assert_eq!(data, [BinaryColor::Off; 3 * 2]);
+    }
+}
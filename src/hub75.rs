@@ -0,0 +1,171 @@
+//! Bitplanes for HUB75 LED panels.
+//!
+//! HUB75 panels have no memory of their own: the controller has to shift in
+//! the pixels of one row pair after the other, over and over again. The
+//! brightness of the LEDs is set by binary coded modulation, i.e. each row is
+//! shifted in once per bit of the color depth, and shown for a time
+//! proportional to the weight of that bit.
+//!
+//! [`Hub75Buffer`] converts a framebuffer into these per-row, per-bitplane
+//! patterns, ready to be clocked out by a refresh interrupt (or by DMA to a
+//! GPIO port). Each byte holds the data lines of one column:
+//!
+//! | bit   | 0  | 1  | 2  | 3  | 4  | 5  |
+//! |-------|----|----|----|----|----|----|
+//! | line  | R1 | G1 | B1 | R2 | G2 | B2 |
+//!
+//! where R1, G1 and B1 drive the top half of the panel and R2, G2 and B2 the
+//! bottom half. The planes are double buffered: a new frame is rendered into
+//! the back planes while the refresh keeps reading the front ones, and
+//! [`Hub75Buffer::swap`] publishes it at once.
+//!
+//! ```rust
+//! use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+//! use embedded_graphics_framebuf::{hub75::Hub75Buffer, FrameBuf};
+//!
+//! // A 64x32 panel with 1/16 scan and 6 bit color depth, usually in a static
+//! // shared with the refresh ISR
+//! let mut planes: Hub75Buffer<64, 16, 6> = Hub75Buffer::new();
+//!
+//! let mut data = [Rgb565::BLACK; 64 * 32];
+//! let mut fbuf = FrameBuf::new(&mut data, 64, 32);
+//! fbuf.set_color_at(Point::new(3, 20), Rgb565::RED);
+//!
+//! planes.render(&fbuf);
+//! planes.swap(); // e.g. in a critical section shared with the refresh ISR
+//!
+//! // In the refresh ISR: for each row address and bitplane, shift out the
+//! // row, latch it and show it for `1 << bit` time units.
+//! let row = planes.front_row(4, 5);
+//! assert_eq!(row[3], 0b001_000);
+//! ```
+
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{PixelColor, Point, RgbColor},
+};
+
+use crate::{backends::FrameBufferBackend, FrameBuf};
+
+/// Double buffered bitplanes of a HUB75 panel `W` pixels wide with `SCAN`
+/// row addresses (i.e. `2 * SCAN` pixels high) and `BITS` bits per channel.
+pub struct Hub75Buffer<const W: usize, const SCAN: usize, const BITS: usize> {
+    planes: [[[[u8; W]; SCAN]; BITS]; 2],
+    front: usize,
+}
+
+impl<const W: usize, const SCAN: usize, const BITS: usize> Hub75Buffer<W, SCAN, BITS> {
+    /// Create blank planes.
+    ///
+    /// # Panic
+    /// Panics if `BITS` is not between 1 and 8.
+    pub const fn new() -> Self {
+        assert!(
+            BITS >= 1 && BITS <= 8,
+            "Hub75Buffer needs between 1 and 8 bits per channel"
+        );
+        Self {
+            planes: [[[[0; W]; SCAN]; BITS]; 2],
+            front: 0,
+        }
+    }
+
+    /// Convert `fbuf` into the back planes, using the `BITS` most significant
+    /// bits of each channel.
+    ///
+    /// # Panic
+    /// Panics if `fbuf` isn't `W` x `2 * SCAN` pixels.
+    pub fn render<C, B>(&mut self, fbuf: &FrameBuf<C, B>)
+    where
+        C: PixelColor + Into<Rgb888>,
+        B: FrameBufferBackend<Color = C>,
+    {
+        assert!(
+            fbuf.width() == W && fbuf.height() == 2 * SCAN,
+            "Hub75Buffer of {}x{} pixels doesn't match the framebuffer size {:?}",
+            W,
+            2 * SCAN,
+            fbuf.size()
+        );
+        let back = &mut self.planes[1 - self.front];
+        for scan in 0..SCAN {
+            for x in 0..W {
+                let top: Rgb888 = fbuf.get_color_at(Point::new(x as i32, scan as i32)).into();
+                let bottom: Rgb888 = fbuf
+                    .get_color_at(Point::new(x as i32, (scan + SCAN) as i32))
+                    .into();
+                let channels = [
+                    top.r(),
+                    top.g(),
+                    top.b(),
+                    bottom.r(),
+                    bottom.g(),
+                    bottom.b(),
+                ];
+                for (bit, plane) in back.iter_mut().enumerate() {
+                    let shift = 8 - BITS + bit;
+                    plane[scan][x] = channels
+                        .iter()
+                        .enumerate()
+                        .fold(0, |lines, (line, channel)| {
+                            lines | ((channel >> shift) & 1) << line
+                        });
+                }
+            }
+        }
+    }
+
+    /// Make the rendered back planes the front planes and vice versa.
+    pub fn swap(&mut self) {
+        self.front = 1 - self.front;
+    }
+
+    /// The data lines of the columns of the row pair at the address `scan`,
+    /// in the front bitplane `bit` (`0` being the least significant).
+    pub fn front_row(&self, scan: usize, bit: usize) -> &[u8; W] {
+        &self.planes[self.front][bit][scan]
+    }
+
+    /// All front planes, indexed by bit, row address and column, e.g. to set
+    /// up a DMA transfer.
+    pub fn front(&self) -> &[[[u8; W]; SCAN]; BITS] {
+        &self.planes[self.front]
+    }
+}
+
+impl<const W: usize, const SCAN: usize, const BITS: usize> Default for Hub75Buffer<W, SCAN, BITS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::pixelcolor::Rgb565;
+
+    #[test]
+    fn renders_bitplanes() {
+        let mut data = [Rgb888::BLACK; 2 * 4];
+        let mut fbuf = FrameBuf::new(&mut data, 2, 4);
+        fbuf.set_color_at(Point::new(0, 0), Rgb888::new(0b1000_0000, 0, 0b0100_0000));
+        fbuf.set_color_at(Point::new(1, 3), Rgb888::new(0, 0b1100_0000, 0));
+
+        let mut planes: Hub75Buffer<2, 2, 2> = Hub75Buffer::new();
+        planes.render(&fbuf);
+        assert_eq!(planes.front(), &[[[0; 2]; 2]; 2]);
+        planes.swap();
+        assert_eq!(planes.front_row(0, 0), &[0b000_100, 0]);
+        assert_eq!(planes.front_row(0, 1), &[0b000_001, 0]);
+        assert_eq!(planes.front_row(1, 0), &[0, 0b010_000]);
+        assert_eq!(planes.front_row(1, 1), &[0, 0b010_000]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn renders_only_matching_size() {
+        let mut data = [Rgb565::BLACK; 2 * 2];
+        let fbuf = FrameBuf::new(&mut data, 2, 2);
+        Hub75Buffer::<2, 2, 4>::new().render(&fbuf);
+    }
+}
@@ -0,0 +1,89 @@
+//! Framebuffers as images.
+//!
+//! A [`FrameBuf`] implements [`ImageDrawable`], so it can be drawn onto any
+//! other [`DrawTarget`] with the [`Image`] API of embedded-graphics, e.g. to
+//! compose an off-screen cached widget or a picture-in-picture into the main
+//! framebuffer, or to draw it directly onto a display.
+//!
+//! ```rust
+//! use embedded_graphics::{image::Image, pixelcolor::Rgb565, prelude::*};
+//! use embedded_graphics_framebuf::FrameBuf;
+//!
+//! let mut icon_data = [Rgb565::RED; 16 * 16];
+//! let icon = FrameBuf::new(&mut icon_data, 16, 16);
+//!
+//! let mut data = [Rgb565::BLACK; 64 * 64];
+//! let mut fbuf = FrameBuf::new(&mut data, 64, 64);
+//! Image::new(&icon, Point::new(8, 8)).draw(&mut fbuf).unwrap();
+//! assert_eq!(fbuf.get_color_at(Point::new(23, 23)), Rgb565::RED);
+//! assert_eq!(fbuf.get_color_at(Point::new(24, 24)), Rgb565::BLACK);
+//! ```
+//!
+//! [`Image`]: embedded_graphics::image::Image
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::Dimensions,
+    image::ImageDrawable,
+    prelude::{PixelColor, Point},
+    primitives::{PointsIter, Rectangle},
+};
+
+use crate::{backends::FrameBufferBackend, FrameBuf};
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> ImageDrawable for FrameBuf<C, B> {
+    type Color = C;
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let area = self.bounding_box();
+        target.fill_contiguous(&area, area.points().map(|p| self.get_color_at(p)))
+    }
+
+    fn draw_sub_image<D>(&self, target: &mut D, area: &Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        // Like the images of embedded-graphics, don't draw anything if `area`
+        // is partially outside of the framebuffer.
+        if area.is_zero_sized() || area.intersection(&self.bounding_box()) != *area {
+            return Ok(());
+        }
+        target.fill_contiguous(
+            &Rectangle::new(Point::zero(), area.size),
+            area.points().map(|p| self.get_color_at(p)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::{
+        image::{Image, ImageDrawableExt},
+        mock_display::MockDisplay,
+        pixelcolor::BinaryColor,
+        prelude::{Drawable, Size},
+    };
+
+    #[test]
+    fn draws_sub_images() {
+        let mut data = [BinaryColor::Off; 3 * 2];
+        let mut fbuf = FrameBuf::new(&mut data, 3, 2);
+        fbuf.set_color_at(Point::new(2, 1), BinaryColor::On);
+
+        let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+        let sub = fbuf.sub_image(&Rectangle::new(Point::new(1, 0), Size::new(2, 2)));
+        Image::new(&sub, Point::new(1, 1))
+            .draw(&mut display)
+            .unwrap();
+        display.assert_pattern(&["   ", " ..", " .#"]);
+
+        let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+        let outside = Rectangle::new(Point::new(2, 0), Size::new(2, 2));
+        fbuf.draw_sub_image(&mut display, &outside).unwrap();
+        display.assert_pattern(&[]);
+    }
+}
@@ -0,0 +1,101 @@
+//! Order of the pixels in the backend.
+//!
+//! By default the pixels are stored row by row, like most displays expect
+//! them. Some displays and DMA engines consume the pixels column by column
+//! instead (e.g. panels mounted in portrait orientation), for which the
+//! framebuffer can store its content in [`Layout::ColumnMajor`] order, so no
+//! transpose is needed when flushing.
+//!
+//! ```rust
+//! use embedded_graphics::{pixelcolor::Gray8, prelude::*};
+//! use embedded_graphics_framebuf::{layout::Layout, FrameBuf};
+//!
+//! let mut data = [Gray8::BLACK; 3 * 2];
+//! let mut fbuf = FrameBuf::new(&mut data, 3, 2);
+//! fbuf.set_layout(Layout::ColumnMajor);
+//! fbuf.set_color_at(Point::new(0, 1), Gray8::WHITE);
+//! assert_eq!(data[1], Gray8::WHITE);
+//! ```
+
+use embedded_graphics::prelude::Point;
+
+use crate::{backends::FrameBufferBackend, FrameBuf};
+
+/// Mapping of pixel coordinates to indices in the backend.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Layout {
+    /// Row by row, from the top left to the bottom right
+    #[default]
+    RowMajor,
+    /// Column by column, from the top left to the bottom right
+    ColumnMajor,
+}
+
+impl Layout {
+    /// Index of `p` in a `width` x `height` buffer.
+    pub fn index(self, p: Point, width: usize, height: usize) -> usize {
+        match self {
+            Layout::RowMajor => width * p.y as usize + p.x as usize,
+            Layout::ColumnMajor => height * p.x as usize + p.y as usize,
+        }
+    }
+}
+
+impl<C, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
+    /// Set the order in which the pixels are stored in the backend.
+    ///
+    /// The content of the backend is not rearranged, so the whole framebuffer
+    /// is marked [dirty](crate::dirty) and should be redrawn.
+    ///
+    /// # Panic
+    /// Panics if a [`Layout::ColumnMajor`] is set on a framebuffer with a
+    /// [stride](FrameBuf::new_with_stride) other than its width, or on a
+    /// backend which is
+    /// [row major only](crate::backends::FrameBufferBackend::ROW_MAJOR_ONLY).
+    pub fn set_layout(&mut self, layout: Layout) {
+        assert!(
+            layout == Layout::RowMajor || self.stride == self.width,
+            "FrameBuf stride is only supported in the row major layout"
+        );
+        assert!(
+            layout == Layout::RowMajor || !B::ROW_MAJOR_ONLY,
+            "FrameBuf backend only supports the row major layout"
+        );
+        self.layout = layout;
+        self.mark_all_dirty();
+    }
+
+    /// Get the order in which the pixels are stored in the backend.
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::{
+        draw_target::DrawTarget, mock_display::MockDisplay, pixelcolor::BinaryColor, prelude::Size,
+        primitives::Rectangle,
+    };
+
+    #[test]
+    fn stores_columns() {
+        let mut data = [BinaryColor::Off; 3 * 2];
+        let mut fbuf = FrameBuf::new(&mut data, 3, 2);
+        fbuf.set_layout(Layout::ColumnMajor);
+        fbuf.fill_solid(
+            &Rectangle::new(Point::new(1, 0), Size::new(2, 1)),
+            BinaryColor::On,
+        )
+        .unwrap();
+
+        let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+        display.draw_iter(&fbuf).unwrap();
+        display.assert_pattern(&[".##", "..."]);
+        assert_eq!(
+            data,
+            [false, false, true, false, true, false].map(BinaryColor::from)
+        );
+    }
+}
@@ -40,16 +40,60 @@
 //! ```
 
 #![no_std]
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "embedded-dma")]
 use embedded_dma::{ReadBuffer, WriteBuffer};
 use embedded_graphics::{
     draw_target::DrawTarget,
-    geometry::OriginDimensions,
+    geometry::{Dimensions, OriginDimensions},
     prelude::{PixelColor, Point, Size},
+    primitives::{PointsIter, Rectangle},
     Pixel,
 };
 
 pub mod backends;
-use backends::{DMACapableFrameBufferBackend, FrameBufferBackend};
+pub mod blend;
+pub mod blit;
+pub mod checksum;
+pub mod color;
+pub mod compositor;
+#[cfg(any(test, feature = "conformance"))]
+pub mod conformance;
+pub mod console;
+pub mod coverage;
+pub mod cursor;
+pub mod dirty;
+pub mod dither;
+pub mod dma;
+pub mod double_buffer;
+pub mod drivers;
+pub mod dynamic;
+pub mod fallible;
+pub mod flipdot;
+pub mod flush;
+pub mod hub75;
+pub mod image;
+pub mod layout;
+pub mod lvgl;
+pub mod mask;
+pub mod memory_lcd;
+pub mod pattern;
+pub mod pool;
+pub mod recolor;
+pub mod region;
+pub mod rle;
+pub mod rop;
+pub mod rotation;
+pub mod scroll;
+#[cfg(feature = "critical-section")]
+pub mod shared;
+pub mod split;
+pub mod strict;
+pub mod strip;
+pub mod transition;
+use backends::{ContiguousBackend, DMACapableFrameBufferBackend, FrameBufferBackend};
 
 /// Constructs a frame buffer in memory. Lets you define the width(`X`), height
 /// (`Y`) and pixel type your using in your display (RGB, Monochrome etc.)
@@ -84,6 +128,25 @@ pub struct FrameBuf<C, B: FrameBufferBackend<Color = C>> {
     width: usize,
     height: usize,
     origin: Point,
+    /// Inclusive corners of the area changed since the last flush
+    dirty: Option<(Point, Point)>,
+    /// Separately tracked dirty rectangles, if enabled
+    dirty_regions: Option<dirty::DirtyRegions>,
+    mask: Option<mask::Mask>,
+    layout: layout::Layout,
+    /// Distance between the starts of two rows in the backend
+    stride: usize,
+}
+
+/// Inclusive corners of a whole `width` x `height` framebuffer.
+const fn full_area(width: usize, height: usize) -> Option<(Point, Point)> {
+    if width == 0 || height == 0 {
+        return None;
+    }
+    Some((
+        Point::new(0, 0),
+        Point::new(width as i32 - 1, height as i32 - 1),
+    ))
 }
 
 impl<C, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
@@ -133,14 +196,85 @@ impl<C, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
             width,
             height,
             origin,
+            dirty: full_area(width, height),
+            dirty_regions: None,
+            mask: None,
+            layout: layout::Layout::RowMajor,
+            stride: width,
         }
     }
 
+    /// Create a new [`FrameBuf`] on top of a backend whose rows are `stride`
+    /// pixels apart, with the pixels in between left untouched.
+    ///
+    /// This allows rows padded for alignment, memory shared with display
+    /// controller layers (e.g. LTDC) or rendering into a part of a larger
+    /// buffer. The stride applies to the default
+    /// [`Layout::RowMajor`](layout::Layout::RowMajor) only.
+    ///
+    /// # Panic
+    /// Panics if `stride` is less than `width`, the backend is too small for
+    /// `height` rows, or `stride` is not `width` on a backend which is
+    /// [row major only](FrameBufferBackend::ROW_MAJOR_ONLY).
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+    /// use embedded_graphics_framebuf::FrameBuf;
+    ///
+    /// // A 100x50 window at (20, 10) of a 320x240 buffer
+    /// let mut screen = [Rgb565::BLACK; 320 * 240];
+    /// let window = &mut screen[10 * 320 + 20..];
+    /// let mut fbuf = FrameBuf::new_with_stride(window, 100, 50, 320);
+    /// fbuf.clear(Rgb565::RED).unwrap();
+    /// assert_eq!(screen[10 * 320 + 20], Rgb565::RED);
+    /// assert_eq!(screen[10 * 320 + 120], Rgb565::BLACK);
+    /// assert_eq!(screen[11 * 320 + 20], Rgb565::RED);
+    /// ```
+    pub fn new_with_stride(data: B, width: usize, height: usize, stride: usize) -> Self {
+        assert!(
+            stride >= width,
+            "FrameBuf stride {} is less than the width {}",
+            stride,
+            width
+        );
+        assert!(
+            !B::ROW_MAJOR_ONLY || stride == width,
+            "FrameBuf backend doesn't support a stride"
+        );
+        let fbuf = Self {
+            data,
+            width,
+            height,
+            origin: Point::new(0, 0),
+            dirty: full_area(width, height),
+            dirty_regions: None,
+            mask: None,
+            layout: layout::Layout::RowMajor,
+            stride,
+        };
+        assert!(
+            fbuf.data.nr_elements() >= fbuf.span(),
+            "FrameBuf underlying data size {} is too small for {} rows of {} pixels with a stride of {}",
+            fbuf.data.nr_elements(),
+            height,
+            width,
+            stride,
+        );
+        fbuf
+    }
+
     /// Get the framebuffers width.
     pub fn width(&self) -> usize {
         self.width
     }
 
+    /// Get the distance between the starts of two rows in the backend, see
+    /// [`FrameBuf::new_with_stride`].
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
     /// Get the framebuffers height.
     pub fn height(&self) -> usize {
         self.height
@@ -155,26 +289,331 @@ impl<C, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
         self.origin
     }
 
+    /// Consume the framebuffer and return its backend, e.g. to hand the
+    /// memory over to a display driver.
+    pub fn into_inner(self) -> B {
+        self.data
+    }
+
+    /// Exchange the backend with `other` without copying any pixels.
+    ///
+    /// With a borrowed backend like `&mut [C]` only the references are
+    /// swapped, so a driver or capture pipeline owning a buffer can trade
+    /// frames with the renderer. The whole framebuffer is marked
+    /// [dirty] afterwards.
+    ///
+    /// # Panic
+    /// Panics if `other` is not of the same size as the current backend.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+    /// use embedded_graphics_framebuf::FrameBuf;
+    /// let (mut front, mut back) = ([Rgb565::BLACK; 4 * 4], [Rgb565::BLACK; 4 * 4]);
+    /// let mut fbuf = FrameBuf::new(&mut back[..], 4, 4);
+    /// let mut displayed = &mut front[..];
+    /// fbuf.clear(Rgb565::RED).unwrap();
+    /// fbuf.swap_backend(&mut displayed);
+    /// assert_eq!(displayed[0], Rgb565::RED);
+    /// ```
+    pub fn swap_backend(&mut self, other: &mut B) {
+        assert_eq!(
+            other.nr_elements(),
+            self.data.nr_elements(),
+            "FrameBuf backends of different sizes can't be swapped"
+        );
+        core::mem::swap(&mut self.data, other);
+        self.mark_all_dirty();
+    }
+
+    /// Number of backend elements from the first to the last pixel.
+    fn span(&self) -> usize {
+        match self.height {
+            0 => 0,
+            height => self.stride * (height - 1) + self.width,
+        }
+    }
+
     fn point_to_index(&self, p: Point) -> usize {
-        self.width * p.y as usize + p.x as usize
+        self.layout.index(p, self.stride, self.height)
     }
 
     /// Set a pixel's color.
     pub fn set_color_at(&mut self, p: Point, color: C) {
-        self.data.set(self.point_to_index(p), color)
+        self.data.set(self.point_to_index(p), color);
+        self.mark_point_dirty(p);
     }
 
     /// Get a pixel's color.
     pub fn get_color_at(&self, p: Point) -> C {
         self.data.get(self.point_to_index(p))
     }
+
+    /// Get a pixel's color, or `None` if `p` is outside of the framebuffer.
+    pub fn get(&self, p: Point) -> Option<C> {
+        self.bounding_box()
+            .contains(p)
+            .then(|| self.get_color_at(p))
+    }
+
+    /// Set a pixel's color, or fail if `p` is outside of the framebuffer.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+    /// use embedded_graphics_framebuf::{FrameBuf, OutOfBounds};
+    ///
+    /// let mut data = [BinaryColor::Off; 12 * 11];
+    /// let mut fbuf = FrameBuf::new(&mut data, 12, 11);
+    /// assert_eq!(fbuf.try_set(Point::new(11, 10), BinaryColor::On), Ok(()));
+    /// assert_eq!(
+    ///     fbuf.try_set(Point::new(12, 10), BinaryColor::On),
+    ///     Err(OutOfBounds(Point::new(12, 10)))
+    /// );
+    /// assert_eq!(fbuf.get(Point::new(11, 10)), Some(BinaryColor::On));
+    /// assert_eq!(fbuf.get(Point::new(-1, 0)), None);
+    /// ```
+    pub fn try_set(&mut self, p: Point, color: C) -> Result<(), OutOfBounds> {
+        if !self.bounding_box().contains(p) {
+            return Err(OutOfBounds(p));
+        }
+        self.set_color_at(p, color);
+        Ok(())
+    }
+
+    fn assert_in_bounds(&self, p: Point) {
+        assert!(
+            self.bounding_box().contains(p),
+            "point ({}, {}) is outside of the {}x{} framebuffer",
+            p.x,
+            p.y,
+            self.width,
+            self.height
+        );
+    }
+}
+impl<C: PixelColor, B: ContiguousBackend<Color = C>> FrameBuf<C, B> {
+    /// Iterate over the rows of pixels, e.g. to feed whole lines to a DMA
+    /// transfer or to `fill_contiguous` of a display driver.
+    ///
+    /// # Panic
+    /// Panics if the [layout] is not [`Layout::RowMajor`](layout::Layout::RowMajor).
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+    /// use embedded_graphics_framebuf::FrameBuf;
+    ///
+    /// let mut data = [Rgb565::BLACK; 240 * 135];
+    /// let mut fbuf = FrameBuf::new(&mut data, 240, 135);
+    /// fbuf.row_mut(3).fill(Rgb565::RED);
+    /// for (y, row) in fbuf.rows().enumerate() {
+    ///     assert_eq!(row.len(), 240);
+    ///     assert_eq!(row[0] == Rgb565::RED, y == 3);
+    /// }
+    /// ```
+    pub fn rows(&self) -> Rows<'_, C> {
+        assert_eq!(
+            self.layout,
+            layout::Layout::RowMajor,
+            "FrameBuf rows are only contiguous in the row major layout"
+        );
+        Rows {
+            pixels: self.data.as_slice(),
+            width: self.width,
+            stride: self.stride,
+            rows: 0..self.height,
+        }
+    }
+
+    /// The pixels of row `y`, which is marked dirty.
+    ///
+    /// # Panic
+    /// Panics if `y` is out of bounds or the [layout] is not
+    /// [`Layout::RowMajor`](layout::Layout::RowMajor).
+    pub fn row_mut(&mut self, y: usize) -> &mut [C] {
+        assert_eq!(
+            self.layout,
+            layout::Layout::RowMajor,
+            "FrameBuf rows are only contiguous in the row major layout"
+        );
+        assert!(y < self.height, "row {} out of bounds", y);
+        self.mark_dirty(Rectangle::new(
+            Point::new(0, y as i32),
+            Size::new(self.width as u32, 1),
+        ));
+        let start = y * self.stride;
+        &mut self.data.as_mut_slice()[start..start + self.width]
+    }
+
+    /// The pixels as bytes, e.g. for display drivers or DMA HALs expecting
+    /// `&[u8]`.
+    ///
+    /// Needs the `bytemuck` feature and a color type with a fixed memory
+    /// representation, like [`Rgb565BE`](color::Rgb565BE). The colors of
+    /// embedded-graphics don't guarantee one, for them see
+    /// [`ByteBuffer`](backends::ByteBuffer).
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+    /// use embedded_graphics_framebuf::{color::Rgb565BE, FrameBuf};
+    ///
+    /// let mut data = [Rgb565BE::BLACK; 240 * 135];
+    /// let mut fbuf = FrameBuf::new(&mut data, 240, 135);
+    /// fbuf.set_color_at(Point::zero(), Rgb565::RED.into());
+    /// // spi.write(fbuf.as_bytes())?;
+    /// assert_eq!(fbuf.as_bytes()[..2], [0xF8, 0x00]);
+    /// ```
+    #[cfg(feature = "bytemuck")]
+    pub fn as_bytes(&self) -> &[u8]
+    where
+        C: bytemuck::Pod,
+    {
+        bytemuck::cast_slice(self.data.as_slice())
+    }
+
+    /// The pixels as mutable bytes, e.g. as the target of a DMA transfer or
+    /// a decoder. The whole framebuffer is marked dirty.
+    ///
+    /// Needs the `bytemuck` feature, see [`FrameBuf::as_bytes`].
+    #[cfg(feature = "bytemuck")]
+    pub fn as_mut_bytes(&mut self) -> &mut [u8]
+    where
+        C: bytemuck::Pod,
+    {
+        self.mark_all_dirty();
+        bytemuck::cast_slice_mut(self.data.as_mut_slice())
+    }
+}
+
+/// Access to the pixels of framebuffers with a [`ContiguousBackend`] by
+/// point.
+///
+/// # Panic
+/// Panics if the point is outside of the framebuffer.
+///
+/// # Example
+/// ```rust
+/// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+/// use embedded_graphics_framebuf::FrameBuf;
+///
+/// let mut data = [Rgb565::BLACK; 240 * 135];
+/// let mut fbuf = FrameBuf::new(&mut data, 240, 135);
+/// fbuf[Point::new(10, 20)] = Rgb565::RED;
+/// assert_eq!(fbuf[Point::new(10, 20)], Rgb565::RED);
+/// ```
+impl<C: PixelColor, B: ContiguousBackend<Color = C>> core::ops::Index<Point> for FrameBuf<C, B> {
+    type Output = C;
+
+    fn index(&self, p: Point) -> &C {
+        self.assert_in_bounds(p);
+        &self.data.as_slice()[self.point_to_index(p)]
+    }
 }
+
+/// The pixel is marked dirty.
+impl<C: PixelColor, B: ContiguousBackend<Color = C>> core::ops::IndexMut<Point> for FrameBuf<C, B> {
+    fn index_mut(&mut self, p: Point) -> &mut C {
+        self.assert_in_bounds(p);
+        self.mark_point_dirty(p);
+        let index = self.point_to_index(p);
+        &mut self.data.as_mut_slice()[index]
+    }
+}
+
+impl<C: PixelColor, const N: usize> FrameBuf<C, [C; N]> {
+    /// Create a new [`FrameBuf`] that owns its memory.
+    ///
+    /// In contrast to [`FrameBuf::new`] this is a `const fn`, so the
+    /// framebuffer can be placed in a `static` or embedded by value in other
+    /// structs.
+    ///
+    /// # Panic
+    /// Panics if `N` does not match the given width and height.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::pixelcolor::BinaryColor;
+    /// use embedded_graphics_framebuf::FrameBuf;
+    /// static FBUF: FrameBuf<BinaryColor, [BinaryColor; 128 * 64]> =
+    ///     FrameBuf::new_owned([BinaryColor::Off; 128 * 64], 128, 64);
+    /// ```
+    pub const fn new_owned(data: [C; N], width: usize, height: usize) -> Self {
+        assert!(
+            N == width * height,
+            "FrameBuf underlying data size does not match width * height"
+        );
+        Self {
+            data,
+            width,
+            height,
+            origin: Point::new(0, 0),
+            dirty: full_area(width, height),
+            dirty_regions: None,
+            mask: None,
+            layout: layout::Layout::RowMajor,
+            stride: width,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<C: PixelColor> FrameBuf<C, alloc::boxed::Box<[C]>> {
+    /// Create a new [`FrameBuf`] on the heap, filled with `color`.
+    ///
+    /// The pixels are initialized in place, so in contrast to
+    /// `Box::new([color; N])` the buffer never exists on the stack, which
+    /// would overflow it for large framebuffers.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::Rgb565, prelude::RgbColor};
+    /// use embedded_graphics_framebuf::FrameBuf;
+    /// let fbuf = FrameBuf::new_boxed(Rgb565::BLACK, 320, 240);
+    /// ```
+    pub fn new_boxed(color: C, width: usize, height: usize) -> Self {
+        Self::new(
+            alloc::vec![color; width * height].into_boxed_slice(),
+            width,
+            height,
+        )
+    }
+}
+
 impl<C: PixelColor + Default, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
     pub fn reset(&mut self) {
         self.clear(C::default()).unwrap();
     }
 }
 
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
+    /// Iterate over all [Pixels](Pixel) row by row, e.g. to pass them to
+    /// [`DrawTarget::draw_iter`] of a display driver.
+    ///
+    /// The points are in display coordinates (offset by the origin) and
+    /// pixels hidden by the [mask] are skipped. This is the same as iterating
+    /// over `&fbuf`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+    /// use embedded_graphics_framebuf::FrameBuf;
+    ///
+    /// let mut data = [BinaryColor::Off; 12 * 11];
+    /// let mut fbuf = FrameBuf::new_with_origin(&mut data, 12, 11, Point::new(100, 0));
+    /// fbuf.set_color_at(Point::new(1, 0), BinaryColor::On);
+    /// let mut pixels = fbuf.pixels();
+    /// assert_eq!(pixels.nth(1), Some(Pixel(Point::new(101, 0), BinaryColor::On)));
+    /// ```
+    pub fn pixels(&self) -> PixelIterator<'_, C, B> {
+        PixelIterator {
+            fbuf: self,
+            index: 0,
+        }
+    }
+}
+
 impl<'a, C: PixelColor, B: FrameBufferBackend<Color = C>> IntoIterator for &'a FrameBuf<C, B> {
     type Item = Pixel<C>;
     type IntoIter = PixelIterator<'a, C, B>;
@@ -203,10 +642,7 @@ impl<'a, C: PixelColor, B: FrameBufferBackend<Color = C>> IntoIterator for &'a F
     /// display.draw_iter(fbuf.into_iter()).unwrap();
     /// ```
     fn into_iter(self) -> Self::IntoIter {
-        PixelIterator {
-            fbuf: self,
-            index: 0,
-        }
+        self.pixels()
     }
 }
 
@@ -229,6 +665,7 @@ impl<C: PixelColor, B: FrameBufferBackend<Color = C>> DrawTarget for FrameBuf<C,
                 && coord.x < self.width as i32
                 && coord.y >= 0
                 && coord.y < self.height as i32
+                && self.is_visible(coord)
             {
                 self.set_color_at(coord, color);
             }
@@ -236,16 +673,172 @@ impl<C: PixelColor, B: FrameBufferBackend<Color = C>> DrawTarget for FrameBuf<C,
         Ok(())
     }
 
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let drawable = area.intersection(&self.bounding_box());
+        if self.mask.is_some() || self.layout != layout::Layout::RowMajor {
+            for (p, color) in area.points().zip(colors) {
+                if drawable.contains(p) && self.is_visible(p) {
+                    let index = self.point_to_index(p);
+                    self.data.set(index, color);
+                }
+            }
+        } else if !drawable.is_zero_sized() {
+            // Skip the colors of clipped pixels, and write the visible part of
+            // each row at once.
+            fn skip<I: Iterator>(colors: &mut I, n: usize) {
+                if n > 0 {
+                    colors.nth(n - 1);
+                }
+            }
+            let mut colors = colors.into_iter();
+            let width = area.size.width as usize;
+            let visible = drawable.size.width as usize;
+            let left = (drawable.top_left.x - area.top_left.x) as usize;
+            let above = (drawable.top_left.y - area.top_left.y) as usize;
+            skip(&mut colors, above * width);
+            for y in drawable.rows() {
+                skip(&mut colors, left);
+                let start = self.point_to_index(Point::new(drawable.top_left.x, y));
+                let row = colors.by_ref().take(visible);
+                match self.data.as_contiguous_mut() {
+                    Some(pixels) => {
+                        for (pixel, color) in pixels[start..start + visible].iter_mut().zip(row) {
+                            *pixel = color;
+                        }
+                    }
+                    None => {
+                        for (index, color) in (start..start + visible).zip(row) {
+                            self.data.set(index, color);
+                        }
+                    }
+                }
+                skip(&mut colors, width - left - visible);
+            }
+        }
+        self.mark_dirty(drawable);
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&self.bounding_box());
+        if self.mask.is_some() || self.layout != layout::Layout::RowMajor {
+            return self.draw_iter(area.points().map(|p| Pixel(p, color)));
+        }
+        let width = area.size.width as usize;
+        for y in area.rows() {
+            let start = self.point_to_index(Point::new(area.top_left.x, y));
+            self.data.fill_range(start..start + width, color);
+        }
+        self.mark_dirty(area);
+        Ok(())
+    }
+
     fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        if self.mask.is_none() && self.stride != self.width {
+            return self.fill_solid(&self.bounding_box(), color);
+        } else if self.mask.is_none() {
+            // The backend may be longer than the framebuffer, e.g. a window
+            // into a larger buffer.
+            let span = self.span();
+            if self.data.nr_elements() == span {
+                self.data.fill(color);
+            } else {
+                self.data.fill_range(0..span, color);
+            }
+            self.mark_all_dirty();
+            return Ok(());
+        }
         for y in 0..self.height {
             for x in 0..self.width {
-                self.set_color_at(Point::new(x as i32, y as i32), color);
+                let p = Point::new(x as i32, y as i32);
+                if self.is_visible(p) {
+                    self.set_color_at(p, color);
+                }
             }
         }
         Ok(())
     }
 }
 
+/// Error for a pixel outside of a framebuffer, e.g. of the [strict] draw
+/// target.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OutOfBounds(pub Point);
+
+impl core::fmt::Display for OutOfBounds {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "pixel ({}, {}) is out of bounds", self.0.x, self.0.y)
+    }
+}
+
+/// A [`DrawTarget`] whose pixels can be read back.
+///
+/// Allows writing read-modify-write algorithms (blending, fades, smears, ...)
+/// generically, instead of against [`FrameBuf`] only.
+///
+/// # Example
+/// ```rust
+/// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+/// use embedded_graphics_framebuf::{FrameBuf, ReadableDrawTarget};
+///
+/// /// Halve the brightness of every pixel.
+/// fn dim<D: ReadableDrawTarget<Color = Rgb565>>(target: &mut D) -> Result<(), D::Error> {
+///     let area = target.bounding_box();
+///     let pixels: Vec<_> = area
+///         .points()
+///         .map(|p| {
+///             let c = target.get_color_at(p);
+///             Pixel(p, Rgb565::new(c.r() / 2, c.g() / 2, c.b() / 2))
+///         })
+///         .collect();
+///     target.draw_iter(pixels)
+/// }
+///
+/// let mut data = [Rgb565::WHITE; 4 * 4];
+/// let mut fbuf = FrameBuf::new(&mut data, 4, 4);
+/// dim(&mut fbuf).unwrap();
+/// assert_eq!(fbuf.get_color_at(Point::new(3, 3)), Rgb565::new(15, 31, 15));
+/// ```
+pub trait ReadableDrawTarget: DrawTarget {
+    /// Get the color of the pixel at `p`.
+    ///
+    /// `p` has to be inside of the target's bounding box, the result for
+    /// other points is unspecified and might panic.
+    fn get_color_at(&self, p: Point) -> Self::Color;
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> ReadableDrawTarget for FrameBuf<C, B> {
+    fn get_color_at(&self, p: Point) -> C {
+        FrameBuf::get_color_at(self, p)
+    }
+}
+
+/// An iterator over the rows of a framebuffer, see [`FrameBuf::rows`].
+pub struct Rows<'a, C> {
+    pixels: &'a [C],
+    width: usize,
+    stride: usize,
+    rows: core::ops::Range<usize>,
+}
+
+impl<'a, C> Iterator for Rows<'a, C> {
+    type Item = &'a [C];
+
+    fn next(&mut self) -> Option<&'a [C]> {
+        let start = self.rows.next()? * self.stride;
+        Some(&self.pixels[start..start + self.width])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.rows.size_hint()
+    }
+}
+
+impl<C> ExactSizeIterator for Rows<'_, C> {}
+
 /// An iterator for all [Pixels](Pixel) in the framebuffer.
 pub struct PixelIterator<'a, C, B: FrameBufferBackend<Color = C>> {
     fbuf: &'a FrameBuf<C, B>,
@@ -255,18 +848,47 @@ pub struct PixelIterator<'a, C, B: FrameBufferBackend<Color = C>> {
 impl<'a, C: PixelColor, B: FrameBufferBackend<Color = C>> Iterator for PixelIterator<'a, C, B> {
     type Item = Pixel<C>;
     fn next(&mut self) -> Option<Pixel<C>> {
-        let y = self.index / self.fbuf.width;
-        let x = self.index - y * self.fbuf.width;
+        loop {
+            let y = self.index / self.fbuf.width;
+            let x = self.index - y * self.fbuf.width;
 
-        if self.index >= self.fbuf.width * self.fbuf.height {
-            return None;
+            if self.index >= self.fbuf.width * self.fbuf.height {
+                return None;
+            }
+            self.index += 1;
+            let p = Point::new(x as i32, y as i32);
+            if self.fbuf.is_visible(p) {
+                return Some(Pixel(self.fbuf.origin + p, self.fbuf.get_color_at(p)));
+            }
         }
-        self.index += 1;
-        let p = Point::new(x as i32, y as i32);
-        Some(Pixel(self.fbuf.origin + p, self.fbuf.get_color_at(p)))
     }
 }
 
+impl<C, B: DMACapableFrameBufferBackend<Color = C>> FrameBuf<C, B> {
+    /// Pointer to and length of the bytes of the framebuffer, for DMA.
+    pub(crate) fn dma_bytes(&self) -> (*const u8, usize) {
+        (
+            self.data.data_ptr() as *const u8,
+            self.span() * core::mem::size_of::<C>(),
+        )
+    }
+}
+
+/// Framebuffers on top of a [`DMACapableFrameBufferBackend`] can be handed to
+/// DMA transfers directly. The buffer is exposed as bytes, so e.g. a 16 bit
+/// color takes two words.
+///
+/// ```rust
+/// use embedded_dma::ReadBuffer;
+/// use embedded_graphics::{pixelcolor::Rgb565, prelude::RgbColor};
+/// use embedded_graphics_framebuf::FrameBuf;
+///
+/// let mut data = [Rgb565::BLACK; 320 * 240];
+/// let fbuf = FrameBuf::new(&mut data, 320, 240);
+/// let (_ptr, len) = unsafe { fbuf.read_buffer() };
+/// assert_eq!(len, 320 * 240 * 2);
+/// ```
+#[cfg(feature = "embedded-dma")]
 unsafe impl<C, B: DMACapableFrameBufferBackend<Color = C>> ReadBuffer
     for FrameBuf<C, B>
 {
@@ -274,13 +896,12 @@ unsafe impl<C, B: DMACapableFrameBufferBackend<Color = C>> ReadBuffer
     unsafe fn read_buffer(&self) -> (*const Self::Word, usize) {
         (
             (self.data.data_ptr() as *const Self::Word),
-            self.height
-                * self.width
-                * (core::mem::size_of::<C>() / core::mem::size_of::<Self::Word>()),
+            self.span() * (core::mem::size_of::<C>() / core::mem::size_of::<Self::Word>()),
         )
     }
 }
 
+#[cfg(feature = "embedded-dma")]
 unsafe impl<C, B: DMACapableFrameBufferBackend<Color = C>> WriteBuffer
     for FrameBuf<C, B>
 {
@@ -288,9 +909,7 @@ unsafe impl<C, B: DMACapableFrameBufferBackend<Color = C>> WriteBuffer
     unsafe fn write_buffer(&mut self) -> (*mut Self::Word, usize) {
         (
             (self.data.data_ptr() as *mut Self::Word),
-            self.height
-                * self.width
-                * (core::mem::size_of::<C>() / core::mem::size_of::<Self::Word>()),
+            self.span() * (core::mem::size_of::<C>() / core::mem::size_of::<Self::Word>()),
         )
     }
 }
@@ -420,6 +1039,204 @@ mod tests {
         assert_eq!(*raw_iter.next().unwrap(), Rgb565::new(3, 2, 1));
     }
 
+    #[test]
+    fn swaps_backend() {
+        let mut a = [BinaryColor::Off; 2];
+        let mut b = [BinaryColor::On; 2];
+        let mut fbuf = FrameBuf::new(&mut a[..], 2, 1);
+        fbuf.take_dirty_area();
+        let mut other = &mut b[..];
+        fbuf.swap_backend(&mut other);
+        assert_eq!(fbuf.get_color_at(Point::new(0, 0)), BinaryColor::On);
+        assert!(fbuf.dirty_area().is_some());
+        other[1] = BinaryColor::On;
+        assert_eq!(a, [BinaryColor::Off, BinaryColor::On]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn swaps_only_same_size() {
+        let mut a = [BinaryColor::Off; 2];
+        let mut b = [BinaryColor::On; 3];
+        let mut fbuf = FrameBuf::new(&mut a[..], 2, 1);
+        fbuf.swap_backend(&mut &mut b[..]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn new_boxed() {
+        let mut fbuf = FrameBuf::new_boxed(BinaryColor::On, 100, 50);
+        assert_eq!(fbuf.data.len(), 5000);
+        fbuf.set_color_at(Point::new(99, 49), BinaryColor::Off);
+        assert_eq!(fbuf.data[4999], BinaryColor::Off);
+        assert_eq!(fbuf.data[0], BinaryColor::On);
+    }
+
+    #[test]
+    fn fills_rows_and_runs() {
+        let mut data = [BinaryColor::Off; 4 * 3];
+        let mut fbuf = FrameBuf::new(&mut data, 4, 3);
+        fbuf.take_dirty_area();
+        fbuf.fill_solid(
+            &Rectangle::new(Point::new(2, 1), Size::new(5, 5)),
+            BinaryColor::On,
+        )
+        .unwrap();
+        assert_eq!(
+            fbuf.take_dirty_area(),
+            Some(Rectangle::new(Point::new(2, 1), Size::new(2, 2)))
+        );
+        fbuf.fill_contiguous(
+            &Rectangle::new(Point::new(-1, 0), Size::new(2, 2)),
+            [true, true, false, true].map(BinaryColor::from),
+        )
+        .unwrap();
+        assert_eq!(
+            fbuf.take_dirty_area(),
+            Some(Rectangle::new(Point::new(0, 0), Size::new(1, 2)))
+        );
+
+        let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+        display.draw_iter(&fbuf).unwrap();
+        display.assert_pattern(&["#...", "#.##", "..##"]);
+    }
+
+    #[test]
+    fn fills_clipped_contiguous_rows() {
+        use embedded_graphics::pixelcolor::Gray8;
+
+        let colors = (0..12).map(Gray8::new);
+        let area = Rectangle::new(Point::new(-1, -1), Size::new(4, 3));
+        let mut data = [Gray8::new(99); 2 * 3];
+        let mut fbuf = FrameBuf::new(&mut data, 2, 3);
+        fbuf.fill_contiguous(&area, colors.clone()).unwrap();
+        assert_eq!(data, [5, 6, 9, 10, 99, 99].map(Gray8::new));
+
+        // The same through the per-pixel path
+        let mut data = [Gray8::new(99); 2 * 3];
+        let mut fbuf = FrameBuf::new(&mut data, 2, 3);
+        fbuf.set_layout(layout::Layout::ColumnMajor);
+        fbuf.fill_contiguous(&area, colors).unwrap();
+        assert_eq!(data, [5, 9, 99, 6, 10, 99].map(Gray8::new));
+    }
+
+    #[test]
+    fn accesses_rows() {
+        let mut data = [BinaryColor::Off; 3 * 2];
+        let mut fbuf = FrameBuf::new(&mut data, 3, 2);
+        fbuf.take_dirty_area();
+        fbuf.row_mut(1)[2] = BinaryColor::On;
+        assert_eq!(
+            fbuf.take_dirty_area(),
+            Some(Rectangle::new(Point::new(0, 1), Size::new(3, 1)))
+        );
+        let mut rows = fbuf.rows();
+        assert_eq!(rows.next(), Some(&[BinaryColor::Off; 3][..]));
+        assert_eq!(
+            rows.next(),
+            Some(&[BinaryColor::Off, BinaryColor::Off, BinaryColor::On][..])
+        );
+        assert_eq!(rows.next(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn accesses_rows_only_row_major() {
+        let mut data = [BinaryColor::Off; 3 * 2];
+        let mut fbuf = FrameBuf::new(&mut data, 3, 2);
+        fbuf.set_layout(layout::Layout::ColumnMajor);
+        fbuf.row_mut(0);
+    }
+
+    #[test]
+    fn draws_with_stride() {
+        use embedded_graphics::pixelcolor::Gray8;
+        use embedded_graphics::prelude::GrayColor;
+
+        // A 2x2 window at (1, 1) of a 4x3 buffer
+        let mut data = [Gray8::BLACK; 4 * 3];
+        let mut fbuf = FrameBuf::new_with_stride(&mut data[5..], 2, 2, 4);
+        assert_eq!(fbuf.stride(), 4);
+        fbuf.clear(Gray8::new(1)).unwrap();
+        fbuf.set_color_at(Point::new(1, 1), Gray8::new(2));
+        fbuf.row_mut(0)[0] = Gray8::new(3);
+        let rows: std::vec::Vec<_> = fbuf.rows().collect();
+        assert_eq!(rows, [[3, 1].map(Gray8::new), [1, 2].map(Gray8::new)]);
+        assert_eq!(data, [0, 0, 0, 0, 0, 3, 1, 0, 0, 1, 2, 0].map(Gray8::new));
+    }
+
+    #[test]
+    fn clears_only_the_span() {
+        use embedded_graphics::pixelcolor::Gray8;
+        use embedded_graphics::prelude::GrayColor;
+
+        // Two full rows at the top of a 2x3 buffer
+        let mut data = [Gray8::BLACK; 2 * 3];
+        let mut fbuf = FrameBuf::new_with_stride(&mut data[..], 2, 2, 2);
+        fbuf.clear(Gray8::new(1)).unwrap();
+        assert_eq!(data, [1, 1, 1, 1, 0, 0].map(Gray8::new));
+    }
+
+    #[test]
+    #[should_panic]
+    fn checks_stride_size() {
+        let mut data = [BinaryColor::Off; 4 * 3];
+        FrameBuf::new_with_stride(&mut data[..], 3, 3, 5);
+    }
+
+    #[test]
+    fn indexes_by_point() {
+        let mut data = [BinaryColor::Off; 3 * 2];
+        let mut fbuf = FrameBuf::new(&mut data, 3, 2);
+        fbuf.set_layout(layout::Layout::ColumnMajor);
+        fbuf.take_dirty_area();
+        fbuf[Point::new(2, 0)] = BinaryColor::On;
+        assert_eq!(
+            fbuf.take_dirty_area(),
+            Some(Rectangle::new(Point::new(2, 0), Size::new(1, 1)))
+        );
+        assert_eq!(fbuf[Point::new(2, 0)], BinaryColor::On);
+        assert_eq!(fbuf.get(Point::new(2, 0)), Some(BinaryColor::On));
+        assert_eq!(fbuf.data[4], BinaryColor::On);
+    }
+
+    #[test]
+    #[should_panic]
+    fn indexes_only_in_bounds() {
+        let mut data = [BinaryColor::Off; 3 * 2];
+        let fbuf = FrameBuf::new(&mut data, 3, 2);
+        let _ = fbuf[Point::new(3, 0)];
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn views_bytes() {
+        use crate::color::Rgb565BE;
+        use embedded_graphics::pixelcolor::{Rgb565, RgbColor};
+
+        let mut data = [Rgb565BE::BLACK; 2 * 2];
+        let mut fbuf = FrameBuf::new(&mut data, 2, 2);
+        fbuf.take_dirty_area();
+        fbuf.as_mut_bytes()[2..4].copy_from_slice(&[0x00, 0x1F]);
+        assert_eq!(fbuf.take_dirty_area(), Some(fbuf.bounding_box()));
+        assert_eq!(fbuf.get_color_at(Point::new(1, 0)), Rgb565::BLUE.into());
+        assert_eq!(fbuf.as_bytes().len(), 2 * 2 * 2);
+    }
+
+    #[test]
+    fn owns_data() {
+        struct App {
+            fbuf: FrameBuf<BinaryColor, [BinaryColor; 3 * 2]>,
+        }
+        const FBUF: FrameBuf<BinaryColor, [BinaryColor; 3 * 2]> =
+            FrameBuf::new_owned([BinaryColor::Off; 3 * 2], 3, 2);
+
+        let mut app = App { fbuf: FBUF };
+        app.fbuf.set_color_at(Point::new(2, 1), BinaryColor::On);
+        assert_eq!(app.fbuf.data[5], BinaryColor::On);
+        assert_eq!(FBUF.data[5], BinaryColor::Off);
+    }
+
     #[test]
     #[should_panic]
     fn wrong_data_size() {
@@ -0,0 +1,138 @@
+//! Adapter for the [LVGL](https://lvgl.io) display flush contract.
+//!
+//! LVGL renders into its own draw buffers and hands finished areas to a flush
+//! callback as an inclusive `x1..=x2`/`y1..=y2` area plus a buffer of colors in
+//! row-major order. The helpers in here blit such an area into a
+//! [`FrameBuf`], so LVGL and `embedded-graphics` can share one framebuffer.
+//!
+//! Example with [lvgl-rs](https://github.com/lvgl/lv_binding_rust):
+//! ```rust,ignore
+//! let mut display = Display::register(draw_buffer, 240, 135, |refresh| {
+//!     let area = LvglArea::new(
+//!         refresh.area.x1.into(),
+//!         refresh.area.y1.into(),
+//!         refresh.area.x2.into(),
+//!         refresh.area.y2.into(),
+//!     );
+//!     fbuf.lvgl_flush(&area, refresh.colors.iter().map(|c| Rgb565::from(*c)));
+//! })?;
+//! ```
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    pixelcolor::raw::RawU16,
+    prelude::{PixelColor, Point},
+    primitives::Rectangle,
+};
+
+use crate::{backends::FrameBufferBackend, FrameBuf};
+
+/// An area as passed to LVGL's flush callback (`lv_area_t`).
+///
+/// Unlike [`Rectangle`], both corners are inclusive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LvglArea {
+    pub x1: i32,
+    pub y1: i32,
+    pub x2: i32,
+    pub y2: i32,
+}
+
+impl LvglArea {
+    pub fn new(x1: i32, y1: i32, x2: i32, y2: i32) -> Self {
+        Self { x1, y1, x2, y2 }
+    }
+
+    /// Number of pixels LVGL delivers for this area.
+    pub fn nr_pixels(&self) -> usize {
+        let width = (self.x2 - self.x1 + 1).max(0) as usize;
+        let height = (self.y2 - self.y1 + 1).max(0) as usize;
+        width * height
+    }
+}
+
+impl From<LvglArea> for Rectangle {
+    fn from(area: LvglArea) -> Self {
+        if area.x2 < area.x1 || area.y2 < area.y1 {
+            return Rectangle::zero();
+        }
+        Rectangle::with_corners(Point::new(area.x1, area.y1), Point::new(area.x2, area.y2))
+    }
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
+    /// Blit an area rendered by LVGL into the framebuffer.
+    ///
+    /// `colors` must yield the pixels of `area` in row-major order. Pixels
+    /// outside of the framebuffer are discarded.
+    pub fn lvgl_flush<I>(&mut self, area: &LvglArea, colors: I)
+    where
+        I: IntoIterator<Item = C>,
+    {
+        self.fill_contiguous(&Rectangle::from(*area), colors)
+            .unwrap();
+    }
+
+    /// Blit a raw 16 bit LVGL color buffer (`LV_COLOR_DEPTH 16`) into the
+    /// framebuffer.
+    ///
+    /// Set `swapped` if LVGL was built with `LV_COLOR_16_SWAP`.
+    pub fn lvgl_flush_raw(&mut self, area: &LvglArea, colors: &[u16], swapped: bool)
+    where
+        C: From<RawU16>,
+    {
+        self.lvgl_flush(
+            area,
+            colors.iter().map(|&raw| {
+                let raw = if swapped { raw.swap_bytes() } else { raw };
+                C::from(RawU16::new(raw))
+            }),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::pixelcolor::{IntoStorage, Rgb565};
+    use embedded_graphics::prelude::RgbColor;
+
+    #[test]
+    fn converts_inclusive_area() {
+        let rect = Rectangle::from(LvglArea::new(2, 3, 4, 3));
+        assert_eq!(rect.top_left, Point::new(2, 3));
+        assert_eq!(rect.size.width, 3);
+        assert_eq!(rect.size.height, 1);
+        assert_eq!(LvglArea::new(2, 3, 4, 3).nr_pixels(), 3);
+        assert_eq!(
+            Rectangle::from(LvglArea::new(4, 3, 2, 3)),
+            Rectangle::zero()
+        );
+    }
+
+    #[test]
+    fn flushes_area_into_buffer() {
+        let mut data = [Rgb565::BLACK; 4 * 3];
+        let mut fbuf = FrameBuf::new(&mut data, 4, 3);
+        fbuf.lvgl_flush(
+            &LvglArea::new(1, 1, 2, 2),
+            [Rgb565::RED, Rgb565::GREEN, Rgb565::BLUE, Rgb565::WHITE],
+        );
+        assert_eq!(fbuf.get_color_at(Point::new(1, 1)), Rgb565::RED);
+        assert_eq!(fbuf.get_color_at(Point::new(2, 1)), Rgb565::GREEN);
+        assert_eq!(fbuf.get_color_at(Point::new(1, 2)), Rgb565::BLUE);
+        assert_eq!(fbuf.get_color_at(Point::new(2, 2)), Rgb565::WHITE);
+        assert_eq!(fbuf.get_color_at(Point::new(0, 0)), Rgb565::BLACK);
+    }
+
+    #[test]
+    fn flushes_swapped_raw_colors() {
+        let mut data = [Rgb565::BLACK; 2 * 2];
+        let mut fbuf = FrameBuf::new(&mut data, 2, 2);
+        let red = Rgb565::RED.into_storage();
+        // The area hangs over the right edge of the buffer.
+        fbuf.lvgl_flush_raw(&LvglArea::new(1, 0, 2, 0), &[red.swap_bytes(), red], true);
+        assert_eq!(fbuf.get_color_at(Point::new(1, 0)), Rgb565::RED);
+        assert_eq!(fbuf.get_color_at(Point::new(0, 0)), Rgb565::BLACK);
+    }
+}
@@ -0,0 +1,104 @@
+//! Masking of non-rectangular displays.
+//!
+//! Round displays (e.g. the GC9A01 found in watches) only show a part of the
+//! rectangular framebuffer. With a [`Mask`] set, drawing outside of the
+//! visible area is suppressed, and the pixel iterators (like
+//! [`FrameBuf::into_iter`](crate::FrameBuf) and
+//! [`FrameBuf::interlaced`](crate::FrameBuf::interlaced)) skip the masked-off
+//! pixels, which saves bandwidth when flushing.
+//!
+//! ```rust
+//! use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+//! use embedded_graphics_framebuf::{mask::Mask, FrameBuf};
+//!
+//! let mut data = [Rgb565::BLACK; 240 * 240];
+//! let mut fbuf = FrameBuf::new(&mut data, 240, 240);
+//! fbuf.set_mask(Some(Mask::inscribed_circle(fbuf.size())));
+//! fbuf.clear(Rgb565::WHITE).unwrap();
+//! assert_eq!(fbuf.get_color_at(Point::new(0, 0)), Rgb565::BLACK);
+//! assert!(fbuf.into_iter().count() < 240 * 240);
+//! ```
+
+use embedded_graphics::{
+    prelude::{Point, Size},
+    primitives::{Circle, ContainsPoint},
+};
+
+use crate::{backends::FrameBufferBackend, FrameBuf};
+
+/// The visible area of a display.
+#[derive(Clone, Copy, Debug)]
+pub enum Mask {
+    /// Only the pixels inside of the circle are visible.
+    Circle(Circle),
+    /// Only the pixels for which the function returns `true` are visible.
+    Custom(fn(Point) -> bool),
+}
+
+impl Mask {
+    /// The largest circle fitting into a display of the given size.
+    pub fn inscribed_circle(size: Size) -> Self {
+        let diameter = size.width.min(size.height);
+        let top_left = Point::new(
+            (size.width - diameter) as i32 / 2,
+            (size.height - diameter) as i32 / 2,
+        );
+        Mask::Circle(Circle::new(top_left, diameter))
+    }
+
+    /// Whether `p` (in framebuffer coordinates) is visible.
+    pub fn contains(&self, p: Point) -> bool {
+        match self {
+            Mask::Circle(circle) => circle.contains(p),
+            Mask::Custom(f) => f(p),
+        }
+    }
+}
+
+impl<C, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
+    /// Set the visible area of the framebuffer, `None` meaning all of it.
+    pub fn set_mask(&mut self, mask: Option<Mask>) {
+        self.mask = mask;
+    }
+
+    /// Get the visible area of the framebuffer.
+    pub fn mask(&self) -> Option<Mask> {
+        self.mask
+    }
+
+    /// Whether `p` is visible according to the mask.
+    pub fn is_visible(&self, p: Point) -> bool {
+        self.mask.is_none_or(|mask| mask.contains(p))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::{
+        draw_target::DrawTarget, mock_display::MockDisplay, pixelcolor::BinaryColor,
+    };
+
+    #[test]
+    fn suppresses_masked_pixels() {
+        let mut data = [BinaryColor::Off; 5 * 5];
+        let mut fbuf = FrameBuf::new(&mut data, 5, 5);
+        fbuf.set_mask(Some(Mask::inscribed_circle(fbuf.size())));
+        fbuf.clear(BinaryColor::On).unwrap();
+        fbuf.set_mask(None);
+
+        let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+        display.draw_iter(&fbuf).unwrap();
+        display.assert_pattern(&[".###.", "#####", "#####", "#####", ".###."]);
+    }
+
+    #[test]
+    fn skips_masked_pixels_when_flushing() {
+        let mut data = [BinaryColor::On; 4 * 3];
+        let mut fbuf = FrameBuf::new(&mut data, 4, 3);
+        fbuf.set_mask(Some(Mask::Custom(|p| p.x != 1)));
+        assert_eq!(fbuf.into_iter().count(), 9);
+        assert_eq!(fbuf.interlaced().count(), 9);
+        assert!(fbuf.interlaced().all(|p| p.0.x != 1));
+    }
+}
@@ -0,0 +1,195 @@
+//! Flushing to Sharp Memory LCDs (LS013B7DH03, LS027B7DH01, ...).
+//!
+//! These panels are written line by line. A transfer starts with a command
+//! byte, followed by each line as its 1 based address, the packed pixels and
+//! a dummy byte, and ends with another dummy byte. The panel sends addresses
+//! least significant bit first, so [`FrameBuf::memory_lcd_update`] yields the
+//! bytes bit-reversed where needed, to be written to an SPI bus in its
+//! default MSB first mode.
+//!
+//! A set bit is a white pixel, i.e. [`BinaryColor::On`] is white. The backend
+//! is a [`PackedBinaryBuffer`], so no pixels have to be repacked.
+//!
+//! ```rust
+//! use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+//! use embedded_graphics_framebuf::{
+//!     backends::{BitOrder, PackedBinaryBuffer},
+//!     FrameBuf,
+//! };
+//!
+//! let mut bytes = [0u8; 144 * 168 / 8];
+//! let mut fbuf = FrameBuf::new(
+//!     PackedBinaryBuffer::new(&mut bytes, 144, BitOrder::MsbFirst),
+//!     144,
+//!     168,
+//! );
+//! fbuf.take_dirty_area();
+//! fbuf.set_color_at(Point::new(0, 2), BinaryColor::On);
+//!
+//! let mut vcom = false;
+//! let update: Vec<u8> = fbuf.memory_lcd_update(vcom).collect();
+//! vcom = !vcom;
+//! // spi.write(&update)?;
+//! assert_eq!(update.len(), 1 + (1 + 18 + 1) + 1);
+//! assert_eq!(update[..3], [0x80, 0b1100_0000, 0b1000_0000]);
+//! ```
+
+use core::ops::Range;
+
+use embedded_graphics::pixelcolor::BinaryColor;
+
+use crate::{
+    backends::{BitOrder, PackedBinaryBuffer},
+    FrameBuf,
+};
+
+/// The write command (M0), bit-reversed.
+const WRITE: u8 = 0x80;
+/// The VCOM bit (M1), bit-reversed.
+const VCOM: u8 = 0x40;
+
+impl<D: AsRef<[u8]> + AsMut<[u8]>> FrameBuf<BinaryColor, PackedBinaryBuffer<D>> {
+    /// Yield the bytes of a transfer updating the lines of the dirty area, and
+    /// mark the framebuffer as clean.
+    ///
+    /// `vcom` is the state of the VCOM bit of the command, which has to be
+    /// toggled regularly (about once per second) unless the panel is driven
+    /// by the EXTCOMIN pin. Yields nothing if there are no dirty lines.
+    ///
+    /// # Panic
+    /// Panics if the framebuffer has more than 255 lines.
+    pub fn memory_lcd_update(&mut self, vcom: bool) -> MemoryLcdUpdate<'_> {
+        assert!(
+            self.height() <= 255,
+            "Memory LCDs have at most 255 lines, not {}",
+            self.height()
+        );
+        let lines = self.take_dirty_area().map_or(0..0, |area| {
+            let rows = area.rows();
+            rows.start as usize..rows.end as usize
+        });
+        MemoryLcdUpdate::new(
+            self.data.as_bytes(),
+            self.width().div_ceil(8),
+            self.data.bit_order(),
+            lines,
+            vcom,
+        )
+    }
+}
+
+/// Iterator over the bytes of a Memory LCD transfer, see
+/// [`FrameBuf::memory_lcd_update`].
+pub struct MemoryLcdUpdate<'a> {
+    bytes: &'a [u8],
+    line_bytes: usize,
+    bit_order: BitOrder,
+    lines: Range<usize>,
+    command: u8,
+    index: usize,
+    len: usize,
+}
+
+impl<'a> MemoryLcdUpdate<'a> {
+    fn new(
+        bytes: &'a [u8],
+        line_bytes: usize,
+        bit_order: BitOrder,
+        lines: Range<usize>,
+        vcom: bool,
+    ) -> Self {
+        let len = match lines.len() {
+            0 => 0,
+            n => 1 + n * (line_bytes + 2) + 1,
+        };
+        Self {
+            bytes,
+            line_bytes,
+            bit_order,
+            lines,
+            command: if vcom { WRITE | VCOM } else { WRITE },
+            index: 0,
+            len,
+        }
+    }
+
+    fn byte(&self, index: usize) -> u8 {
+        if index == 0 {
+            return self.command;
+        } else if index == self.len - 1 {
+            return 0;
+        }
+        let line = self.lines.start + (index - 1) / (self.line_bytes + 2);
+        match (index - 1) % (self.line_bytes + 2) {
+            0 => (line as u8 + 1).reverse_bits(),
+            column if column <= self.line_bytes => {
+                let byte = self.bytes[line * self.line_bytes + column - 1];
+                match self.bit_order {
+                    BitOrder::MsbFirst => byte,
+                    BitOrder::LsbFirst => byte.reverse_bits(),
+                }
+            }
+            _ => 0,
+        }
+    }
+}
+
+impl Iterator for MemoryLcdUpdate<'_> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.index >= self.len {
+            return None;
+        }
+        self.index += 1;
+        Some(self.byte(self.index - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len - self.index;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for MemoryLcdUpdate<'_> {}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use embedded_graphics::prelude::Point;
+    use std::vec::Vec;
+
+    #[test]
+    fn yields_dirty_lines() {
+        let mut bytes = [0u8; 2 * 4];
+        let mut fbuf = FrameBuf::new(
+            PackedBinaryBuffer::new(&mut bytes, 10, BitOrder::LsbFirst),
+            10,
+            4,
+        );
+        fbuf.take_dirty_area();
+        assert_eq!(fbuf.memory_lcd_update(false).count(), 0);
+
+        fbuf.set_color_at(Point::new(0, 1), BinaryColor::On);
+        fbuf.set_color_at(Point::new(9, 2), BinaryColor::On);
+        let update: Vec<_> = fbuf.memory_lcd_update(true).collect();
+        assert_eq!(
+            update,
+            [
+                0xC0,
+                0b0100_0000,
+                0b1000_0000,
+                0,
+                0,
+                0b1100_0000,
+                0,
+                0b0100_0000,
+                0,
+                0
+            ]
+        );
+        assert_eq!(fbuf.memory_lcd_update(true).len(), 0);
+    }
+}
@@ -0,0 +1,260 @@
+//! Pattern fills.
+//!
+//! Besides tiling arbitrary textures with [`FrameBuf::fill_pattern`], a set of
+//! built-in [`Hatch`] patterns can simulate shades of gray on monochrome
+//! displays (SSD1306, e-paper) with [`FrameBuf::fill_hatch`].
+
+use embedded_graphics::{
+    geometry::Dimensions,
+    prelude::{PixelColor, Point},
+    primitives::{PointsIter, Rectangle},
+};
+
+use crate::{backends::FrameBufferBackend, layout::Layout, FrameBuf};
+
+/// Built-in hatch patterns, repeating every 4x4 pixels at most.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Hatch {
+    /// Every fourth pixel set, "25% gray"
+    Gray25,
+    /// Checkerboard, "50% gray"
+    Gray50,
+    /// Every fourth pixel unset, "75% gray"
+    Gray75,
+    /// Every other row set
+    Horizontal,
+    /// Every other column set
+    Vertical,
+    /// Diagonal lines from bottom left to top right
+    Diagonal,
+}
+
+impl Hatch {
+    /// Rows of the 4x4 pattern, bit 0 being the leftmost pixel.
+    const fn rows(self) -> [u8; 4] {
+        match self {
+            Hatch::Gray25 => [0b0101, 0b0000, 0b0101, 0b0000],
+            Hatch::Gray50 => [0b0101, 0b1010, 0b0101, 0b1010],
+            Hatch::Gray75 => [0b1111, 0b1010, 0b1111, 0b1010],
+            Hatch::Horizontal => [0b1111, 0b0000, 0b1111, 0b0000],
+            Hatch::Vertical => [0b0101, 0b0101, 0b0101, 0b0101],
+            Hatch::Diagonal => [0b1000, 0b0100, 0b0010, 0b0001],
+        }
+    }
+
+    /// Whether the pattern is set at `p` in framebuffer coordinates.
+    pub fn is_set(self, p: Point) -> bool {
+        let row = self.rows()[p.y.rem_euclid(4) as usize];
+        row & (1 << p.x.rem_euclid(4)) != 0
+    }
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
+    /// Fill `area` with a [`Hatch`] pattern, using `set` for the set and
+    /// `unset` for the unset pixels of the pattern.
+    ///
+    /// Like [`FrameBuf::fill_pattern`] the pattern is aligned to the top left
+    /// corner of the framebuffer. Pixels hidden by the [mask](crate::mask) are
+    /// left unchanged.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::BinaryColor, prelude::*, primitives::Rectangle};
+    /// use embedded_graphics_framebuf::{pattern::Hatch, FrameBuf};
+    ///
+    /// let mut data = [BinaryColor::Off; 128 * 64];
+    /// let mut fbuf = FrameBuf::new(&mut data, 128, 64);
+    /// // A "disabled" looking panel
+    /// let panel = Rectangle::new(Point::new(10, 10), Size::new(60, 20));
+    /// fbuf.fill_hatch(&panel, Hatch::Gray50, BinaryColor::On, BinaryColor::Off);
+    /// ```
+    pub fn fill_hatch(&mut self, area: &Rectangle, hatch: Hatch, set: C, unset: C) {
+        let area = area.intersection(&self.bounding_box());
+        for p in area.points() {
+            if !self.is_visible(p) {
+                continue;
+            }
+            let color = if hatch.is_set(p) { set } else { unset };
+            let index = self.point_to_index(p);
+            self.data.set(index, color);
+        }
+        self.mark_dirty(area);
+    }
+
+    /// Fill `area` by tiling the content of `tile`.
+    ///
+    /// The tiles are aligned to the top left corner of the framebuffer, so
+    /// adjacent fills continue the pattern seamlessly. `area` is clipped to
+    /// the framebuffer, and pixels hidden by the [mask](crate::mask) are left
+    /// unchanged.
+    ///
+    /// Whole rows of the tile are copied at once if both framebuffers store
+    /// their rows contiguously, see [`FrameBuf::blit`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::Rgb565, prelude::*, primitives::Rectangle};
+    /// use embedded_graphics_framebuf::FrameBuf;
+    ///
+    /// let mut tile_data = [Rgb565::BLUE, Rgb565::WHITE, Rgb565::WHITE, Rgb565::BLUE];
+    /// let tile = FrameBuf::new(&mut tile_data, 2, 2);
+    ///
+    /// let mut data = [Rgb565::BLACK; 240 * 135];
+    /// let mut fbuf = FrameBuf::new(&mut data, 240, 135);
+    /// fbuf.fill_pattern(&Rectangle::new(Point::new(10, 10), Size::new(100, 50)), &tile);
+    /// assert_eq!(fbuf.get_color_at(Point::new(11, 10)), Rgb565::WHITE);
+    /// ```
+    pub fn fill_pattern<T>(&mut self, area: &Rectangle, tile: &FrameBuf<C, T>)
+    where
+        T: FrameBufferBackend<Color = C>,
+    {
+        let area = area.intersection(&self.bounding_box());
+        if tile.width() == 0 || tile.height() == 0 || area.is_zero_sized() {
+            return;
+        }
+        let x_start = area.top_left.x as usize;
+        let x_end = x_start + area.size.width as usize;
+        let rows_contiguous = self.mask.is_none()
+            && self.layout == Layout::RowMajor
+            && tile.layout == Layout::RowMajor;
+        match (tile.data.as_contiguous(), self.data.as_contiguous_mut()) {
+            (Some(tile_pixels), Some(pixels)) if rows_contiguous => {
+                for y in area.rows() {
+                    let from = tile.point_to_index(Point::new(0, y % tile.height() as i32));
+                    let tile_row = &tile_pixels[from..from + tile.width()];
+                    let mut x = x_start;
+                    while x < x_end {
+                        let tile_x = x % tile.width();
+                        let len = (tile.width() - tile_x).min(x_end - x);
+                        let to =
+                            self.layout
+                                .index(Point::new(x as i32, y), self.stride, self.height);
+                        pixels[to..to + len].copy_from_slice(&tile_row[tile_x..tile_x + len]);
+                        x += len;
+                    }
+                }
+            }
+            _ => {
+                for y in area.rows() {
+                    let tile_y = (y as usize % tile.height()) as i32;
+                    for x in x_start..x_end {
+                        let p = Point::new(x as i32, y);
+                        if !self.is_visible(p) {
+                            continue;
+                        }
+                        let tile_x = (x % tile.width()) as i32;
+                        let color = tile.get_color_at(Point::new(tile_x, tile_y));
+                        let index = self.point_to_index(p);
+                        self.data.set(index, color);
+                    }
+                }
+            }
+        }
+        self.mark_dirty(area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mask::Mask;
+    use embedded_graphics::{
+        draw_target::DrawTarget,
+        mock_display::MockDisplay,
+        pixelcolor::{BinaryColor, Gray8},
+        prelude::{GrayColor, Size},
+    };
+
+    #[test]
+    fn tiles_pattern() {
+        let mut tile_data = [
+            BinaryColor::On,
+            BinaryColor::Off,
+            BinaryColor::Off,
+            BinaryColor::Off,
+            BinaryColor::Off,
+            BinaryColor::On,
+        ];
+        let tile = FrameBuf::new(&mut tile_data, 3, 2);
+
+        let mut data = [BinaryColor::Off; 8 * 5];
+        let mut fbuf = FrameBuf::new(&mut data, 8, 5);
+        fbuf.take_dirty_area();
+        fbuf.fill_pattern(&Rectangle::new(Point::new(1, 1), Size::new(10, 3)), &tile);
+        assert_eq!(
+            fbuf.take_dirty_area(),
+            Some(Rectangle::new(Point::new(1, 1), Size::new(7, 3)))
+        );
+
+        let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+        display.draw_iter(&fbuf).unwrap();
+        display.assert_pattern(&["........", "..#..#..", "...#..#.", "..#..#..", "........"]);
+    }
+
+    #[test]
+    fn tiles_pattern_per_pixel() {
+        let mut tile_data = [Gray8::new(1), Gray8::new(2), Gray8::new(3)];
+        let tile = FrameBuf::new(&mut tile_data, 3, 1);
+        let mut data = [Gray8::BLACK; 5 * 2];
+        let mut fbuf = FrameBuf::new(&mut data, 5, 2);
+        fbuf.set_layout(Layout::ColumnMajor);
+        fbuf.fill_pattern(&Rectangle::new(Point::new(1, 1), Size::new(4, 1)), &tile);
+        let row: [Gray8; 5] = core::array::from_fn(|x| fbuf.get_color_at(Point::new(x as i32, 1)));
+        assert_eq!(row, [0, 2, 3, 1, 2].map(Gray8::new));
+    }
+
+    #[test]
+    fn fills_hatch() {
+        let mut data = [BinaryColor::Off; 6 * 4];
+        let mut fbuf = FrameBuf::new(&mut data, 6, 4);
+        fbuf.fill_hatch(
+            &Rectangle::new(Point::new(1, 0), Size::new(4, 4)),
+            Hatch::Gray50,
+            BinaryColor::On,
+            BinaryColor::Off,
+        );
+        fbuf.fill_hatch(
+            &Rectangle::new(Point::new(5, 0), Size::new(1, 4)),
+            Hatch::Diagonal,
+            BinaryColor::On,
+            BinaryColor::Off,
+        );
+
+        let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+        display.draw_iter(&fbuf).unwrap();
+        display.assert_pattern(&["..#.#.", ".#.#..", "..#.##", ".#.#.."]);
+    }
+
+    #[test]
+    fn respects_mask() {
+        let mut tile_data = [BinaryColor::On];
+        let tile = FrameBuf::new(&mut tile_data, 1, 1);
+        let mut data = [BinaryColor::Off; 4 * 2];
+        let mut fbuf = FrameBuf::new(&mut data, 4, 2);
+        fbuf.set_mask(Some(Mask::Custom(|p| p.x != 1)));
+        fbuf.fill_pattern(&Rectangle::new(Point::zero(), Size::new(4, 1)), &tile);
+        fbuf.fill_hatch(
+            &Rectangle::new(Point::new(0, 1), Size::new(4, 1)),
+            Hatch::Horizontal,
+            BinaryColor::Off,
+            BinaryColor::On,
+        );
+        fbuf.set_mask(None);
+
+        let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+        display.draw_iter(&fbuf).unwrap();
+        display.assert_pattern(&["#.##", "#.##"]);
+    }
+
+    #[test]
+    fn hatch_repeats_for_negative_coordinates() {
+        for hatch in [Hatch::Gray25, Hatch::Gray75, Hatch::Diagonal] {
+            assert_eq!(
+                hatch.is_set(Point::new(-1, -3)),
+                hatch.is_set(Point::new(3, 1))
+            );
+        }
+        assert!(Hatch::Horizontal.is_set(Point::new(3, 2)));
+        assert!(!Hatch::Vertical.is_set(Point::new(3, 2)));
+    }
+}
@@ -0,0 +1,210 @@
+//! Reusable off-screen surfaces.
+//!
+//! Widgets often need a small scratch framebuffer, e.g. to render a sprite
+//! before blending it or to compose a text label before scrolling it. A
+//! [`SurfacePool`] carves several equally sized surfaces out of one buffer, so
+//! that not every widget needs its own static buffer and no allocator is
+//! needed.
+
+use core::{cell::Cell, marker::PhantomData, ops::Range, ptr::NonNull};
+
+use embedded_graphics::prelude::PixelColor;
+
+use crate::{
+    backends::{ContiguousBackend, FrameBufferBackend},
+    FrameBuf,
+};
+
+/// A pool of equally sized framebuffers sharing one buffer.
+///
+/// Surfaces are checked out with [`SurfacePool::checkout`] and returned to the
+/// pool when the [`Surface`] is dropped. At most 32 surfaces are supported.
+///
+/// # Example
+/// ```rust
+/// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+/// use embedded_graphics_framebuf::pool::SurfacePool;
+///
+/// static mut SCRATCH: [Rgb565; 3 * 32 * 16] = [Rgb565::BLACK; 3 * 32 * 16];
+///
+/// let pool = SurfacePool::new(unsafe { &mut *core::ptr::addr_of_mut!(SCRATCH) }, 32, 16);
+/// assert_eq!(pool.capacity(), 3);
+/// {
+///     let mut icon = pool.checkout().unwrap();
+///     icon.clear(Rgb565::RED).unwrap();
+///     assert_eq!(pool.available(), 2);
+/// }
+/// assert_eq!(pool.available(), 3);
+/// ```
+pub struct SurfacePool<'a, C> {
+    data: NonNull<C>,
+    width: usize,
+    height: usize,
+    capacity: usize,
+    in_use: Cell<u32>,
+    _data: PhantomData<&'a mut [C]>,
+}
+
+impl<'a, C: PixelColor> SurfacePool<'a, C> {
+    /// Split `data` into as many surfaces of `width` x `height` as fit (but at
+    /// most 32). Left over elements at the end of `data` are unused.
+    pub fn new(data: &'a mut [C], width: usize, height: usize) -> Self {
+        let capacity = match width * height {
+            0 => 0,
+            len => (data.len() / len).min(32),
+        };
+        Self {
+            data: NonNull::from(data).cast(),
+            width,
+            height,
+            capacity,
+            in_use: Cell::new(0),
+            _data: PhantomData,
+        }
+    }
+
+    /// The total number of surfaces in the pool.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of surfaces that are currently not checked out.
+    pub fn available(&self) -> usize {
+        self.capacity - self.in_use.get().count_ones() as usize
+    }
+
+    /// Take a surface out of the pool, or `None` if all of them are in use.
+    ///
+    /// The content of the surface is whatever the previous user left in it.
+    pub fn checkout(&self) -> Option<Surface<'_, C>> {
+        let in_use = self.in_use.get();
+        let slot = (0..self.capacity).find(|slot| in_use & (1 << slot) == 0)?;
+        self.in_use.set(in_use | (1 << slot));
+
+        let len = self.width * self.height;
+        // SAFETY: Slots don't overlap and each slot is only handed out once
+        // until its surface is dropped, so this is the only reference to it.
+        let data =
+            unsafe { core::slice::from_raw_parts_mut(self.data.as_ptr().add(slot * len), len) };
+        let slot = PoolSlot {
+            data,
+            in_use: &self.in_use,
+            slot,
+        };
+        Some(FrameBuf::new(slot, self.width, self.height))
+    }
+}
+
+/// A framebuffer checked out of a [`SurfacePool`].
+///
+/// Its backend returns the slot to the pool when it is dropped.
+pub type Surface<'p, C> = FrameBuf<C, PoolSlot<'p, C>>;
+
+/// The backend of a [`Surface`], owning a slot of a [`SurfacePool`] until it
+/// is dropped.
+pub struct PoolSlot<'p, C> {
+    data: &'p mut [C],
+    in_use: &'p Cell<u32>,
+    slot: usize,
+}
+
+impl<C: PixelColor> FrameBufferBackend for PoolSlot<'_, C> {
+    type Color = C;
+    fn set(&mut self, index: usize, color: C) {
+        self.data[index] = color
+    }
+
+    fn get(&self, index: usize) -> C {
+        self.data[index]
+    }
+
+    fn nr_elements(&self) -> usize {
+        self.data.len()
+    }
+
+    fn fill(&mut self, color: C) {
+        self.data.fill(color)
+    }
+
+    fn fill_range(&mut self, range: Range<usize>, color: C) {
+        self.data[range].fill(color)
+    }
+
+    fn as_contiguous(&self) -> Option<&[C]> {
+        Some(self.data)
+    }
+
+    fn as_contiguous_mut(&mut self) -> Option<&mut [C]> {
+        Some(self.data)
+    }
+}
+
+impl<C: PixelColor> ContiguousBackend for PoolSlot<'_, C> {
+    fn as_slice(&self) -> &[C] {
+        self.data
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [C] {
+        self.data
+    }
+}
+
+impl<C> Drop for PoolSlot<'_, C> {
+    fn drop(&mut self) {
+        self.in_use.set(self.in_use.get() & !(1 << self.slot));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::{pixelcolor::Gray8, prelude::*};
+
+    #[test]
+    fn checks_out_and_returns_surfaces() {
+        let mut data = [Gray8::BLACK; 2 * 4 + 3];
+        let pool = SurfacePool::new(&mut data, 2, 2);
+        assert_eq!(pool.capacity(), 2);
+
+        let mut a = pool.checkout().unwrap();
+        let mut b = pool.checkout().unwrap();
+        assert!(pool.checkout().is_none());
+        a.clear(Gray8::new(1)).unwrap();
+        b.clear(Gray8::new(2)).unwrap();
+        assert_eq!(a.get_color_at(Point::new(1, 1)), Gray8::new(1));
+        drop(a);
+
+        assert_eq!(pool.available(), 1);
+        let c = pool.checkout().unwrap();
+        assert_eq!(c.get_color_at(Point::new(0, 0)), Gray8::new(1));
+        drop((b, c));
+        assert_eq!(data, [1, 1, 1, 1, 2, 2, 2, 2, 0, 0, 0].map(Gray8::new));
+    }
+
+    #[test]
+    fn swapped_surfaces_keep_their_slots() {
+        let mut data = [Gray8::BLACK; 2 * 4];
+        let pool = SurfacePool::new(&mut data, 2, 2);
+        let mut a = pool.checkout().unwrap();
+        let b = pool.checkout().unwrap();
+        // The swapped out framebuffer still owns the first slot
+        let old = core::mem::replace(&mut a, b);
+        drop(a);
+        assert_eq!(pool.available(), 1);
+
+        let mut c = pool.checkout().unwrap();
+        assert!(pool.checkout().is_none());
+        c.clear(Gray8::new(2)).unwrap();
+        assert_eq!(old.get_color_at(Point::new(0, 0)), Gray8::BLACK);
+        drop(old);
+        assert_eq!(pool.available(), 1);
+    }
+
+    #[test]
+    fn empty_surfaces() {
+        let mut data = [Gray8::BLACK; 4];
+        assert_eq!(SurfacePool::new(&mut data, 0, 2).capacity(), 0);
+        assert_eq!(SurfacePool::new(&mut data, 5, 1).capacity(), 0);
+        assert!(SurfacePool::new(&mut data, 5, 1).checkout().is_none());
+    }
+}
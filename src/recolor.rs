@@ -0,0 +1,156 @@
+//! Drawing content of another color type into a [`FrameBuf`].
+//!
+//! Icons are often [`BinaryColor`](embedded_graphics::pixelcolor::BinaryColor)
+//! images and plots are easiest to draw in
+//! [`Gray8`](embedded_graphics::pixelcolor::Gray8), while the framebuffer of a
+//! color display is e.g. [`Rgb565`](embedded_graphics::pixelcolor::Rgb565).
+//! [`FrameBuf::recolored`] returns a [`DrawTarget`] of the other color type
+//! which maps each color with a function, e.g. to an on and an off color or
+//! through a false-color lookup table:
+//!
+//! ```rust
+//! use embedded_graphics::{
+//!     pixelcolor::{BinaryColor, Gray8, Rgb565},
+//!     prelude::*,
+//!     primitives::{PrimitiveStyle, Rectangle},
+//! };
+//! use embedded_graphics_framebuf::FrameBuf;
+//!
+//! let mut data = [Rgb565::BLACK; 12 * 11];
+//! let mut fbuf = FrameBuf::new(&mut data, 12, 11);
+//!
+//! let (on, off) = (Rgb565::YELLOW, Rgb565::BLUE);
+//! Rectangle::new(Point::zero(), Size::new(4, 4))
+//!     .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
+//!     .draw(&mut fbuf.recolored(|c: BinaryColor| if c.is_on() { on } else { off }))
+//!     .unwrap();
+//! assert_eq!(fbuf.get_color_at(Point::new(1, 1)), Rgb565::YELLOW);
+//!
+//! let heat = |c: Gray8| Rgb565::new(c.luma() >> 3, 0, (255 - c.luma()) >> 3);
+//! fbuf.recolored(heat).clear(Gray8::WHITE).unwrap();
+//! assert_eq!(fbuf.get_color_at(Point::new(1, 1)), Rgb565::RED);
+//! ```
+
+use core::marker::PhantomData;
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::OriginDimensions,
+    prelude::{PixelColor, Size},
+    primitives::Rectangle,
+    Pixel,
+};
+
+use crate::{backends::FrameBufferBackend, FrameBuf};
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
+    /// Get a [`DrawTarget`] with the color type `S`, mapping colors to the
+    /// color type of the framebuffer with `map`. See the
+    /// [module documentation](crate::recolor).
+    ///
+    /// Drawing behaves like drawing into the framebuffer directly, i.e. it is
+    /// clipped, respects the [mask](crate::mask) and marks the drawn area
+    /// dirty.
+    pub fn recolored<S, F>(&mut self, map: F) -> Recolored<'_, C, B, S, F>
+    where
+        S: PixelColor,
+        F: FnMut(S) -> C,
+    {
+        Recolored {
+            fbuf: self,
+            map,
+            color: PhantomData,
+        }
+    }
+}
+
+/// A [`DrawTarget`] converting colors into those of a framebuffer, see
+/// [`FrameBuf::recolored`].
+pub struct Recolored<'a, C: PixelColor, B: FrameBufferBackend<Color = C>, S, F> {
+    fbuf: &'a mut FrameBuf<C, B>,
+    map: F,
+    color: PhantomData<S>,
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>, S, F> OriginDimensions
+    for Recolored<'_, C, B, S, F>
+{
+    fn size(&self) -> Size {
+        self.fbuf.size()
+    }
+}
+
+impl<C, B, S, F> DrawTarget for Recolored<'_, C, B, S, F>
+where
+    C: PixelColor,
+    B: FrameBufferBackend<Color = C>,
+    S: PixelColor,
+    F: FnMut(S) -> C,
+{
+    type Color = S;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let map = &mut self.map;
+        self.fbuf.draw_iter(
+            pixels
+                .into_iter()
+                .map(|Pixel(p, color)| Pixel(p, map(color))),
+        )
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        self.fbuf
+            .fill_contiguous(area, colors.into_iter().map(&mut self.map))
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.fbuf.fill_solid(area, (self.map)(color))
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.fbuf.clear((self.map)(color))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::{
+        pixelcolor::{BinaryColor, Gray8},
+        prelude::{GrayColor, Point},
+    };
+
+    #[test]
+    fn maps_colors() {
+        let lut = [Gray8::new(10), Gray8::new(20)];
+        let mut data = [Gray8::BLACK; 3 * 2];
+        let mut fbuf = FrameBuf::new(&mut data, 3, 2);
+        fbuf.take_dirty_area();
+        let mut target = fbuf.recolored(|c: BinaryColor| lut[c.is_on() as usize]);
+        assert_eq!(target.size(), Size::new(3, 2));
+        target
+            .fill_solid(
+                &Rectangle::new(Point::new(1, 0), Size::new(4, 1)),
+                BinaryColor::On,
+            )
+            .unwrap();
+        target
+            .draw_iter([
+                Pixel(Point::new(0, 1), BinaryColor::Off),
+                Pixel(Point::new(9, 1), BinaryColor::On),
+            ])
+            .unwrap();
+        assert_eq!(
+            fbuf.take_dirty_area(),
+            Some(Rectangle::new(Point::zero(), Size::new(3, 2)))
+        );
+        assert_eq!(data, [0, 20, 20, 10, 0, 0].map(Gray8::new));
+    }
+}
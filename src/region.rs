@@ -0,0 +1,222 @@
+//! Tracked drawing into a region of a [`FrameBuf`].
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Dimensions, OriginDimensions},
+    prelude::{PixelColor, Point, Size},
+    primitives::Rectangle,
+    Pixel,
+};
+
+use crate::{backends::FrameBufferBackend, FrameBuf, ReadableDrawTarget};
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
+    /// Get a [`DrawTarget`] for the region `area` of the framebuffer.
+    ///
+    /// Coordinates of the returned target are relative to the top left corner
+    /// of `area`, and drawing outside of `area` (or hidden by the
+    /// [mask](crate::mask)) is suppressed, so a widget can draw in its own
+    /// coordinate space without knowing its position on the screen. When the
+    /// guard is dropped, `area` is [marked dirty](crate::dirty), so widgets
+    /// can update their own area without caring about dirty tracking.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{
+    ///     pixelcolor::BinaryColor,
+    ///     prelude::*,
+    ///     primitives::{PrimitiveStyle, Rectangle},
+    /// };
+    /// use embedded_graphics_framebuf::FrameBuf;
+    ///
+    /// let mut data = [BinaryColor::Off; 12 * 11];
+    /// let mut fbuf = FrameBuf::new(&mut data, 12, 11);
+    /// fbuf.take_dirty_area();
+    ///
+    /// let area = Rectangle::new(Point::new(4, 4), Size::new(4, 4));
+    /// Rectangle::new(Point::zero(), Size::new(10, 10))
+    ///     .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
+    ///     .draw(&mut fbuf.edit_region(area))
+    ///     .unwrap();
+    /// assert_eq!(fbuf.take_dirty_area(), Some(area));
+    /// assert_eq!(fbuf.get_color_at(Point::new(8, 8)), BinaryColor::Off);
+    /// ```
+    pub fn edit_region(&mut self, area: Rectangle) -> RegionGuard<'_, C, B> {
+        let clip = area.intersection(&self.bounding_box());
+        RegionGuard {
+            fbuf: self,
+            area,
+            clip,
+        }
+    }
+
+    /// Get a window into the region `area` of the framebuffer, with its own
+    /// origin at the top left corner of `area`.
+    ///
+    /// The same as [`FrameBuf::edit_region`].
+    pub fn window(&mut self, area: Rectangle) -> FrameBufWindow<'_, C, B> {
+        self.edit_region(area)
+    }
+}
+
+/// A window into a framebuffer, see [`FrameBuf::window`].
+pub type FrameBufWindow<'a, C, B> = RegionGuard<'a, C, B>;
+
+/// A [`DrawTarget`] for a region of a framebuffer, see
+/// [`FrameBuf::edit_region`].
+pub struct RegionGuard<'a, C: PixelColor, B: FrameBufferBackend<Color = C>> {
+    fbuf: &'a mut FrameBuf<C, B>,
+    area: Rectangle,
+    /// The part of `area` that can be drawn to
+    clip: Rectangle,
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> RegionGuard<'_, C, B> {
+    /// The region in framebuffer coordinates.
+    pub fn area(&self) -> Rectangle {
+        self.area
+    }
+
+    /// Get a [`DrawTarget`] for the region `area` of this region, e.g. for a
+    /// widget nested in another widget.
+    ///
+    /// `area` is relative to this region, and drawing is clipped to both
+    /// regions.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::BinaryColor, prelude::*, primitives::Rectangle};
+    /// use embedded_graphics_framebuf::FrameBuf;
+    ///
+    /// let mut data = [BinaryColor::Off; 12 * 11];
+    /// let mut fbuf = FrameBuf::new(&mut data, 12, 11);
+    /// let mut panel = fbuf.edit_region(Rectangle::new(Point::new(4, 4), Size::new(6, 6)));
+    /// let mut button = panel.edit_region(Rectangle::new(Point::new(2, 2), Size::new(10, 2)));
+    /// button.clear(BinaryColor::On).unwrap();
+    /// assert_eq!(button.area(), Rectangle::new(Point::new(6, 6), Size::new(10, 2)));
+    /// drop(button);
+    /// drop(panel);
+    /// assert_eq!(fbuf.get_color_at(Point::new(9, 6)), BinaryColor::On);
+    /// assert_eq!(fbuf.get_color_at(Point::new(10, 6)), BinaryColor::Off);
+    /// ```
+    pub fn edit_region(&mut self, area: Rectangle) -> RegionGuard<'_, C, B> {
+        let area = Rectangle::new(self.area.top_left + area.top_left, area.size);
+        let clip = area.intersection(&self.clip);
+        RegionGuard {
+            fbuf: &mut *self.fbuf,
+            area,
+            clip,
+        }
+    }
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> OriginDimensions for RegionGuard<'_, C, B> {
+    fn size(&self) -> Size {
+        self.area.size
+    }
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> DrawTarget for RegionGuard<'_, C, B> {
+    type Color = C;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(p, color) in pixels {
+            let p = p + self.area.top_left;
+            if self.clip.contains(p) && self.fbuf.is_visible(p) {
+                let index = self.fbuf.point_to_index(p);
+                self.fbuf.data.set(index, color);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> ReadableDrawTarget for RegionGuard<'_, C, B> {
+    fn get_color_at(&self, p: Point) -> C {
+        self.fbuf.get_color_at(p + self.area.top_left)
+    }
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> Drop for RegionGuard<'_, C, B> {
+    fn drop(&mut self) {
+        self.fbuf.mark_dirty(self.clip);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::{mock_display::MockDisplay, pixelcolor::BinaryColor};
+
+    #[test]
+    fn draws_translated_and_clipped() {
+        let mut data = [BinaryColor::Off; 4 * 3];
+        let mut fbuf = FrameBuf::new(&mut data, 4, 3);
+        fbuf.take_dirty_area();
+        {
+            let mut region = fbuf.edit_region(Rectangle::new(Point::new(2, 1), Size::new(3, 3)));
+            assert_eq!(region.size(), Size::new(3, 3));
+            region.clear(BinaryColor::On).unwrap();
+        }
+        assert_eq!(
+            fbuf.take_dirty_area(),
+            Some(Rectangle::new(Point::new(2, 1), Size::new(2, 2)))
+        );
+
+        let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+        display.draw_iter(&fbuf).unwrap();
+        display.assert_pattern(&["....", "..##", "..##"]);
+    }
+
+    #[test]
+    fn reads_translated() {
+        let mut data = [BinaryColor::Off; 4 * 3];
+        let mut fbuf = FrameBuf::new(&mut data, 4, 3);
+        fbuf.set_color_at(Point::new(3, 2), BinaryColor::On);
+        let region = fbuf.edit_region(Rectangle::new(Point::new(2, 1), Size::new(2, 2)));
+        assert_eq!(region.get_color_at(Point::new(1, 1)), BinaryColor::On);
+        assert_eq!(region.get_color_at(Point::new(0, 1)), BinaryColor::Off);
+    }
+
+    #[test]
+    fn opens_windows() {
+        let mut data = [BinaryColor::Off; 4 * 3];
+        let mut fbuf = FrameBuf::new(&mut data, 4, 3);
+        let mut window = fbuf.window(Rectangle::new(Point::new(3, 1), Size::new(2, 2)));
+        assert_eq!(
+            window.bounding_box(),
+            Rectangle::new(Point::zero(), Size::new(2, 2))
+        );
+        window.clear(BinaryColor::On).unwrap();
+        drop(window);
+
+        let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+        display.draw_iter(&fbuf).unwrap();
+        display.assert_pattern(&["....", "...#", "...#"]);
+    }
+
+    #[test]
+    fn nests_regions() {
+        let mut data = [BinaryColor::Off; 4 * 3];
+        let mut fbuf = FrameBuf::new(&mut data, 4, 3);
+        fbuf.take_dirty_area();
+        {
+            let mut outer = fbuf.edit_region(Rectangle::new(Point::new(1, 1), Size::new(2, 2)));
+            let mut inner = outer.edit_region(Rectangle::new(Point::new(-1, 1), Size::new(3, 3)));
+            assert_eq!(inner.size(), Size::new(3, 3));
+            inner.clear(BinaryColor::On).unwrap();
+        }
+        assert_eq!(
+            fbuf.take_dirty_area(),
+            Some(Rectangle::new(Point::new(1, 1), Size::new(2, 2)))
+        );
+
+        let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+        display.draw_iter(&fbuf).unwrap();
+        display.assert_pattern(&["....", "....", ".##."]);
+    }
+}
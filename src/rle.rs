@@ -0,0 +1,257 @@
+//! Run-length encoded frames for slow links.
+//!
+//! A raw 320x240 [`Rgb565`](embedded_graphics::pixelcolor::Rgb565) frame is
+//! 150 KiB, far too much to push over a UART or BLE link to a remote display
+//! or a companion app. UI frames mostly consist of large areas of the same
+//! color, which run-length encoding compresses well. [`FrameBuf::rle`]
+//! encodes the whole frame, [`FrameBuf::rle_rows`] only some rows, e.g. those
+//! which changed since the last frame was sent (see
+//! [`RowChecksums`](crate::checksum::RowChecksums)).
+//!
+//! # Format
+//! The pixels are encoded in row major order as runs of the same color. A
+//! run is a byte with the number of pixels minus one, so up to 256 pixels,
+//! followed by the color in big endian byte order (see
+//! [`ToBytes::to_be_bytes`]). With [`FrameBuf::rle`], runs may span several
+//! rows, and decoders find the end of the frame by counting the pixels.
+//!
+//! With [`FrameBuf::rle_rows`], every row starts with its index as a big
+//! endian `u16`, followed by the runs of the row. These runs stop at the end
+//! of the row, so decoders find the end of a row by counting the pixels.
+//!
+//! ```rust
+//! use embedded_graphics::{
+//!     pixelcolor::Rgb565,
+//!     prelude::*,
+//!     primitives::{PrimitiveStyle, Rectangle},
+//! };
+//! use embedded_graphics_framebuf::{checksum::RowChecksums, FrameBuf};
+//!
+//! let mut data = [Rgb565::BLACK; 320 * 240];
+//! let mut fbuf = FrameBuf::new(&mut data, 320, 240);
+//! Rectangle::new(Point::new(0, 200), Size::new(320, 40))
+//!     .into_styled(PrimitiveStyle::with_fill(Rgb565::BLUE))
+//!     .draw(&mut fbuf)
+//!     .unwrap();
+//! let mut checksums = RowChecksums::new([0; 240]);
+//! assert_eq!(fbuf.rle().count(), 900);
+//! checksums.changed_rows(&fbuf).for_each(drop);
+//!
+//! fbuf.set_color_at(Point::new(10, 20), Rgb565::RED);
+//! let mut changed = fbuf.rle_rows(checksums.changed_rows(&fbuf));
+//! let mut message = [0; 32];
+//! let mut len = 0;
+//! for (byte, value) in message.iter_mut().zip(&mut changed) {
+//!     *byte = value;
+//!     len += 1;
+//! }
+//! assert_eq!(message[..len], [0, 20, 9, 0, 0, 0, 0xF8, 0, 255, 0, 0, 52, 0, 0]);
+//! // uart.write(&message[..len])?;
+//! ```
+
+use embedded_graphics::{
+    pixelcolor::raw::ToBytes,
+    prelude::{PixelColor, Point, Size},
+    primitives::{rectangle::Points, PointsIter, Rectangle},
+};
+
+use crate::{backends::FrameBufferBackend, FrameBuf};
+
+impl<C, B> FrameBuf<C, B>
+where
+    C: PixelColor + ToBytes,
+    C::Bytes: AsRef<[u8]>,
+    B: FrameBufferBackend<Color = C>,
+{
+    /// Iterate over the bytes of the run-length encoded frame, see the
+    /// [module documentation](crate::rle).
+    pub fn rle(&self) -> RleBytes<'_, C, B> {
+        RleBytes(Runs::new(self, Rectangle::new(Point::zero(), self.size())))
+    }
+
+    /// Iterate over the bytes of the run-length encoded `rows`, each prefixed
+    /// with its index, see the [module documentation](crate::rle).
+    ///
+    /// # Panic
+    /// Panics if a row is out of bounds or its index doesn't fit into a `u16`.
+    pub fn rle_rows<I: Iterator<Item = usize>>(&self, rows: I) -> RleRows<'_, C, B, I> {
+        RleRows {
+            fbuf: self,
+            rows,
+            header: [0; 2],
+            header_index: 2,
+            runs: None,
+        }
+    }
+}
+
+/// Encoder of the runs of the pixels of an area.
+struct Runs<'a, C: ToBytes, B: FrameBufferBackend<Color = C>> {
+    fbuf: &'a FrameBuf<C, B>,
+    points: Points,
+    /// The first color of the next run, if already read
+    pending: Option<C>,
+    /// The color bytes of the current run
+    color: Option<C::Bytes>,
+    byte: usize,
+}
+
+impl<'a, C, B> Runs<'a, C, B>
+where
+    C: PixelColor + ToBytes,
+    C::Bytes: AsRef<[u8]>,
+    B: FrameBufferBackend<Color = C>,
+{
+    fn new(fbuf: &'a FrameBuf<C, B>, area: Rectangle) -> Self {
+        Self {
+            fbuf,
+            points: area.points(),
+            pending: None,
+            color: None,
+            byte: 0,
+        }
+    }
+}
+
+impl<C, B> Iterator for Runs<'_, C, B>
+where
+    C: PixelColor + ToBytes,
+    C::Bytes: AsRef<[u8]>,
+    B: FrameBufferBackend<Color = C>,
+{
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if let Some(&byte) = self.color.as_ref().and_then(|c| c.as_ref().get(self.byte)) {
+            self.byte += 1;
+            return Some(byte);
+        }
+        let color = self
+            .pending
+            .take()
+            .or_else(|| self.points.next().map(|p| self.fbuf.get_color_at(p)))?;
+        let mut count = 1;
+        for p in self.points.by_ref() {
+            let next = self.fbuf.get_color_at(p);
+            if next != color {
+                self.pending = Some(next);
+                break;
+            }
+            count += 1;
+            if count == 256 {
+                break;
+            }
+        }
+        self.color = Some(color.to_be_bytes());
+        self.byte = 0;
+        Some((count - 1) as u8)
+    }
+}
+
+/// Iterator over the bytes of a run-length encoded frame, see
+/// [`FrameBuf::rle`].
+pub struct RleBytes<'a, C: ToBytes, B: FrameBufferBackend<Color = C>>(Runs<'a, C, B>);
+
+impl<C, B> Iterator for RleBytes<'_, C, B>
+where
+    C: PixelColor + ToBytes,
+    C::Bytes: AsRef<[u8]>,
+    B: FrameBufferBackend<Color = C>,
+{
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        self.0.next()
+    }
+}
+
+/// Iterator over the bytes of run-length encoded rows of a frame, see
+/// [`FrameBuf::rle_rows`].
+pub struct RleRows<'a, C: ToBytes, B: FrameBufferBackend<Color = C>, I> {
+    fbuf: &'a FrameBuf<C, B>,
+    rows: I,
+    /// Big endian index of the current row
+    header: [u8; 2],
+    /// Index of the next header byte, past the end once it was sent
+    header_index: usize,
+    runs: Option<Runs<'a, C, B>>,
+}
+
+impl<C, B, I> Iterator for RleRows<'_, C, B, I>
+where
+    C: PixelColor + ToBytes,
+    C::Bytes: AsRef<[u8]>,
+    B: FrameBufferBackend<Color = C>,
+    I: Iterator<Item = usize>,
+{
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        loop {
+            if let Some(&byte) = self.header.get(self.header_index) {
+                self.header_index += 1;
+                return Some(byte);
+            }
+            if let Some(byte) = self.runs.as_mut().and_then(Iterator::next) {
+                return Some(byte);
+            }
+            let y = self.rows.next()?;
+            assert!(y < self.fbuf.height(), "row {} out of bounds", y);
+            assert!(y <= u16::MAX as usize, "row {} doesn't fit into a u16", y);
+            self.header = (y as u16).to_be_bytes();
+            self.header_index = 0;
+            self.runs = Some(Runs::new(
+                self.fbuf,
+                Rectangle::new(
+                    Point::new(0, y as i32),
+                    Size::new(self.fbuf.width() as u32, 1),
+                ),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use embedded_graphics::{
+        pixelcolor::{Gray8, Rgb565},
+        prelude::{GrayColor, RgbColor},
+    };
+    use std::vec::Vec;
+
+    #[test]
+    fn encodes_runs() {
+        let mut data = [Gray8::BLACK; 4 * 3];
+        let mut fbuf = FrameBuf::new(&mut data, 4, 3);
+        fbuf.set_color_at(Point::new(1, 1), Gray8::WHITE);
+        assert_eq!(fbuf.rle().collect::<Vec<_>>(), [4, 0, 0, 255, 5, 0]);
+        assert_eq!(
+            fbuf.rle_rows([2, 1].into_iter()).collect::<Vec<_>>(),
+            [0, 2, 3, 0, 0, 1, 0, 0, 0, 255, 1, 0]
+        );
+        assert_eq!(fbuf.rle_rows(core::iter::empty()).next(), None);
+
+        let mut data = [Rgb565::RED; 20 * 15];
+        let fbuf = FrameBuf::new(&mut data, 20, 15);
+        assert_eq!(fbuf.rle().collect::<Vec<_>>(), [255, 0xF8, 0, 43, 0xF8, 0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn checks_rows() {
+        let mut data = [Gray8::BLACK; 4 * 3];
+        let fbuf = FrameBuf::new(&mut data, 4, 3);
+        fbuf.rle_rows([3].into_iter()).for_each(drop);
+    }
+
+    #[test]
+    #[should_panic]
+    fn checks_row_index_size() {
+        let mut data = std::vec![Gray8::BLACK; (1 << 16) + 1];
+        let fbuf = FrameBuf::new(&mut data[..], 1, (1 << 16) + 1);
+        fbuf.rle_rows([1 << 16].into_iter()).for_each(drop);
+    }
+}
@@ -0,0 +1,144 @@
+//! Raster operations for monochrome framebuffers.
+//!
+//! Instead of overwriting pixels, [`FrameBuf::raster_op`] combines the drawn
+//! colors with the pixels already in a [`BinaryColor`] framebuffer. Drawing a
+//! cursor or a rubber-band selection with [`RasterOp::Xor`] keeps it visible
+//! on any background, and drawing it again removes it without having to save
+//! the pixels below:
+//!
+//! ```rust
+//! use embedded_graphics::{
+//!     pixelcolor::BinaryColor,
+//!     prelude::*,
+//!     primitives::{PrimitiveStyle, Rectangle},
+//! };
+//! use embedded_graphics_framebuf::{rop::RasterOp, FrameBuf};
+//!
+//! let mut data = [BinaryColor::Off; 12 * 11];
+//! let mut fbuf = FrameBuf::new(&mut data, 12, 11);
+//! fbuf.set_color_at(Point::new(4, 2), BinaryColor::On);
+//!
+//! let selection = Rectangle::new(Point::new(2, 2), Size::new(6, 4))
+//!     .into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 1));
+//! selection.draw(&mut fbuf.raster_op(RasterOp::Xor)).unwrap();
+//! assert_eq!(fbuf.get_color_at(Point::new(3, 2)), BinaryColor::On);
+//! assert_eq!(fbuf.get_color_at(Point::new(4, 2)), BinaryColor::Off);
+//!
+//! selection.draw(&mut fbuf.raster_op(RasterOp::Xor)).unwrap();
+//! assert_eq!(fbuf.get_color_at(Point::new(3, 2)), BinaryColor::Off);
+//! assert_eq!(fbuf.get_color_at(Point::new(4, 2)), BinaryColor::On);
+//! ```
+//!
+//! Pixels drawn more than once by the same drawable are combined more than
+//! once, e.g. the overlapping ends of a polyline's segments cancel out with
+//! [`RasterOp::Xor`].
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Dimensions, OriginDimensions},
+    pixelcolor::BinaryColor,
+    prelude::Size,
+    Pixel,
+};
+
+use crate::{backends::FrameBufferBackend, FrameBuf};
+
+/// How a drawn color is combined with the pixel in the framebuffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RasterOp {
+    /// Pixels drawn `On` are inverted
+    Xor,
+    /// Pixels drawn `Off` are cleared
+    And,
+    /// Pixels drawn `On` are set
+    Or,
+    /// Pixels are set to the inverted drawn color
+    Not,
+}
+
+impl RasterOp {
+    /// Combine the color `src` with the pixel color `dest`.
+    pub fn apply(self, dest: BinaryColor, src: BinaryColor) -> BinaryColor {
+        let (dest, src) = (dest.is_on(), src.is_on());
+        BinaryColor::from(match self {
+            RasterOp::Xor => dest ^ src,
+            RasterOp::And => dest & src,
+            RasterOp::Or => dest | src,
+            RasterOp::Not => !src,
+        })
+    }
+}
+
+impl<B: FrameBufferBackend<Color = BinaryColor>> FrameBuf<BinaryColor, B> {
+    /// Get a [`DrawTarget`] combining drawn colors with the pixels of the
+    /// framebuffer with `op`, see the [module documentation](crate::rop).
+    ///
+    /// Drawing is clipped, respects the [mask](crate::mask) and marks the
+    /// drawn pixels dirty.
+    pub fn raster_op(&mut self, op: RasterOp) -> RasterOpTarget<'_, B> {
+        RasterOpTarget { fbuf: self, op }
+    }
+}
+
+/// A [`DrawTarget`] applying a [`RasterOp`], see [`FrameBuf::raster_op`].
+pub struct RasterOpTarget<'a, B: FrameBufferBackend<Color = BinaryColor>> {
+    fbuf: &'a mut FrameBuf<BinaryColor, B>,
+    op: RasterOp,
+}
+
+impl<B: FrameBufferBackend<Color = BinaryColor>> OriginDimensions for RasterOpTarget<'_, B> {
+    fn size(&self) -> Size {
+        self.fbuf.size()
+    }
+}
+
+impl<B: FrameBufferBackend<Color = BinaryColor>> DrawTarget for RasterOpTarget<'_, B> {
+    type Color = BinaryColor;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let bounds = self.fbuf.bounding_box();
+        for Pixel(p, color) in pixels {
+            if bounds.contains(p) && self.fbuf.is_visible(p) {
+                let dest = self.fbuf.get_color_at(p);
+                self.fbuf.set_color_at(p, self.op.apply(dest, color));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::{prelude::Point, primitives::Rectangle};
+
+    #[test]
+    fn combines_with_pixels() {
+        use BinaryColor::{Off, On};
+
+        let ops = [RasterOp::Xor, RasterOp::And, RasterOp::Or, RasterOp::Not];
+        let expected = [
+            [Off, On, On, Off],
+            [Off, Off, Off, On],
+            [Off, On, On, On],
+            [On, Off, On, Off],
+        ];
+        for (op, expected) in ops.into_iter().zip(expected) {
+            // The combinations (dest, src): (Off, Off), (Off, On), (On, Off), (On, On)
+            let mut data = [Off, Off, On, On, On];
+            let mut fbuf = FrameBuf::new(&mut data, 5, 1);
+            fbuf.take_dirty_area();
+            let area = Rectangle::new(Point::new(0, 0), Size::new(4, 1));
+            fbuf.raster_op(op)
+                .fill_contiguous(&area, [Off, On, Off, On])
+                .unwrap();
+            assert_eq!(fbuf.take_dirty_area(), Some(area));
+            assert_eq!(data[..4], expected);
+            assert_eq!(data[4], On);
+        }
+    }
+}
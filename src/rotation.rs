@@ -0,0 +1,276 @@
+//! Drawing in a logical orientation.
+//!
+//! Panels are often mounted rotated, while their scan order (and thus the
+//! framebuffer) stays fixed. [`FrameBuf::rotated`] returns a view of the
+//! framebuffer whose coordinates are in the logical orientation of the UI, and
+//! remaps them while drawing. The framebuffer keeps matching the panel's
+//! native scan order, so it can be flushed as is.
+//!
+//! Alternatively, a [`RotatedBuffer`] backend stores the pixels rotated, so
+//! the framebuffer itself has the logical orientation.
+//!
+//! For frames already drawn, [`FrameBuf::flip_horizontal`],
+//! [`FrameBuf::flip_vertical`] and [`FrameBuf::rotate_180`] change the
+//! orientation of the stored pixels in place, e.g. for displays mounted
+//! upside down or mirrored behind glass.
+//!
+//! [`RotatedBuffer`]: crate::backends::RotatedBuffer
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::OriginDimensions,
+    prelude::{PixelColor, Point, Size},
+    Pixel,
+};
+
+use crate::{backends::FrameBufferBackend, layout::Layout, FrameBuf, ReadableDrawTarget};
+
+/// Clockwise rotation of the logical picture on the panel.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Rotation {
+    #[default]
+    Deg0,
+    Deg90,
+    Deg180,
+    Deg270,
+}
+
+impl Rotation {
+    /// Whether width and height are swapped.
+    pub fn is_transposed(self) -> bool {
+        matches!(self, Rotation::Deg90 | Rotation::Deg270)
+    }
+
+    /// Map a logical point to a `size` framebuffer.
+    pub(crate) fn to_fbuf(self, p: Point, size: Size) -> Point {
+        let (width, height) = (size.width as i32, size.height as i32);
+        match self {
+            Rotation::Deg0 => p,
+            Rotation::Deg90 => Point::new(width - 1 - p.y, p.x),
+            Rotation::Deg180 => Point::new(width - 1 - p.x, height - 1 - p.y),
+            Rotation::Deg270 => Point::new(p.y, height - 1 - p.x),
+        }
+    }
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
+    /// Get a [`DrawTarget`] drawing into the framebuffer rotated by
+    /// `rotation`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{
+    ///     pixelcolor::BinaryColor,
+    ///     prelude::*,
+    ///     primitives::{Line, PrimitiveStyle},
+    /// };
+    /// use embedded_graphics_framebuf::{rotation::Rotation, FrameBuf};
+    ///
+    /// // A 240x320 portrait panel used in landscape orientation
+    /// let mut data = [BinaryColor::Off; 240 * 320];
+    /// let mut fbuf = FrameBuf::new(&mut data, 240, 320);
+    /// let mut landscape = fbuf.rotated(Rotation::Deg90);
+    /// assert_eq!(landscape.size(), Size::new(320, 240));
+    /// Line::new(Point::new(0, 0), Point::new(319, 0))
+    ///     .into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 1))
+    ///     .draw(&mut landscape)
+    ///     .unwrap();
+    /// assert_eq!(fbuf.get_color_at(Point::new(239, 319)), BinaryColor::On);
+    /// ```
+    pub fn rotated(&mut self, rotation: Rotation) -> RotatedView<'_, C, B> {
+        RotatedView {
+            fbuf: self,
+            rotation,
+        }
+    }
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
+    /// Mirror the stored pixels from left to right, in place.
+    ///
+    /// The [mask](crate::mask) is ignored, and the whole framebuffer is
+    /// marked dirty.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::Gray8, prelude::*};
+    /// use embedded_graphics_framebuf::FrameBuf;
+    ///
+    /// let mut data = [1, 2, 3, 4, 5, 6].map(Gray8::new);
+    /// let mut fbuf = FrameBuf::new(&mut data, 3, 2);
+    /// fbuf.flip_horizontal();
+    /// assert_eq!(data, [3, 2, 1, 6, 5, 4].map(Gray8::new));
+    /// ```
+    pub fn flip_horizontal(&mut self) {
+        let (width, height, stride) = (self.width, self.height, self.stride);
+        match self.data.as_contiguous_mut() {
+            Some(pixels) if self.layout == Layout::RowMajor => {
+                for y in 0..height {
+                    pixels[y * stride..y * stride + width].reverse();
+                }
+            }
+            _ => {
+                for y in 0..height as i32 {
+                    for x in 0..(width / 2) as i32 {
+                        self.swap_pixels(Point::new(x, y), Point::new(width as i32 - 1 - x, y));
+                    }
+                }
+            }
+        }
+        self.mark_all_dirty();
+    }
+
+    /// Mirror the stored pixels from top to bottom, in place.
+    ///
+    /// The [mask](crate::mask) is ignored, and the whole framebuffer is
+    /// marked dirty.
+    pub fn flip_vertical(&mut self) {
+        let (width, height, stride) = (self.width, self.height, self.stride);
+        match self.data.as_contiguous_mut() {
+            Some(pixels) if self.layout == Layout::RowMajor => {
+                for y in 0..height / 2 {
+                    let (top, bottom) = pixels.split_at_mut((height - 1 - y) * stride);
+                    top[y * stride..y * stride + width].swap_with_slice(&mut bottom[..width]);
+                }
+            }
+            _ => {
+                for y in 0..(height / 2) as i32 {
+                    for x in 0..width as i32 {
+                        self.swap_pixels(Point::new(x, y), Point::new(x, height as i32 - 1 - y));
+                    }
+                }
+            }
+        }
+        self.mark_all_dirty();
+    }
+
+    /// Rotate the stored pixels by 180°, in place.
+    ///
+    /// The [mask](crate::mask) is ignored, and the whole framebuffer is
+    /// marked dirty.
+    pub fn rotate_180(&mut self) {
+        self.flip_horizontal();
+        self.flip_vertical();
+    }
+
+    fn swap_pixels(&mut self, a: Point, b: Point) {
+        let (a, b) = (self.point_to_index(a), self.point_to_index(b));
+        let color = self.data.get(a);
+        self.data.set(a, self.data.get(b));
+        self.data.set(b, color);
+    }
+}
+
+/// A rotated view of a framebuffer, see [`FrameBuf::rotated`].
+pub struct RotatedView<'a, C: PixelColor, B: FrameBufferBackend<Color = C>> {
+    fbuf: &'a mut FrameBuf<C, B>,
+    rotation: Rotation,
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> OriginDimensions for RotatedView<'_, C, B> {
+    fn size(&self) -> Size {
+        let size = self.fbuf.size();
+        match self.rotation.is_transposed() {
+            true => Size::new(size.height, size.width),
+            false => size,
+        }
+    }
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> DrawTarget for RotatedView<'_, C, B> {
+    type Color = C;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        // The mapping is a bijection between the bounding boxes, so points
+        // outside of the view stay outside of the framebuffer.
+        let (rotation, size) = (self.rotation, self.fbuf.size());
+        self.fbuf.draw_iter(
+            pixels
+                .into_iter()
+                .map(|Pixel(p, color)| Pixel(rotation.to_fbuf(p, size), color)),
+        )
+    }
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> ReadableDrawTarget for RotatedView<'_, C, B> {
+    fn get_color_at(&self, p: Point) -> C {
+        self.fbuf
+            .get_color_at(self.rotation.to_fbuf(p, self.fbuf.size()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::{mock_display::MockDisplay, pixelcolor::BinaryColor};
+
+    fn draw_rotated(rotation: Rotation) -> MockDisplay<BinaryColor> {
+        let mut data = [BinaryColor::Off; 3 * 2];
+        let mut fbuf = FrameBuf::new(&mut data, 3, 2);
+        {
+            let mut view = fbuf.rotated(rotation);
+            view.draw_iter([
+                Pixel(Point::new(0, 0), BinaryColor::On),
+                Pixel(Point::new(1, 0), BinaryColor::On),
+                Pixel(Point::new(-1, 0), BinaryColor::On),
+                Pixel(Point::new(0, 3), BinaryColor::On),
+            ])
+            .unwrap();
+            assert_eq!(view.get_color_at(Point::new(1, 0)), BinaryColor::On);
+        }
+        let mut display = MockDisplay::new();
+        display.draw_iter(&fbuf).unwrap();
+        display
+    }
+
+    #[test]
+    fn remaps_coordinates() {
+        draw_rotated(Rotation::Deg0).assert_pattern(&["##.", "..."]);
+        draw_rotated(Rotation::Deg90).assert_pattern(&["..#", "..#"]);
+        draw_rotated(Rotation::Deg180).assert_pattern(&["...", ".##"]);
+        draw_rotated(Rotation::Deg270).assert_pattern(&["#..", "#.."]);
+    }
+
+    #[test]
+    fn flips_in_place() {
+        use embedded_graphics::{pixelcolor::Gray8, primitives::Rectangle};
+
+        // Flipped vertically in the end, i.e. rows or columns are reversed
+        for (layout, expected) in [
+            (Layout::RowMajor, [7, 8, 9, 4, 5, 6, 1, 2, 3]),
+            (Layout::ColumnMajor, [3, 2, 1, 6, 5, 4, 9, 8, 7]),
+        ] {
+            let mut data = [1, 2, 3, 4, 5, 6, 7, 8, 9].map(Gray8::new);
+            let mut fbuf = FrameBuf::new(&mut data[..], 3, 3);
+            fbuf.set_layout(layout);
+            fbuf.flip_vertical();
+            fbuf.flip_horizontal();
+            fbuf.rotate_180();
+            fbuf.take_dirty_area();
+            fbuf.flip_vertical();
+            assert_eq!(
+                fbuf.take_dirty_area(),
+                Some(Rectangle::new(Point::zero(), Size::new(3, 3)))
+            );
+            assert_eq!(data, expected.map(Gray8::new));
+        }
+
+        // Padding between the rows stays untouched
+        let mut data = [1, 2, 0, 3, 4].map(Gray8::new);
+        let mut fbuf = FrameBuf::new_with_stride(&mut data[..], 2, 2, 3);
+        fbuf.rotate_180();
+        assert_eq!(data, [4, 3, 0, 2, 1].map(Gray8::new));
+    }
+
+    #[test]
+    fn swaps_size() {
+        let mut data = [BinaryColor::Off; 3 * 2];
+        let mut fbuf = FrameBuf::new(&mut data, 3, 2);
+        assert_eq!(fbuf.rotated(Rotation::Deg270).size(), Size::new(2, 3));
+        assert_eq!(fbuf.rotated(Rotation::Deg180).size(), Size::new(3, 2));
+    }
+}
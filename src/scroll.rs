@@ -0,0 +1,214 @@
+//! Scrolling the contents of a framebuffer.
+//!
+//! [`FrameBuf::scroll`] moves the pixels of the framebuffer and fills the
+//! area they leave behind, e.g. for log consoles or plots moving to the left.
+//! [`FrameBuf::copy_within`] moves just a rectangle, e.g. for marquee text or
+//! moving windows. On
+//! [contiguous](crate::backends::FrameBufferBackend::as_contiguous) backends,
+//! rows are moved with [`slice::copy_within`].
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::Dimensions,
+    prelude::{PixelColor, Point, Size},
+    primitives::Rectangle,
+};
+
+use crate::{backends::FrameBufferBackend, blit::clip_blit, layout::Layout, FrameBuf};
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
+    /// Move the contents of the framebuffer by `dx` pixels to the right and
+    /// `dy` pixels down, and fill the exposed area with `fill`.
+    ///
+    /// Negative offsets move the contents to the left or up. Pixels hidden
+    /// by the [mask](crate::mask) are left unchanged, and the whole
+    /// framebuffer is marked dirty.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+    /// use embedded_graphics_framebuf::FrameBuf;
+    ///
+    /// let mut data = [BinaryColor::Off; 4 * 4];
+    /// let mut fbuf = FrameBuf::new(&mut data, 4, 4);
+    /// fbuf.set_color_at(Point::new(1, 1), BinaryColor::On);
+    /// fbuf.scroll(2, -1, BinaryColor::Off);
+    /// assert_eq!(fbuf.get_color_at(Point::new(3, 0)), BinaryColor::On);
+    /// assert_eq!(fbuf.get_color_at(Point::new(1, 1)), BinaryColor::Off);
+    /// ```
+    pub fn scroll(&mut self, dx: i32, dy: i32, fill: C) {
+        self.shift(dx, dy);
+        let (width, height) = (self.width as i32, self.height as i32);
+        let rows = dy.clamp(-height, height);
+        let columns = dx.clamp(-width, width);
+        let exposed_rows = Rectangle::new(
+            Point::new(0, if rows < 0 { height + rows } else { 0 }),
+            Size::new(width as u32, rows.unsigned_abs()),
+        );
+        let exposed_columns = Rectangle::new(
+            Point::new(if columns < 0 { width + columns } else { 0 }, 0),
+            Size::new(columns.unsigned_abs(), height as u32),
+        );
+        let Ok(()) = self.fill_solid(&exposed_rows, fill);
+        let Ok(()) = self.fill_solid(&exposed_columns, fill);
+    }
+
+    /// Move the contents of the framebuffer up by `n` rows.
+    ///
+    /// Unlike [`FrameBuf::scroll`], the bottom `n` rows are not filled and
+    /// keep their old contents, for when they are redrawn anyway (e.g. the
+    /// new line of a console).
+    pub fn scroll_up(&mut self, n: usize) {
+        self.shift(0, -(n.min(self.height) as i32));
+    }
+
+    /// Move the contents of the framebuffer left by `n` columns.
+    ///
+    /// Like [`FrameBuf::scroll_up`], the right `n` columns keep their old
+    /// contents.
+    pub fn scroll_left(&mut self, n: usize) {
+        self.shift(-(n.min(self.width) as i32), 0);
+    }
+
+    /// Copy the pixels in `src_rect` to the rectangle of the same size with
+    /// the top left corner at `dest`, within the framebuffer.
+    ///
+    /// The rectangles may overlap. Pixels outside of the framebuffer or
+    /// hidden by the [mask](crate::mask) are skipped, and the written area is
+    /// marked dirty.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::Gray8, prelude::*, primitives::Rectangle};
+    /// use embedded_graphics_framebuf::FrameBuf;
+    ///
+    /// let mut data = [1, 2, 3, 4, 5].map(Gray8::new);
+    /// let mut fbuf = FrameBuf::new(&mut data, 5, 1);
+    /// fbuf.copy_within(Rectangle::new(Point::zero(), Size::new(3, 1)), Point::new(1, 0));
+    /// assert_eq!(data, [1, 1, 2, 3, 5].map(Gray8::new));
+    /// ```
+    pub fn copy_within(&mut self, src_rect: Rectangle, dest: Point) {
+        let bounds = self.bounding_box();
+        let (src_top_left, area) = clip_blit(src_rect, bounds, dest, bounds);
+        let offset = area.top_left - src_top_left;
+        let (width, height) = (area.size.width as usize, area.size.height as i32);
+        // Copy in the direction of the move, so no pixel is overwritten
+        // before it is moved.
+        let rows = (0..height).map(|y| if offset.y > 0 { height - 1 - y } else { y });
+        let rows_contiguous = self.mask.is_none() && self.layout == Layout::RowMajor;
+        match self.data.as_contiguous_mut() {
+            Some(pixels) if rows_contiguous => {
+                for y in rows {
+                    let from = Layout::RowMajor.index(
+                        src_top_left + Point::new(0, y),
+                        self.stride,
+                        self.height,
+                    );
+                    let to = Layout::RowMajor.index(
+                        area.top_left + Point::new(0, y),
+                        self.stride,
+                        self.height,
+                    );
+                    pixels.copy_within(from..from + width, to);
+                }
+            }
+            _ => {
+                for y in rows {
+                    for x in 0..width as i32 {
+                        let x = if offset.x > 0 {
+                            width as i32 - 1 - x
+                        } else {
+                            x
+                        };
+                        let p = area.top_left + Point::new(x, y);
+                        if self.is_visible(p) {
+                            let color = self.get_color_at(src_top_left + Point::new(x, y));
+                            self.data.set(self.point_to_index(p), color);
+                        }
+                    }
+                }
+            }
+        }
+        self.mark_dirty(area);
+    }
+
+    /// Move the pixels by `dx` and `dy`, without filling the exposed area.
+    fn shift(&mut self, dx: i32, dy: i32) {
+        let bounds = self.bounding_box();
+        self.copy_within(bounds, Point::new(dx, dy));
+        self.mark_dirty(bounds);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::{
+        pixelcolor::{Gray8, GrayColor},
+        primitives::PointsIter,
+    };
+
+    fn scrolled(layout: Layout, dx: i32, dy: i32) -> [u8; 3 * 3] {
+        let mut data = [1, 2, 3, 4, 5, 6, 7, 8, 9].map(Gray8::new);
+        let mut fbuf = FrameBuf::new(&mut data, 3, 3);
+        fbuf.set_layout(layout);
+        let pixels = [1, 2, 3, 4, 5, 6, 7, 8, 9].map(Gray8::new);
+        for (p, color) in fbuf.bounding_box().points().zip(pixels) {
+            fbuf.set_color_at(p, color);
+        }
+        fbuf.take_dirty_area();
+        fbuf.scroll(dx, dy, Gray8::new(0));
+        assert_eq!(fbuf.take_dirty_area(), Some(fbuf.bounding_box()));
+        let mut result = [0; 3 * 3];
+        for (value, p) in result.iter_mut().zip(fbuf.bounding_box().points()) {
+            *value = fbuf.get_color_at(p).luma();
+        }
+        result
+    }
+
+    #[test]
+    fn scrolls() {
+        for layout in [Layout::RowMajor, Layout::ColumnMajor] {
+            assert_eq!(scrolled(layout, 1, 1), [0, 0, 0, 0, 1, 2, 0, 4, 5]);
+            assert_eq!(scrolled(layout, -1, -1), [5, 6, 0, 8, 9, 0, 0, 0, 0]);
+            assert_eq!(scrolled(layout, 0, -2), [7, 8, 9, 0, 0, 0, 0, 0, 0]);
+            assert_eq!(scrolled(layout, 3, 0), [0; 9]);
+            assert_eq!(scrolled(layout, 0, 0), [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        }
+    }
+
+    #[test]
+    fn scrolls_up_and_left_without_filling() {
+        let mut data = [1, 2, 3, 4, 5, 6].map(Gray8::new);
+        let mut fbuf = FrameBuf::new(&mut data, 3, 2);
+        fbuf.scroll_up(1);
+        fbuf.scroll_left(2);
+        assert_eq!(data, [6, 5, 6, 6, 5, 6].map(Gray8::new));
+    }
+
+    #[test]
+    fn copies_overlapping_rects() {
+        for layout in [Layout::RowMajor, Layout::ColumnMajor] {
+            let mut data = [1, 2, 3, 4, 5, 6, 7, 8, 9].map(Gray8::new);
+            let mut fbuf = FrameBuf::new(&mut data, 3, 3);
+            fbuf.set_layout(layout);
+            let pixels = [1, 2, 3, 4, 5, 6, 7, 8, 9].map(Gray8::new);
+            for (p, color) in fbuf.bounding_box().points().zip(pixels) {
+                fbuf.set_color_at(p, color);
+            }
+            fbuf.take_dirty_area();
+            let src = Rectangle::new(Point::new(1, 1), Size::new(2, 2));
+            fbuf.copy_within(src, Point::new(0, 0));
+            assert_eq!(
+                fbuf.take_dirty_area(),
+                Some(Rectangle::new(Point::zero(), Size::new(2, 2)))
+            );
+            fbuf.copy_within(src, Point::new(2, 2));
+            let mut result = [0; 3 * 3];
+            for (value, p) in result.iter_mut().zip(fbuf.bounding_box().points()) {
+                *value = fbuf.get_color_at(p).luma();
+            }
+            assert_eq!(result, [5, 6, 3, 8, 9, 6, 7, 8, 9]);
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! Framebuffers shared between tasks.
+//!
+//! With RTIC or embassy, the UI is often drawn in one task while another task
+//! (or the DMA complete interrupt) sends the frame to the display. A
+//! [`SharedFrameBuf`] guards the framebuffer with a
+//! [`critical_section::Mutex`], so both sides can access it through a shared
+//! reference, e.g. from a `static`, instead of through a `static mut`.
+//!
+//! Two locking granularities are available:
+//!
+//! - [`SharedFrameBuf::lock`] holds the lock for a whole closure, e.g. for
+//!   drawing a complete frame. The display task never sees a half-drawn
+//!   frame, but interrupts are disabled for the whole time on single core
+//!   MCUs.
+//! - [`SharedFrameBuf::copy_row`] holds the lock only for copying a single
+//!   row, so the display task can flush row by row with short critical
+//!   sections. Drawing may then happen between two rows, which shows up as
+//!   tearing if the frame is redrawn while it is being flushed.
+//!
+//! ```rust
+//! use embedded_graphics::{
+//!     pixelcolor::Rgb565,
+//!     prelude::*,
+//!     primitives::{Circle, PrimitiveStyle},
+//! };
+//! use embedded_graphics_framebuf::{shared::SharedFrameBuf, FrameBuf};
+//!
+//! let mut data = [Rgb565::BLACK; 64 * 32];
+//! // Usually in a `static`, e.g. a `StaticCell`, or an RTIC shared resource
+//! let shared = SharedFrameBuf::new(FrameBuf::new(&mut data, 64, 32));
+//!
+//! // UI task
+//! shared.lock(|fbuf| {
+//!     Circle::new(Point::new(16, 0), 32)
+//!         .into_styled(PrimitiveStyle::with_fill(Rgb565::RED))
+//!         .draw(fbuf)
+//! })
+//! .unwrap();
+//!
+//! // Display task
+//! let mut row = [Rgb565::BLACK; 64];
+//! if let Some(area) = shared.take_dirty_area() {
+//!     for y in area.rows() {
+//!         shared.copy_row(y as usize, &mut row);
+//!         // display.fill_contiguous(&row_area, row)?;
+//!     }
+//! }
+//! assert_eq!(row[32], Rgb565::RED);
+//! ```
+
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+use embedded_graphics::{
+    prelude::{PixelColor, Point},
+    primitives::Rectangle,
+};
+
+use crate::{backends::FrameBufferBackend, FrameBuf};
+
+/// A framebuffer guarded by a critical section, see the
+/// [module documentation](self).
+pub struct SharedFrameBuf<C, B: FrameBufferBackend<Color = C>> {
+    fbuf: Mutex<RefCell<FrameBuf<C, B>>>,
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> SharedFrameBuf<C, B> {
+    /// Share `fbuf`.
+    pub fn new(fbuf: FrameBuf<C, B>) -> Self {
+        Self {
+            fbuf: Mutex::new(RefCell::new(fbuf)),
+        }
+    }
+
+    /// Call `f` with the framebuffer, holding the lock until it returns.
+    ///
+    /// # Panic
+    /// Panics if called from within `f`.
+    pub fn lock<R>(&self, f: impl FnOnce(&mut FrameBuf<C, B>) -> R) -> R {
+        critical_section::with(|cs| f(&mut self.fbuf.borrow_ref_mut(cs)))
+    }
+
+    /// Copy the row `y` into `row`, holding the lock only while copying.
+    ///
+    /// # Panic
+    /// Panics if `y` is out of bounds or `row` is shorter than the width of
+    /// the framebuffer.
+    pub fn copy_row(&self, y: usize, row: &mut [C]) {
+        self.lock(|fbuf| {
+            assert!(y < fbuf.height(), "row {} out of bounds", y);
+            for (x, pixel) in row[..fbuf.width()].iter_mut().enumerate() {
+                *pixel = fbuf.get_color_at(Point::new(x as i32, y as i32));
+            }
+        })
+    }
+
+    /// Take the dirty area of the framebuffer, see
+    /// [`FrameBuf::take_dirty_area`].
+    pub fn take_dirty_area(&self) -> Option<Rectangle> {
+        self.lock(|fbuf| fbuf.take_dirty_area())
+    }
+
+    /// Return the framebuffer.
+    pub fn into_inner(self) -> FrameBuf<C, B> {
+        self.fbuf.into_inner().into_inner()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use embedded_graphics::{pixelcolor::Gray8, prelude::GrayColor};
+
+    #[test]
+    fn shares_between_threads() {
+        let mut data = [Gray8::BLACK; 3 * 2];
+        let shared = SharedFrameBuf::new(FrameBuf::new(&mut data, 3, 2));
+        shared.take_dirty_area();
+        std::thread::scope(|s| {
+            s.spawn(|| shared.lock(|fbuf| fbuf.set_color_at(Point::new(1, 1), Gray8::WHITE)));
+        });
+        let mut row = [Gray8::new(7); 4];
+        shared.copy_row(1, &mut row);
+        assert_eq!(row, [0, 255, 0, 7].map(Gray8::new));
+        assert!(shared.take_dirty_area().is_some());
+        assert_eq!(
+            shared.into_inner().get_color_at(Point::new(1, 1)),
+            Gray8::WHITE
+        );
+    }
+}
@@ -0,0 +1,431 @@
+//! Flushing parts of a framebuffer independently.
+//!
+//! Some displays consist of two independently driven halves, and some products
+//! have two displays showing one picture. [`FrameBuf::split`] divides the
+//! framebuffer into two [`FramePart`]s, whose pixels or bytes can be
+//! transmitted concurrently, e.g. by two SPI peripherals with DMA or from two
+//! async tasks. The parts only borrow the framebuffer immutably, so they can be
+//! used at the same time.
+//!
+//! For rendering, [`FrameBuf::split_at_row`] and [`FrameBuf::bands_mut`]
+//! divide the framebuffer into horizontal bands of mutable [`FrameBufView`]s
+//! instead. Each view is a draw target of its own, so e.g. the two cores of an
+//! ESP32 or RP2040 can rasterize one half of the frame each:
+//!
+//! ```rust
+//! use embedded_graphics::{
+//!     pixelcolor::Rgb565,
+//!     prelude::*,
+//!     primitives::{Circle, PrimitiveStyle},
+//! };
+//! use embedded_graphics_framebuf::FrameBuf;
+//!
+//! fn scene<D: DrawTarget<Color = Rgb565>>(target: &mut D) -> Result<(), D::Error> {
+//!     Circle::new(Point::new(40, 60), 160)
+//!         .into_styled(PrimitiveStyle::with_fill(Rgb565::RED))
+//!         .draw(target)
+//! }
+//!
+//! let mut data = [Rgb565::BLACK; 240 * 320];
+//! let mut fbuf = FrameBuf::new(&mut data, 240, 320);
+//! let (mut top, mut bottom) = fbuf.split_at_row(160);
+//! std::thread::scope(|s| {
+//!     s.spawn(|| scene(&mut top).unwrap());
+//!     scene(&mut bottom).unwrap();
+//! });
+//! assert_eq!(fbuf.get_color_at(Point::new(120, 140)), Rgb565::RED);
+//! assert_eq!(fbuf.get_color_at(Point::new(120, 200)), Rgb565::RED);
+//! ```
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::OriginDimensions,
+    pixelcolor::raw::ToBytes,
+    prelude::{PixelColor, Point, Size},
+    primitives::{PointsIter, Rectangle},
+    Pixel,
+};
+
+use crate::{
+    backends::{ContiguousBackend, EndianCorrection, FrameBufferBackend},
+    layout::Layout,
+    mask::Mask,
+    FrameBuf,
+};
+
+/// How to divide a framebuffer, see [`FrameBuf::split`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Split {
+    /// Into a top and a bottom part, the top part having the given number of
+    /// rows.
+    Rows(u32),
+    /// Into a left and a right part, the left part having the given number
+    /// of columns.
+    Columns(u32),
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
+    /// Divide the framebuffer into two parts.
+    ///
+    /// The position of the split is clamped to the framebuffer, so one of the
+    /// parts may be empty.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+    /// use embedded_graphics_framebuf::{backends::EndianCorrection, split::Split, FrameBuf};
+    ///
+    /// let mut data = [Rgb565::BLACK; 240 * 320];
+    /// let fbuf = FrameBuf::new(&mut data, 240, 320);
+    /// let [top, bottom] = fbuf.split(Split::Rows(160));
+    /// // Feed each stream to its own SPI bus.
+    /// let top_bytes = top.bytes(EndianCorrection::ToBigEndian);
+    /// let bottom_bytes = bottom.bytes(EndianCorrection::ToBigEndian);
+    /// assert_eq!(top_bytes.count(), 240 * 160 * 2);
+    /// assert_eq!(bottom_bytes.count(), 240 * 160 * 2);
+    /// ```
+    pub fn split(&self, split: Split) -> [FramePart<'_, C, B>; 2] {
+        let Size { width, height } = self.size();
+        let (first, second) = match split {
+            Split::Rows(rows) => {
+                let rows = rows.min(height);
+                (
+                    Rectangle::new(Point::zero(), Size::new(width, rows)),
+                    Rectangle::new(Point::new(0, rows as i32), Size::new(width, height - rows)),
+                )
+            }
+            Split::Columns(columns) => {
+                let columns = columns.min(width);
+                (
+                    Rectangle::new(Point::zero(), Size::new(columns, height)),
+                    Rectangle::new(
+                        Point::new(columns as i32, 0),
+                        Size::new(width - columns, height),
+                    ),
+                )
+            }
+        };
+        [
+            FramePart {
+                fbuf: self,
+                area: first,
+            },
+            FramePart {
+                fbuf: self,
+                area: second,
+            },
+        ]
+    }
+}
+
+impl<C: PixelColor, B: ContiguousBackend<Color = C>> FrameBuf<C, B> {
+    /// Divide the framebuffer into the rows above `y` and the rows from `y`
+    /// on, as mutable views. See the [module documentation](self).
+    ///
+    /// `y` is clamped to the framebuffer, so one of the views may be empty.
+    /// The whole framebuffer is marked dirty.
+    ///
+    /// # Panic
+    /// Panics if the [layout](crate::layout) is not [`Layout::RowMajor`].
+    pub fn split_at_row(&mut self, y: usize) -> (FrameBufView<'_, C>, FrameBufView<'_, C>) {
+        let y = y.min(self.height);
+        let [top, bottom] = self.bands_at([0, y]);
+        (top, bottom)
+    }
+
+    /// Divide the framebuffer into `N` horizontal bands of (almost) equal
+    /// height, as mutable views. See the [module documentation](self).
+    ///
+    /// The whole framebuffer is marked dirty.
+    ///
+    /// # Panic
+    /// Panics if the [layout](crate::layout) is not [`Layout::RowMajor`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+    /// use embedded_graphics_framebuf::FrameBuf;
+    ///
+    /// let mut data = [Rgb565::BLACK; 64 * 10];
+    /// let mut fbuf = FrameBuf::new(&mut data, 64, 10);
+    /// let bands: [_; 3] = fbuf.bands_mut();
+    /// let heights = bands.map(|band| band.area().size.height);
+    /// assert_eq!(heights, [3, 3, 4]);
+    /// ```
+    pub fn bands_mut<const N: usize>(&mut self) -> [FrameBufView<'_, C>; N] {
+        let height = self.height;
+        self.bands_at(core::array::from_fn(|band| band * height / N))
+    }
+
+    /// Split into bands starting at the ascending rows `tops`.
+    fn bands_at<const N: usize>(&mut self, tops: [usize; N]) -> [FrameBufView<'_, C>; N] {
+        assert_eq!(
+            self.layout,
+            Layout::RowMajor,
+            "FrameBuf bands are only contiguous in the row major layout"
+        );
+        self.mark_all_dirty();
+        let (width, height, stride, mask) = (self.width, self.height, self.stride, self.mask);
+        let mut rest = self.data.as_mut_slice();
+        // Start of `rest` in the backend
+        let mut offset = 0;
+        core::array::from_fn(|band| {
+            let top = tops[band];
+            let bottom = tops.get(band + 1).copied().unwrap_or(height);
+            let end = (bottom * stride).min(rest.len() + offset);
+            let (pixels, tail) = core::mem::take(&mut rest).split_at_mut(end - offset);
+            rest = tail;
+            offset = end;
+            FrameBufView {
+                pixels,
+                size: Size::new(width as u32, height as u32),
+                stride,
+                area: Rectangle::new(
+                    Point::new(0, top as i32),
+                    Size::new(width as u32, (bottom - top) as u32),
+                ),
+                mask,
+            }
+        })
+    }
+}
+
+/// A mutable view of a horizontal band of a framebuffer, see
+/// [`FrameBuf::split_at_row`] and [`FrameBuf::bands_mut`].
+///
+/// The view is a [`DrawTarget`] with the coordinates and size of the whole
+/// framebuffer, drawing only into its band, so the same drawing code can be
+/// run for every band. The [mask](crate::mask) of the framebuffer applies.
+pub struct FrameBufView<'a, C> {
+    /// The pixels from the start of the band
+    pixels: &'a mut [C],
+    size: Size,
+    stride: usize,
+    area: Rectangle,
+    mask: Option<Mask>,
+}
+
+impl<C: PixelColor> FrameBufView<'_, C> {
+    /// The band in framebuffer coordinates. Drawing can be skipped for
+    /// anything outside of it.
+    pub fn area(&self) -> Rectangle {
+        self.area
+    }
+
+    fn index(&self, p: Point) -> usize {
+        (p.y - self.area.top_left.y) as usize * self.stride + p.x as usize
+    }
+}
+
+impl<C: PixelColor> OriginDimensions for FrameBufView<'_, C> {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl<C: PixelColor> DrawTarget for FrameBufView<'_, C> {
+    type Color = C;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(p, color) in pixels {
+            if self.area.contains(p) && self.mask.is_none_or(|mask| mask.contains(p)) {
+                let index = self.index(p);
+                self.pixels[index] = color;
+            }
+        }
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&self.area);
+        if self.mask.is_some() {
+            return self.draw_iter(area.points().map(|p| Pixel(p, color)));
+        }
+        for y in area.rows() {
+            let start = self.index(Point::new(area.top_left.x, y));
+            self.pixels[start..start + area.size.width as usize].fill(color);
+        }
+        Ok(())
+    }
+}
+
+/// A part of a framebuffer, see [`FrameBuf::split`].
+pub struct FramePart<'a, C, B: FrameBufferBackend<Color = C>> {
+    fbuf: &'a FrameBuf<C, B>,
+    area: Rectangle,
+}
+
+impl<'a, C: PixelColor, B: FrameBufferBackend<Color = C>> FramePart<'a, C, B> {
+    /// The area of the part in framebuffer coordinates.
+    pub fn area(&self) -> Rectangle {
+        self.area
+    }
+
+    /// Iterate over the pixels of the part row by row.
+    ///
+    /// The coordinates are relative to the top left corner of the part, as
+    /// each part is usually shown by its own controller.
+    pub fn pixels(&self) -> PartPixels<'a, C, B> {
+        PartPixels {
+            fbuf: self.fbuf,
+            area: self.area,
+            index: 0,
+        }
+    }
+
+    /// Iterate over the bytes of the pixels of the part row by row, in the
+    /// requested byte order.
+    pub fn bytes(&self, endian: EndianCorrection) -> PartBytes<'a, C, B>
+    where
+        C: ToBytes,
+    {
+        PartBytes {
+            pixels: self.pixels(),
+            endian,
+            bytes: None,
+            index: 0,
+        }
+    }
+}
+
+/// Iterator over the pixels of a [`FramePart`].
+pub struct PartPixels<'a, C, B: FrameBufferBackend<Color = C>> {
+    fbuf: &'a FrameBuf<C, B>,
+    area: Rectangle,
+    index: u32,
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> Iterator for PartPixels<'_, C, B> {
+    type Item = Pixel<C>;
+
+    fn next(&mut self) -> Option<Pixel<C>> {
+        let Size { width, height } = self.area.size;
+        if self.index >= width * height {
+            return None;
+        }
+        let p = Point::new((self.index % width) as i32, (self.index / width) as i32);
+        self.index += 1;
+        Some(Pixel(p, self.fbuf.get_color_at(self.area.top_left + p)))
+    }
+}
+
+/// Iterator over the bytes of a [`FramePart`].
+pub struct PartBytes<'a, C: ToBytes, B: FrameBufferBackend<Color = C>> {
+    pixels: PartPixels<'a, C, B>,
+    endian: EndianCorrection,
+    /// Bytes of the current pixel
+    bytes: Option<C::Bytes>,
+    index: usize,
+}
+
+impl<C, B> Iterator for PartBytes<'_, C, B>
+where
+    C: PixelColor + ToBytes,
+    C::Bytes: AsRef<[u8]>,
+    B: FrameBufferBackend<Color = C>,
+{
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        loop {
+            if let Some(&byte) = self.bytes.as_ref().and_then(|b| b.as_ref().get(self.index)) {
+                self.index += 1;
+                return Some(byte);
+            }
+            let Pixel(_, color) = self.pixels.next()?;
+            self.bytes = Some(match self.endian {
+                EndianCorrection::ToBigEndian => color.to_be_bytes(),
+                EndianCorrection::ToLittleEndian => color.to_le_bytes(),
+            });
+            self.index = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use embedded_graphics::{
+        geometry::Dimensions,
+        pixelcolor::{Gray8, Rgb565},
+    };
+    use std::vec::Vec;
+
+    #[test]
+    fn splits_rows_and_columns() {
+        let mut data = [0, 1, 2, 3, 4, 5].map(Gray8::new);
+        let fbuf = FrameBuf::new(&mut data, 3, 2);
+
+        let [left, right] = fbuf.split(Split::Columns(1));
+        let left: Vec<_> = left.pixels().map(|p| p.1).collect();
+        let right: Vec<_> = right.pixels().collect();
+        assert_eq!(left, [0, 3].map(Gray8::new));
+        assert_eq!(right[2], Pixel(Point::new(0, 1), Gray8::new(4)));
+
+        let [top, bottom] = fbuf.split(Split::Rows(5));
+        assert_eq!(top.area(), Rectangle::new(Point::zero(), Size::new(3, 2)));
+        assert_eq!(bottom.pixels().count(), 0);
+    }
+
+    #[test]
+    fn splits_into_mutable_bands() {
+        let mut data = [Gray8::new(0); 2 * 5];
+        let mut fbuf = FrameBuf::new(&mut data, 2, 5);
+        fbuf.take_dirty_area();
+        {
+            let (mut top, mut bottom) = fbuf.split_at_row(2);
+            assert_eq!(top.size(), Size::new(2, 5));
+            assert_eq!(
+                bottom.area(),
+                Rectangle::new(Point::new(0, 2), Size::new(2, 3))
+            );
+            top.clear(Gray8::new(1)).unwrap();
+            bottom
+                .draw_iter([
+                    Pixel(Point::new(0, 1), Gray8::new(9)),
+                    Pixel(Point::new(1, 2), Gray8::new(2)),
+                ])
+                .unwrap();
+        }
+        assert_eq!(fbuf.dirty_area(), Some(fbuf.bounding_box()));
+
+        let [_, mut middle, _] = fbuf.bands_mut();
+        assert_eq!(middle.area().rows(), 1..3);
+        middle
+            .fill_solid(
+                &Rectangle::new(Point::new(1, 0), Size::new(5, 5)),
+                Gray8::new(3),
+            )
+            .unwrap();
+        assert_eq!(data, [1, 1, 1, 3, 0, 3, 0, 0, 0, 0].map(Gray8::new));
+    }
+
+    #[test]
+    fn splits_strided_backends() {
+        let mut data = [Gray8::new(0); 4 * 2 + 2];
+        let mut fbuf = FrameBuf::new_with_stride(&mut data, 2, 3, 4);
+        let (_, mut bottom) = fbuf.split_at_row(9);
+        assert!(bottom.area().is_zero_sized());
+        bottom.clear(Gray8::new(1)).unwrap();
+        let [_, mut last] = fbuf.bands_mut();
+        last.clear(Gray8::new(1)).unwrap();
+        assert_eq!(data, [0, 0, 0, 0, 1, 1, 0, 0, 1, 1].map(Gray8::new));
+    }
+
+    #[test]
+    fn streams_bytes() {
+        let mut data = [Rgb565::new(1, 2, 3), Rgb565::new(4, 5, 6)];
+        let fbuf = FrameBuf::new(&mut data, 1, 2);
+        let [top, bottom] = fbuf.split(Split::Rows(1));
+        let top: Vec<_> = top.bytes(EndianCorrection::ToBigEndian).collect();
+        let bottom: Vec<_> = bottom.bytes(EndianCorrection::ToLittleEndian).collect();
+        assert_eq!(top, Rgb565::new(1, 2, 3).to_be_bytes());
+        assert_eq!(bottom, Rgb565::new(4, 5, 6).to_le_bytes());
+    }
+}
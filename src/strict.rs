@@ -0,0 +1,131 @@
+//! Drawing with out of bounds checks.
+//!
+//! The [`DrawTarget`] implementation of [`FrameBuf`] silently discards pixels
+//! outside of the framebuffer, like the one of most displays. This also hides
+//! layout bugs, e.g. a widget placed partly off screen. [`FrameBuf::strict`]
+//! returns a draw target which reports such pixels as an [`OutOfBounds`]
+//! error instead.
+//!
+//! With the `log` feature, the offending coordinates are also logged as a
+//! warning, which helps to find the culprit when the error is ignored.
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Dimensions, OriginDimensions},
+    prelude::{PixelColor, Point, Size},
+    Pixel,
+};
+
+use crate::{backends::FrameBufferBackend, FrameBuf, OutOfBounds, ReadableDrawTarget};
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> FrameBuf<C, B> {
+    /// Get a [`DrawTarget`] which fails on pixels outside of the framebuffer.
+    ///
+    /// All pixels inside of the framebuffer are drawn, the error reports the
+    /// first pixel outside of it.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::{
+    ///     pixelcolor::BinaryColor,
+    ///     prelude::*,
+    ///     primitives::{Circle, PrimitiveStyle},
+    /// };
+    /// use embedded_graphics_framebuf::{FrameBuf, OutOfBounds};
+    ///
+    /// let mut data = [BinaryColor::Off; 12 * 11];
+    /// let mut fbuf = FrameBuf::new(&mut data, 12, 11);
+    /// let result = Circle::new(Point::new(8, 0), 5)
+    ///     .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
+    ///     .draw(&mut fbuf.strict());
+    /// assert_eq!(result, Err(OutOfBounds(Point::new(12, 1))));
+    /// ```
+    pub fn strict(&mut self) -> Strict<'_, C, B> {
+        Strict { fbuf: self }
+    }
+}
+
+/// A draw target failing on pixels outside of the framebuffer, see
+/// [`FrameBuf::strict`].
+pub struct Strict<'a, C: PixelColor, B: FrameBufferBackend<Color = C>> {
+    fbuf: &'a mut FrameBuf<C, B>,
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> OriginDimensions for Strict<'_, C, B> {
+    fn size(&self) -> Size {
+        self.fbuf.size()
+    }
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> DrawTarget for Strict<'_, C, B> {
+    type Color = C;
+    type Error = OutOfBounds;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let area = self.fbuf.bounding_box();
+        let mut first = None;
+        #[cfg(feature = "log")]
+        let mut count = 0usize;
+        for Pixel(p, color) in pixels {
+            if !area.contains(p) {
+                first = first.or(Some(p));
+                #[cfg(feature = "log")]
+                {
+                    count += 1;
+                }
+            } else if self.fbuf.is_visible(p) {
+                self.fbuf.set_color_at(p, color);
+            }
+        }
+        match first {
+            Some(p) => {
+                #[cfg(feature = "log")]
+                log::warn!(
+                    "{} pixels drawn out of the bounds of a {}x{} framebuffer, the first at ({}, {})",
+                    count,
+                    self.fbuf.width(),
+                    self.fbuf.height(),
+                    p.x,
+                    p.y
+                );
+                Err(OutOfBounds(p))
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> ReadableDrawTarget for Strict<'_, C, B> {
+    fn get_color_at(&self, p: Point) -> C {
+        self.fbuf.get_color_at(p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::pixelcolor::BinaryColor;
+
+    #[test]
+    fn reports_first_out_of_bounds_pixel() {
+        let mut data = [BinaryColor::Off; 2 * 2];
+        let mut fbuf = FrameBuf::new(&mut data, 2, 2);
+        let mut strict = fbuf.strict();
+        assert_eq!(
+            strict.draw_iter([Pixel(Point::new(1, 1), BinaryColor::On)]),
+            Ok(())
+        );
+        assert_eq!(
+            strict.draw_iter([
+                Pixel(Point::new(-1, 0), BinaryColor::On),
+                Pixel(Point::new(0, 1), BinaryColor::On),
+                Pixel(Point::new(2, 0), BinaryColor::On),
+            ]),
+            Err(OutOfBounds(Point::new(-1, 0)))
+        );
+        assert_eq!(data, [false, false, true, true].map(BinaryColor::from));
+    }
+}
@@ -0,0 +1,211 @@
+//! Rendering in horizontal strips.
+//!
+//! A 480x320 [`Rgb565`](embedded_graphics::pixelcolor::Rgb565) framebuffer
+//! takes 300 KiB, more than many MCUs have. A [`StripRenderer`] only keeps a
+//! strip of a few rows in RAM: it draws the whole picture once per strip,
+//! with everything outside of the strip clipped away, and sends each strip to
+//! the display before rendering the next one. This trades CPU time for RAM.
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::OriginDimensions,
+    prelude::{PixelColor, Point, Size},
+    primitives::Rectangle,
+    Pixel,
+};
+
+use crate::{
+    backends::FrameBufferBackend,
+    split::{FramePart, Split},
+    FrameBuf,
+};
+
+/// Renders a display in strips using a small framebuffer.
+///
+/// # Example
+/// ```rust
+/// use embedded_graphics::{
+///     pixelcolor::Rgb565,
+///     prelude::*,
+///     primitives::{Circle, PrimitiveStyle},
+/// };
+/// use embedded_graphics_framebuf::strip::StripRenderer;
+///
+/// // 16 rows of a 480x320 display take 15 KiB instead of 300 KiB.
+/// let mut strip = [Rgb565::BLACK; 480 * 16];
+/// let mut renderer = StripRenderer::new(&mut strip, Size::new(480, 320), 16);
+/// let mut strips = 0;
+/// renderer
+///     .render(
+///         |target| {
+///             target.clear(Rgb565::BLACK)?;
+///             Circle::new(Point::new(100, 100), 120)
+///                 .into_styled(PrimitiveStyle::with_fill(Rgb565::RED))
+///                 .draw(target)
+///         },
+///         |area, part| {
+///             // Send `part.pixels()` (or `part.bytes(..)`) to `area` of the display.
+///             assert_eq!(area.size, Size::new(480, 16));
+///             strips += 1;
+///             Ok::<(), ()>(())
+///         },
+///     )
+///     .unwrap();
+/// assert_eq!(strips, 20);
+/// ```
+pub struct StripRenderer<C, B: FrameBufferBackend<Color = C>> {
+    strip: FrameBuf<C, B>,
+    display: Size,
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> StripRenderer<C, B> {
+    /// Create a renderer for a display of the size `display`, using `strip`
+    /// as storage for `rows` rows.
+    ///
+    /// # Panic
+    /// Panics if the size of `strip` doesn't match `rows` rows of the display
+    /// width, or `rows` is 0.
+    pub fn new(strip: B, display: Size, rows: usize) -> Self {
+        assert!(rows > 0, "StripRenderer needs at least one row");
+        Self {
+            strip: FrameBuf::new(strip, display.width as usize, rows),
+            display,
+        }
+    }
+
+    /// Render the display strip by strip, from top to bottom.
+    ///
+    /// For every strip, `draw` is called with a [`Strip`] draw target having
+    /// the size of the whole display, so it draws the whole picture. Then
+    /// `flush` is called with the area of the strip on the display and its
+    /// pixels. The last strip may have fewer rows. An error of `flush` aborts
+    /// rendering.
+    pub fn render<D, F, E>(&mut self, mut draw: D, mut flush: F) -> Result<(), E>
+    where
+        D: FnMut(&mut Strip<'_, C, B>) -> Result<(), core::convert::Infallible>,
+        F: FnMut(Rectangle, FramePart<'_, C, B>) -> Result<(), E>,
+    {
+        let rows = self.strip.height() as u32;
+        let mut top = 0;
+        while top < self.display.height {
+            let height = rows.min(self.display.height - top);
+            let mut strip = Strip {
+                fbuf: &mut self.strip,
+                top: top as i32,
+                display: self.display,
+            };
+            let Ok(()) = draw(&mut strip);
+            let [part, _] = self.strip.split(Split::Rows(height));
+            let area = Rectangle::new(
+                Point::new(0, top as i32),
+                Size::new(self.display.width, height),
+            );
+            flush(area, part)?;
+            top += height;
+        }
+        Ok(())
+    }
+
+    /// Return the storage of the strip.
+    pub fn into_inner(self) -> B {
+        self.strip.into_inner()
+    }
+}
+
+/// A draw target for the whole display drawing into one strip, see
+/// [`StripRenderer::render`].
+pub struct Strip<'a, C: PixelColor, B: FrameBufferBackend<Color = C>> {
+    fbuf: &'a mut FrameBuf<C, B>,
+    /// The first row of the strip on the display
+    top: i32,
+    display: Size,
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> Strip<'_, C, B> {
+    /// The area of the display covered by this strip. Drawing can be skipped
+    /// for anything outside of it.
+    pub fn area(&self) -> Rectangle {
+        Rectangle::new(Point::new(0, self.top), self.fbuf.size())
+    }
+
+    fn to_strip(&self, area: &Rectangle) -> Rectangle {
+        Rectangle::new(area.top_left - Point::new(0, self.top), area.size)
+    }
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> OriginDimensions for Strip<'_, C, B> {
+    fn size(&self) -> Size {
+        self.display
+    }
+}
+
+impl<C: PixelColor, B: FrameBufferBackend<Color = C>> DrawTarget for Strip<'_, C, B> {
+    type Color = C;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let offset = Point::new(0, self.top);
+        self.fbuf.draw_iter(
+            pixels
+                .into_iter()
+                .map(|Pixel(p, color)| Pixel(p - offset, color)),
+        )
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let area = self.to_strip(area);
+        self.fbuf.fill_contiguous(&area, colors)
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = self.to_strip(area);
+        self.fbuf.fill_solid(&area, color)
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.fbuf.clear(color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use embedded_graphics::{mock_display::MockDisplay, pixelcolor::BinaryColor};
+
+    #[test]
+    fn renders_strips() {
+        let mut strip = [BinaryColor::Off; 3 * 2];
+        let mut renderer = StripRenderer::new(&mut strip, Size::new(3, 5), 2);
+        let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+        let mut areas = std::vec::Vec::new();
+        renderer
+            .render(
+                |target| {
+                    target.clear(BinaryColor::Off)?;
+                    target.fill_solid(
+                        &Rectangle::new(Point::new(1, 1), Size::new(2, 3)),
+                        BinaryColor::On,
+                    )?;
+                    target.draw_iter([Pixel(Point::new(0, 4), BinaryColor::On)])
+                },
+                |area, part| {
+                    areas.push(area);
+                    display.draw_iter(
+                        part.pixels()
+                            .map(|Pixel(p, color)| Pixel(p + area.top_left, color)),
+                    )
+                },
+            )
+            .unwrap();
+        display.assert_pattern(&["...", ".##", ".##", ".##", "#.."]);
+        assert_eq!(areas[2], Rectangle::new(Point::new(0, 4), Size::new(3, 1)));
+    }
+}
@@ -0,0 +1,211 @@
+//! Animated transitions between two frames.
+//!
+//! Page changes in menus or smartwatch UIs look smoother when the old page
+//! fades, is wiped or slides out instead of being replaced at once. A
+//! [`Transition`] renders the frames in between two framebuffers holding the
+//! old and the new page, either into a destination framebuffer or as a
+//! stream of pixels for the display.
+//!
+//! ```rust
+//! use embedded_graphics::{mock_display::MockDisplay, pixelcolor::Rgb565, prelude::*};
+//! use embedded_graphics_framebuf::{
+//!     transition::{Effect, Transition},
+//!     FrameBuf,
+//! };
+//!
+//! let mut old_data = [Rgb565::BLACK; 12 * 11];
+//! let old = FrameBuf::new(&mut old_data, 12, 11);
+//! let mut new_data = [Rgb565::WHITE; 12 * 11];
+//! let new = FrameBuf::new(&mut new_data, 12, 11);
+//!
+//! let transition = Transition::new(&old, &new, Effect::Wipe);
+//! for step in 1..=4u32 {
+//!     let mut display: MockDisplay<Rgb565> = MockDisplay::new();
+//!     display.draw_iter(transition.pixels((step * 255 / 4) as u8)).unwrap();
+//!     assert_eq!(display.get_pixel(Point::new(1, 0)), Some(Rgb565::WHITE));
+//!     // delay.delay_ms(16);
+//! }
+//! ```
+
+use embedded_graphics::{
+    geometry::Dimensions,
+    pixelcolor::Rgb888,
+    prelude::{PixelColor, Point},
+    primitives::{rectangle::Points, PointsIter},
+    Pixel,
+};
+
+use crate::{backends::FrameBufferBackend, blend::blend, FrameBuf};
+
+/// The animation of a [`Transition`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Effect {
+    /// The old frame fades into the new one
+    Crossfade,
+    /// The new frame is uncovered from the left to the right
+    Wipe,
+    /// The new frame slides in from the right, pushing the old one out
+    Slide,
+}
+
+/// A transition from one frame to another, see the
+/// [module documentation](self).
+pub struct Transition<'a, C, A: FrameBufferBackend<Color = C>, B: FrameBufferBackend<Color = C>> {
+    from: &'a FrameBuf<C, A>,
+    to: &'a FrameBuf<C, B>,
+    effect: Effect,
+}
+
+impl<'a, C, A, B> Transition<'a, C, A, B>
+where
+    C: PixelColor + Into<Rgb888> + From<Rgb888>,
+    A: FrameBufferBackend<Color = C>,
+    B: FrameBufferBackend<Color = C>,
+{
+    /// Create a transition from the frame `from` to the frame `to`.
+    ///
+    /// # Panic
+    /// Panics if the frames differ in size.
+    pub fn new(from: &'a FrameBuf<C, A>, to: &'a FrameBuf<C, B>, effect: Effect) -> Self {
+        assert_eq!(
+            from.size(),
+            to.size(),
+            "Transition frames have different sizes"
+        );
+        Self { from, to, effect }
+    }
+
+    /// The color at `p` when the transition has progressed to `progress`,
+    /// from `0` (the old frame) to `255` (the new frame).
+    pub fn color_at(&self, p: Point, progress: u8) -> C {
+        let width = self.from.width() as i32;
+        let offset = width * progress as i32 / 255;
+        match self.effect {
+            Effect::Crossfade => blend(
+                self.from.get_color_at(p).into(),
+                self.to.get_color_at(p).into(),
+                progress,
+            )
+            .into(),
+            Effect::Wipe if p.x < offset => self.to.get_color_at(p),
+            Effect::Wipe => self.from.get_color_at(p),
+            Effect::Slide if p.x + offset < width => {
+                self.from.get_color_at(Point::new(p.x + offset, p.y))
+            }
+            Effect::Slide => self.to.get_color_at(Point::new(p.x + offset - width, p.y)),
+        }
+    }
+
+    /// Render the frame at `progress` into `dest`, which is marked dirty.
+    /// Pixels hidden by the [mask](crate::mask) of `dest` are left untouched.
+    ///
+    /// # Panic
+    /// Panics if `dest` is smaller than the frames.
+    pub fn render_into<D>(&self, progress: u8, dest: &mut FrameBuf<C, D>)
+    where
+        D: FrameBufferBackend<Color = C>,
+    {
+        for p in self.from.bounding_box().points() {
+            if dest.is_visible(p) {
+                dest.set_color_at(p, self.color_at(p, progress));
+            }
+        }
+        dest.mark_dirty(self.from.bounding_box());
+    }
+
+    /// Iterate over the pixels of the frame at `progress`, in display
+    /// coordinates (offset by the origin of the new frame).
+    pub fn pixels(&self, progress: u8) -> TransitionPixels<'_, 'a, C, A, B> {
+        TransitionPixels {
+            transition: self,
+            progress,
+            points: self.from.bounding_box().points(),
+        }
+    }
+}
+
+/// Iterator over the pixels of a frame of a [`Transition`], see
+/// [`Transition::pixels`].
+pub struct TransitionPixels<
+    'a,
+    'b,
+    C,
+    A: FrameBufferBackend<Color = C>,
+    B: FrameBufferBackend<Color = C>,
+> {
+    transition: &'a Transition<'b, C, A, B>,
+    progress: u8,
+    points: Points,
+}
+
+impl<C, A, B> Iterator for TransitionPixels<'_, '_, C, A, B>
+where
+    C: PixelColor + Into<Rgb888> + From<Rgb888>,
+    A: FrameBufferBackend<Color = C>,
+    B: FrameBufferBackend<Color = C>,
+{
+    type Item = Pixel<C>;
+
+    fn next(&mut self) -> Option<Pixel<C>> {
+        let p = self.points.next()?;
+        Some(Pixel(
+            p + self.transition.to.origin(),
+            self.transition.color_at(p, self.progress),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::mask::Mask;
+    use embedded_graphics::pixelcolor::Gray8;
+    use embedded_graphics::prelude::GrayColor;
+    use std::vec::Vec;
+
+    fn frame(effect: Effect, progress: u8) -> Vec<u8> {
+        let mut from_data = [0, 10, 20, 30].map(Gray8::new);
+        let from = FrameBuf::new(&mut from_data, 4, 1);
+        let mut to_data = [100, 110, 120, 130].map(Gray8::new);
+        let to = FrameBuf::new_with_origin(&mut to_data, 4, 1, Point::new(5, 0));
+        let transition = Transition::new(&from, &to, effect);
+        let pixels: Vec<_> = transition.pixels(progress).collect();
+        assert_eq!(pixels[0].0, Point::new(5, 0));
+
+        let mut data = [Gray8::BLACK; 4];
+        transition.render_into(progress, &mut FrameBuf::new(&mut data, 4, 1));
+        assert_eq!(
+            data.to_vec(),
+            pixels.iter().map(|p| p.1).collect::<Vec<_>>()
+        );
+        data.map(|c| c.luma()).to_vec()
+    }
+
+    #[test]
+    fn transitions() {
+        for effect in [Effect::Crossfade, Effect::Wipe, Effect::Slide] {
+            assert_eq!(frame(effect, 0), [0, 10, 20, 30]);
+            assert_eq!(frame(effect, 255), [100, 110, 120, 130]);
+        }
+        assert_eq!(frame(Effect::Crossfade, 51), [20, 30, 40, 50]);
+        assert_eq!(frame(Effect::Wipe, 128), [100, 110, 20, 30]);
+        assert_eq!(frame(Effect::Slide, 64), [10, 20, 30, 100]);
+    }
+
+    #[test]
+    fn respects_dest_mask() {
+        let mut from_data = [0, 10, 20, 30].map(Gray8::new);
+        let from = FrameBuf::new(&mut from_data, 4, 1);
+        let mut to_data = [100, 110, 120, 130].map(Gray8::new);
+        let to = FrameBuf::new(&mut to_data, 4, 1);
+        let transition = Transition::new(&from, &to, Effect::Wipe);
+
+        let mut data = [Gray8::BLACK; 4];
+        let mut dest = FrameBuf::new(&mut data, 4, 1);
+        dest.set_mask(Some(Mask::Custom(|p| p.x != 2)));
+        transition.render_into(255, &mut dest);
+        assert_eq!(data, [100, 110, 0, 130].map(Gray8::new));
+    }
+}